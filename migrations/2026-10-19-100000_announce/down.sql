@@ -0,0 +1,3 @@
+-- This file should undo anything in `up.sql`
+ALTER TABLE guild_settings DROP COLUMN announce_delete_previous;
+ALTER TABLE guild_settings DROP COLUMN announce_channel;
@@ -0,0 +1,3 @@
+-- Your SQL goes here
+ALTER TABLE guild_settings ADD COLUMN announce_channel BIGINT;
+ALTER TABLE guild_settings ADD COLUMN announce_delete_previous BOOLEAN NOT NULL DEFAULT false;
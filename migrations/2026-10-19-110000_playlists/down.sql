@@ -0,0 +1,3 @@
+-- This file should undo anything in `up.sql`
+DROP TABLE IF EXISTS playlist_tracks;
+DROP TABLE IF EXISTS playlists;
@@ -0,0 +1,12 @@
+-- Your SQL goes here
+CREATE TABLE IF NOT EXISTS plays (
+    id INTEGER PRIMARY KEY AUTOINCREMENT NOT NULL,
+    guild_id BIGINT NOT NULL,
+    webpage_url TEXT NOT NULL,
+    title TEXT,
+    requester BIGINT,
+    started_at TIMESTAMP NOT NULL,
+    listened DOUBLE NOT NULL DEFAULT 0.0,
+    skipped BOOLEAN NOT NULL DEFAULT false
+);
+CREATE INDEX IF NOT EXISTS plays_guild_id_started_at ON plays (guild_id, started_at);
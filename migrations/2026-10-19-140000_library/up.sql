@@ -0,0 +1,14 @@
+-- Your SQL goes here
+CREATE TABLE IF NOT EXISTS library_tracks (
+    id INTEGER PRIMARY KEY AUTOINCREMENT NOT NULL,
+    path TEXT UNIQUE NOT NULL,
+    modified BIGINT NOT NULL,
+    size BIGINT NOT NULL,
+    title TEXT,
+    artist TEXT,
+    album TEXT,
+    duration DOUBLE,
+    chapters TEXT NOT NULL DEFAULT '[]',
+    art BLOB,
+    art_type TEXT
+);
@@ -0,0 +1,7 @@
+-- Your SQL goes here
+CREATE TABLE IF NOT EXISTS track_cache (
+    cache_key TEXT PRIMARY KEY NOT NULL,
+    track TEXT NOT NULL,
+    cached_at TIMESTAMP NOT NULL
+);
+CREATE INDEX IF NOT EXISTS track_cache_cached_at ON track_cache (cached_at);
@@ -0,0 +1,2 @@
+-- Your SQL goes here
+ALTER TABLE guild_settings ADD COLUMN effects INTEGER NOT NULL DEFAULT 0;
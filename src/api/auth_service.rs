@@ -1,3 +1,29 @@
-pub fn auth() {
-    
-}
\ No newline at end of file
+use actix_web_httpauth::extractors::basic::BasicAuth;
+use argon2::{password_hash::{rand_core::OsRng, PasswordHash, PasswordHasher, PasswordVerifier, SaltString}, Argon2};
+use diesel::{r2d2::{ConnectionManager, Pool}, ExpressionMethods, OptionalExtension, QueryDsl, RunQueryDsl, SelectableHelper, SqliteConnection};
+
+use crate::models::UserDB;
+
+pub fn hash_password(password: &str) -> Option<String> {
+    let salt = SaltString::generate(&mut OsRng);
+    Argon2::default().hash_password(password.as_bytes(), &salt).ok().map(|hash| hash.to_string())
+}
+
+pub fn auth(pool: &Pool<ConnectionManager<SqliteConnection>>, credentials: &BasicAuth) -> Option<u64> {
+    let user_id = credentials.user_id().parse::<u64>().ok()?;
+    let password = credentials.password()?;
+    let mut conn = pool.get().ok()?;
+
+    use crate::schema::users;
+    let user = users::table
+        .filter(users::id.eq(user_id as i64))
+        .select(UserDB::as_select())
+        .first(&mut conn)
+        .optional()
+        .ok()??;
+    let hash = PasswordHash::new(&user.password).ok()?;
+    match Argon2::default().verify_password(password.as_bytes(), &hash) {
+        Ok(_) => Some(user_id),
+        Err(_) => None
+    }
+}
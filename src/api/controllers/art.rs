@@ -0,0 +1,16 @@
+use actix_web::{get, web, HttpResponse, Responder, Result, Scope};
+
+use crate::bot::utils::ffprobe::get_cached_art;
+
+#[get("/{key}")]
+async fn art(path: web::Path<String>) -> Result<impl Responder> {
+    match get_cached_art(&path.into_inner()) {
+        Some((data, media_type)) => Ok(HttpResponse::Ok().content_type(media_type).body(data)),
+        None => Ok(HttpResponse::NotFound().body("Art not found"))
+    }
+}
+
+pub fn api_scope() -> Scope {
+    web::scope("/art")
+        .service(art)
+}
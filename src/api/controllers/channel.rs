@@ -2,13 +2,13 @@ use std::sync::Arc;
 
 use actix_web::{get, web, HttpResponse, Responder, Result, Scope};
 use diesel::{r2d2::{ConnectionManager, Pool}, SqliteConnection};
-use serenity::{all::{ChannelId, ChannelType, GuildId}, client::Cache};
+use serenity::{all::{ChannelId, ChannelType, GuildId}, client::Cache, http::Http};
 use songbird::{driver::Bitrate, CoreEvent, Event, Songbird, TrackEvent};
 
-use crate::bot::{events::TrackEndNotifierWeb, utils::player::{initialize_guild_player_web, PlayerDataType}};
+use crate::bot::{events::TrackEndNotifierWeb, utils::{player::{initialize_guild_player_web, PlayerDataType}, presence::Presence}};
 
 #[get("/join/{channel_id}")] // <- define path parameters
-async fn join(path: web::Path<(u64, u64)>, songbird: web::Data<Arc<Songbird>>, cache: web::Data<Arc<Cache>>, player: web::Data<PlayerDataType>, pool: web::Data<Pool<ConnectionManager<SqliteConnection>>>) -> Result<impl Responder> {
+async fn join(path: web::Path<(u64, u64)>, songbird: web::Data<Arc<Songbird>>, cache: web::Data<Arc<Cache>>, http: web::Data<Arc<Http>>, presence: web::Data<Presence>, player: web::Data<PlayerDataType>, pool: web::Data<Pool<ConnectionManager<SqliteConnection>>>) -> Result<impl Responder> {
     let (guild_id, channel_id) = path.into_inner();
     let guild_id = GuildId::from(guild_id);
 
@@ -45,7 +45,11 @@ async fn join(path: web::Path<(u64, u64)>, songbird: web::Data<Arc<Songbird>>, c
                     TrackEndNotifierWeb {
                         guild_id: guild_id,
                         songbird: songbird.clone().into_inner(),
-                        player: player.clone().into_inner()
+                        player: player.clone().into_inner(),
+                        cache: cache.get_ref().clone(),
+                        http: http.get_ref().clone(),
+                        presence: presence.get_ref().clone(),
+                        pool: pool.get_ref().clone()
                     }
                 );
                 handler.add_global_event(
@@ -53,7 +57,11 @@ async fn join(path: web::Path<(u64, u64)>, songbird: web::Data<Arc<Songbird>>, c
                     TrackEndNotifierWeb {
                         guild_id: guild_id,
                         songbird: songbird.into_inner(),
-                        player: player.into_inner()
+                        player: player.into_inner(),
+                        cache: cache.get_ref().clone(),
+                        http: http.get_ref().clone(),
+                        presence: presence.get_ref().clone(),
+                        pool: pool.get_ref().clone()
                     }
                 );
                 Ok(HttpResponse::Ok().body("ok"))
@@ -0,0 +1,27 @@
+use std::sync::Arc;
+
+use actix_web::{get, web, HttpResponse, Responder, Result, Scope};
+use diesel::{r2d2::{ConnectionManager, Pool}, SqliteConnection};
+use serenity::{all::GuildId, client::Cache};
+
+use crate::bot::utils::{jobs::get_jobs, player::{initialize_guild_player_web, PlayerDataType}};
+
+#[get("")]
+async fn list(path: web::Path<u64>, cache: web::Data<Arc<Cache>>, player_data: web::Data<PlayerDataType>, pool: web::Data<Pool<ConnectionManager<SqliteConnection>>>) -> Result<impl Responder> {
+    let guild_id = GuildId::from(path.into_inner());
+    let guild_id = match cache.guild(guild_id) {
+        Some(guild) => guild.id,
+        None => return Ok(HttpResponse::NotFound().body("Guild not found"))
+    };
+
+    initialize_guild_player_web(player_data.as_ref(), pool.get(), guild_id).await;
+
+    let player = player_data.read().await;
+    let player = player.get(&guild_id.get()).unwrap();
+    Ok(HttpResponse::Ok().json(get_jobs(player).await))
+}
+
+pub fn api_scope() -> Scope {
+    web::scope("/{guild_id}/jobs")
+        .service(list)
+}
@@ -0,0 +1,18 @@
+use actix_web::{get, web, HttpResponse, Responder, Result, Scope};
+use diesel::{r2d2::{ConnectionManager, Pool}, SqliteConnection};
+
+use crate::bot::utils::library::get_library_art;
+
+#[get("/{id}/art")]
+async fn art(path: web::Path<i32>, pool: web::Data<Pool<ConnectionManager<SqliteConnection>>>) -> Result<impl Responder> {
+    match pool.get().map(|mut conn| get_library_art(&mut conn, path.into_inner())) {
+        Ok(Ok(Some((data, media_type)))) => Ok(HttpResponse::Ok().content_type(media_type).body(data)),
+        Ok(Ok(None)) => Ok(HttpResponse::NotFound().body("Art not found")),
+        _ => Ok(HttpResponse::InternalServerError().body("Failed to get art"))
+    }
+}
+
+pub fn api_scope() -> Scope {
+    web::scope("/library")
+        .service(art)
+}
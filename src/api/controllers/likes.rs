@@ -0,0 +1,64 @@
+use actix_web::{get, web, HttpResponse, Responder, Result, Scope};
+use actix_web_httpauth::extractors::basic::BasicAuth;
+use chrono::NaiveDateTime;
+use diesel::{r2d2::{ConnectionManager, Pool}, SqliteConnection};
+use serde::{Deserialize, Serialize};
+
+use crate::api::auth_service::auth;
+use crate::bot::utils::{likes::get_likes_page, track::Track};
+
+#[derive(Deserialize)]
+struct LikesQuery {
+    page: Option<i64>,
+}
+
+#[derive(Serialize)]
+struct LikeResponse {
+    id: i32,
+    webpage_url: String,
+    title: Option<String>,
+    duration: Option<f64>,
+    created_at: NaiveDateTime,
+    track: Option<Track>,
+}
+
+#[derive(Serialize)]
+struct LikesResponse {
+    page: i64,
+    total: i64,
+    likes: Vec<LikeResponse>,
+}
+
+#[get("")]
+async fn get(credentials: BasicAuth, query: web::Query<LikesQuery>, pool: web::Data<Pool<ConnectionManager<SqliteConnection>>>) -> Result<impl Responder> {
+    let user = match auth(pool.as_ref(), &credentials) {
+        Some(user) => user,
+        None => return Ok(HttpResponse::Unauthorized().body("Invalid credentials"))
+    };
+    let page = query.page.unwrap_or(0).max(0);
+
+    let mut conn = match pool.get() {
+        Ok(conn) => conn,
+        Err(_) => return Ok(HttpResponse::InternalServerError().body("Failed to connect to the database"))
+    };
+    match get_likes_page(&mut conn, user, page) {
+        Ok((liked, total)) => Ok(HttpResponse::Ok().json(LikesResponse {
+            page,
+            total,
+            likes: liked.into_iter().map(|like| LikeResponse {
+                id: like.id,
+                track: serde_json::from_str(&like.track).ok(),
+                webpage_url: like.webpage_url,
+                title: like.title,
+                duration: like.duration,
+                created_at: like.created_at,
+            }).collect()
+        })),
+        Err(_) => Ok(HttpResponse::InternalServerError().body("Failed to get likes"))
+    }
+}
+
+pub fn api_scope() -> Scope {
+    web::scope("/likes")
+        .service(get)
+}
@@ -1,14 +1,30 @@
 use actix_web::{web, Scope};
 
+mod art;
 mod channel;
+mod jobs;
+mod library;
+mod likes;
 mod playlist;
+mod podcast;
+mod radio;
 mod seek;
+mod sources;
 mod state;
+mod stats;
 
 pub fn api_scope() -> Scope {
     web::scope("/api")
+        .service(likes::api_scope())
+        .service(library::api_scope())
+        .service(art::api_scope())
+        .service(sources::api_scope())
         .service(channel::api_scope())
+        .service(jobs::api_scope())
         .service(playlist::api_scope())
+        .service(podcast::api_scope())
+        .service(radio::api_scope())
         .service(seek::api_scope())
         .service(state::api_scope())
+        .service(stats::api_scope())
 }
\ No newline at end of file
@@ -1,14 +1,18 @@
 use std::sync::Arc;
 
+use actix_multipart::Multipart;
 use actix_web::{get, post, web, HttpResponse, Responder, Result, Scope};
 use diesel::{r2d2::{ConnectionManager, Pool}, SqliteConnection};
-use serenity::{all::GuildId, client::Cache};
+use serde::Deserialize;
+use serenity::{all::GuildId, client::Cache, futures::{FutureExt, StreamExt}, http::Http};
 use songbird::Songbird;
 
-use crate::bot::utils::{player::{initialize_guild_player_web, PlayerDataType, PlayerState, Position}, track::{Track, WebTrack}};
+use crate::bot::utils::{presence::Presence, player::{enqueue_track_web, initialize_guild_player_web, PlayerDataType}, jobs::spawn_import_job, queue_file::{export_queue, import_queue, QueueFormat, IMPORT_LIMIT}, track::{Track, WebTrack}};
+
+const IMPORT_MAX_SIZE: usize = 1024 * 1024;
 
 #[post("/")] // <- define path parameters
-async fn add(path: web::Path<u64>, trak: web::Json<WebTrack>, songbird: web::Data<Arc<Songbird>>, cache: web::Data<Arc<Cache>>, player_data: web::Data<PlayerDataType>, pool: web::Data<Pool<ConnectionManager<SqliteConnection>>>) -> Result<impl Responder> {
+async fn add(path: web::Path<u64>, trak: web::Json<WebTrack>, songbird: web::Data<Arc<Songbird>>, cache: web::Data<Arc<Cache>>, http: web::Data<Arc<Http>>, presence: web::Data<Presence>, player_data: web::Data<PlayerDataType>, pool: web::Data<Pool<ConnectionManager<SqliteConnection>>>) -> Result<impl Responder> {
     let guild_id = path.into_inner();
     let guild_id = GuildId::from(guild_id);
 
@@ -37,39 +41,119 @@ async fn add(path: web::Path<u64>, trak: web::Json<WebTrack>, songbird: web::Dat
     *last_id+=1;
 
     let track = Track::from_web(trak.into_inner(), last_id.clone());
-    let mut player_playlist = player.playlist.write().await;
-    let mut state = player.state.write().await;
+    enqueue_track_web(player_data.clone().into_inner(), &songbird, cache.get_ref().clone(), http.get_ref().clone(), presence.get_ref().clone(), player, track.clone()).await;
+    Ok(HttpResponse::Ok().json(track))
+}
 
-    match *state {
-        PlayerState::Ended => {
-            let mut last_updated_position = player.position.write().await;
-            player_playlist.current = Some(track.clone());
-            *state = PlayerState::Playing;
-            *last_updated_position = Position::default();
+#[derive(Deserialize)]
+struct ExportQuery {
+    format: Option<String>,
+}
 
-            let mut child = track.get_child_web(player_data.clone().into_inner(), &guild_id.get(), 0.0).await.unwrap();
-            let stdin = child.stdin.take().unwrap();
-            let data = songbird::input::Input::from(songbird::input::ChildContainer::from(child));
+#[get("/export")]
+async fn export(path: web::Path<u64>, query: web::Query<ExportQuery>, cache: web::Data<Arc<Cache>>, player_data: web::Data<PlayerDataType>, pool: web::Data<Pool<ConnectionManager<SqliteConnection>>>) -> Result<impl Responder> {
+    let guild_id = GuildId::from(path.into_inner());
+    let guild_id = match cache.guild(guild_id) {
+        Some(guild) => guild.id,
+        None => return Ok(HttpResponse::NotFound().body("Guild not found"))
+    };
+    let format = match &query.format {
+        Some(format) => match QueueFormat::from_str(format) {
+            Some(format) => format,
+            None => return Ok(HttpResponse::BadRequest().body("Unknown format"))
+        },
+        None => QueueFormat::M3u
+    };
 
-            if let Some(handler_lock) = songbird.get(guild_id) {
-                let mut handler = handler_lock.lock().await;
-                let mut ffmpeg = player.ffmpeg.write().await;
-                let mut player_handler = player.player.write().await;
+    initialize_guild_player_web(player_data.as_ref(), pool.get(), guild_id).await;
 
-                let _ = ffmpeg.insert(stdin);
+    let player = player_data.read().await;
+    let player = player.get(&guild_id.get()).unwrap();
+    let tracks: Vec<Track> = {
+        let player_playlist = player.playlist.read().await;
+        player_playlist.current.iter().chain(player_playlist.tracks.iter()).cloned().collect()
+    };
+
+    Ok(HttpResponse::Ok()
+        .content_type(format.content_type())
+        .insert_header(("Content-Disposition", format!("attachment; filename=\"queue.{}\"", format.extension())))
+        .body(export_queue(&tracks, format)))
+}
+
+#[post("/import")]
+async fn import(path: web::Path<u64>, mut payload: Multipart, songbird: web::Data<Arc<Songbird>>, cache: web::Data<Arc<Cache>>, http: web::Data<Arc<Http>>, presence: web::Data<Presence>, player_data: web::Data<PlayerDataType>, pool: web::Data<Pool<ConnectionManager<SqliteConnection>>>) -> Result<impl Responder> {
+    let guild_id = GuildId::from(path.into_inner());
+    let guild_id = match cache.guild(guild_id) {
+        Some(guild) => guild.id,
+        None => return Ok(HttpResponse::NotFound().body("Guild not found"))
+    };
 
-                let handle = handler.play_only_input(data);
-                let _ = player_handler.insert(handle);
+    let mut data = Vec::new();
+    let mut filename = None;
+    while let Some(Ok(mut field)) = payload.next().await {
+        filename = field.content_disposition().get_filename().map(|filename| filename.to_string());
+        while let Some(Ok(chunk)) = field.next().await {
+            if data.len() + chunk.len() > IMPORT_MAX_SIZE {
+                return Ok(HttpResponse::PayloadTooLarge().body("File is too large"));
             }
-        },
-        _ => {
-            player_playlist.tracks.push_back(track.clone());
+            data.extend_from_slice(&chunk);
+        }
+        if !data.is_empty() {
+            break;
         }
     }
-    Ok(HttpResponse::Ok().json(track))
+    let data = match String::from_utf8(data) {
+        Ok(data) => data,
+        Err(_) => return Ok(HttpResponse::BadRequest().body("File is not valid UTF-8"))
+    };
+    let format = filename.as_deref().and_then(QueueFormat::from_filename).unwrap_or(QueueFormat::detect(&data));
+    let entries = match import_queue(&data, format) {
+        Ok(entries) => entries,
+        Err(error) => return Ok(HttpResponse::BadRequest().body(error))
+    };
+
+    initialize_guild_player_web(player_data.as_ref(), pool.get(), guild_id).await;
+
+    match songbird.get(guild_id) {
+        Some(handler) => {
+            let handler = handler.lock().await;
+            match handler.current_channel() {
+                Some(_) => {},
+                None => return Ok(HttpResponse::Conflict().body("Join a channel first"))
+            }
+        },
+        None => return Ok(HttpResponse::Conflict().body("Join a channel first"))
+    };
+
+    let player = player_data.read().await.get(&guild_id.get()).unwrap().clone();
+    let enqueue_player = player.clone();
+    let player_data = player_data.clone().into_inner();
+    let songbird = songbird.get_ref().clone();
+    let cache = cache.get_ref().clone();
+    let http = http.get_ref().clone();
+    let presence = presence.get_ref().clone();
+
+    let entries: Vec<_> = entries.into_iter().take(IMPORT_LIMIT).collect();
+    let job = spawn_import_job(player, filename.unwrap_or("import".to_string()), None, entries,
+        move |track| {
+            let player_data = player_data.clone();
+            let songbird = songbird.clone();
+            let cache = cache.clone();
+            let http = http.clone();
+            let presence = presence.clone();
+            let player = enqueue_player.clone();
+            async move {
+                enqueue_track_web(player_data, &songbird, cache, http, presence, &player, track).await;
+            }.boxed()
+        },
+        |_| async {}.boxed()
+    ).await;
+    Ok(HttpResponse::Accepted().json(job.status()))
 }
 
 pub fn api_scope() -> Scope {
     web::scope("/{guild_id}/playlist")
         .service(add)
+        .service(export)
+        .service(import)
 }
\ No newline at end of file
@@ -0,0 +1,30 @@
+use std::sync::Arc;
+
+use actix_web::{get, web, HttpResponse, Responder, Result, Scope};
+use diesel::{r2d2::{ConnectionManager, Pool}, SqliteConnection};
+use serenity::{all::GuildId, client::Cache};
+
+use crate::bot::utils::radio::list_stations;
+
+#[get("")]
+async fn list(path: web::Path<u64>, cache: web::Data<Arc<Cache>>, pool: web::Data<Pool<ConnectionManager<SqliteConnection>>>) -> Result<impl Responder> {
+    let guild_id = GuildId::from(path.into_inner());
+    let guild_id = match cache.guild(guild_id) {
+        Some(guild) => guild.id,
+        None => return Ok(HttpResponse::NotFound().body("Guild not found"))
+    };
+
+    let mut conn = match pool.get() {
+        Ok(conn) => conn,
+        Err(_) => return Ok(HttpResponse::InternalServerError().body("Failed to connect to the database"))
+    };
+    match list_stations(&mut conn, guild_id.get()) {
+        Ok(stations) => Ok(HttpResponse::Ok().json(stations)),
+        Err(_) => Ok(HttpResponse::InternalServerError().body("Failed to get radio stations"))
+    }
+}
+
+pub fn api_scope() -> Scope {
+    web::scope("/{guild_id}/radio")
+        .service(list)
+}
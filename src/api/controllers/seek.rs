@@ -25,7 +25,7 @@ async fn get(path: web::Path<u64>, cache: web::Data<Arc<Cache>>, player_data: we
     match player_handler {
         Some(player_handler) => match player_handler.get_info().await {
             Ok(data) => {
-                let res = position.last_position + Duration::from_secs_f64((data.position - position.last_player_position).as_secs_f64() * settings.speed + 0.2);
+                let res = position.last_position + Duration::from_secs_f64((data.position - position.last_player_position).as_secs_f64() * settings.get_rate() + 0.2);
                 Ok(HttpResponse::Ok().json(res))
             },
             _ => Ok(HttpResponse::InternalServerError().body("Error getting player info"))
@@ -49,6 +49,10 @@ async fn seek(path: web::Path<(u64, f64)>, cache: web::Data<Arc<Cache>>, player_
     let player = player_data.read().await;
     let player = player.get(&guild_id.get()).unwrap();
 
+    if player.playlist.read().await.current.as_ref().map(|track| track.is_live).unwrap_or(false) {
+        return Ok(HttpResponse::BadRequest().body("Cannot seek in a live stream"));
+    }
+
     let mut state = player.state.write().await;
     let player_handler = player.player.write().await.clone();
 
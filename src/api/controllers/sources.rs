@@ -0,0 +1,97 @@
+use actix_web::{get, web, HttpResponse, Responder, Result, Scope};
+use actix_web_httpauth::extractors::basic::BasicAuth;
+use diesel::{r2d2::{ConnectionManager, Pool}, SqliteConnection};
+use serde::{Deserialize, Serialize};
+
+use crate::api::auth_service::auth;
+use crate::bot::utils::{parser::ParsedDataType, resolver::{source_registry, DEFAULT_SEARCH_SOURCE}};
+
+#[derive(Serialize)]
+struct SourceResponse {
+    name: &'static str,
+    display_name: &'static str,
+    default: bool,
+}
+
+#[derive(Deserialize)]
+struct ResolveQuery {
+    url: String,
+}
+
+#[derive(Deserialize)]
+struct SearchQuery {
+    query: String,
+    source: Option<String>,
+}
+
+#[get("")]
+async fn list() -> Result<impl Responder> {
+    let sources: Vec<SourceResponse> = source_registry().searchable()
+        .map(|resolver| SourceResponse {
+            name: resolver.name(),
+            display_name: resolver.display_name(),
+            default: resolver.name() == DEFAULT_SEARCH_SOURCE,
+        })
+        .collect();
+    Ok(HttpResponse::Ok().json(sources))
+}
+
+#[get("/resolve")]
+async fn resolve(credentials: BasicAuth, query: web::Query<ResolveQuery>, pool: web::Data<Pool<ConnectionManager<SqliteConnection>>>) -> Result<impl Responder> {
+    if auth(pool.as_ref(), &credentials).is_none() {
+        return Ok(HttpResponse::Unauthorized().body("Invalid credentials"));
+    }
+    if !source_registry().is_url(&query.url) {
+        return Ok(HttpResponse::BadRequest().body("Unsupported URL"));
+    }
+    match source_registry().resolve(&query.url, 0).await {
+        ParsedDataType::Track(track) => Ok(HttpResponse::Ok().json(serde_json::json!({
+            "track": track
+        }))),
+        ParsedDataType::YtDlPlaylist((playlist, track)) => Ok(HttpResponse::Ok().json(serde_json::json!({
+            "track": track,
+            "playlist": {
+                "title": playlist.title,
+                "webpage_url": playlist.webpage_url,
+                "tracks": playlist.tracks
+            }
+        }))),
+        ParsedDataType::VkPlaylist((playlist, track)) => Ok(HttpResponse::Ok().json(serde_json::json!({
+            "track": track,
+            "playlist": {
+                "title": playlist.title,
+                "webpage_url": playlist.webpage_url,
+                "tracks": playlist.tracks.iter().map(|track| track.webpage_url.clone()).collect::<Vec<String>>(),
+                "failed": playlist.failed
+            }
+        }))),
+        ParsedDataType::Podcast((feed, track)) => Ok(HttpResponse::Ok().json(serde_json::json!({
+            "track": track,
+            "podcast": feed
+        }))),
+        ParsedDataType::None => Ok(HttpResponse::NotFound().body("Nothing was found"))
+    }
+}
+
+#[get("/search")]
+async fn search(credentials: BasicAuth, query: web::Query<SearchQuery>, pool: web::Data<Pool<ConnectionManager<SqliteConnection>>>) -> Result<impl Responder> {
+    if auth(pool.as_ref(), &credentials).is_none() {
+        return Ok(HttpResponse::Unauthorized().body("Invalid credentials"));
+    }
+    if let Some(source) = &query.source {
+        if !source_registry().get(source).map(|resolver| resolver.searchable()).unwrap_or(false) {
+            return Ok(HttpResponse::BadRequest().body("Unknown source"));
+        }
+    }
+    match source_registry().search(&query.query, query.source.as_deref(), 0).await {
+        Some(track) => Ok(HttpResponse::Ok().json(track)),
+        None => Ok(HttpResponse::NotFound().body("Nothing was found"))
+    }
+}
+
+pub fn api_scope() -> Scope {
+    web::scope("/sources")
+        .service(list)
+        .service(resolve)
+        .service(search)
+}
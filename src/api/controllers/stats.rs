@@ -0,0 +1,104 @@
+use std::sync::Arc;
+
+use actix_web::{get, web, HttpResponse, Responder, Result, Scope};
+use diesel::{r2d2::{ConnectionManager, Pool}, SqliteConnection};
+use serde::{Deserialize, Serialize};
+use serenity::{all::GuildId, client::Cache};
+
+use crate::bot::utils::stats::{get_most_skipped, get_top_requesters, get_top_tracks, get_total, RequesterStats, StatsPeriod, TotalStats, TrackStats};
+
+#[derive(Deserialize)]
+struct StatsQuery {
+    period: Option<String>,
+    limit: Option<i64>,
+}
+
+#[derive(Serialize)]
+struct StatsResponse {
+    total: TotalStats,
+    top_tracks: Vec<TrackStats>,
+    top_requesters: Vec<RequesterStats>,
+    most_skipped: Vec<TrackStats>,
+}
+
+fn get_params(query: &StatsQuery) -> (StatsPeriod, i64) {
+    (
+        StatsPeriod::from_str(query.period.as_deref().unwrap_or("week")),
+        query.limit.unwrap_or(10).clamp(1, 100)
+    )
+}
+
+#[get("")]
+async fn get(path: web::Path<u64>, query: web::Query<StatsQuery>, cache: web::Data<Arc<Cache>>, pool: web::Data<Pool<ConnectionManager<SqliteConnection>>>) -> Result<impl Responder> {
+    let guild_id = match cache.guild(GuildId::from(path.into_inner())) {
+        Some(guild) => guild.id.get(),
+        None => return Ok(HttpResponse::NotFound().body("Guild not found"))
+    };
+    let (period, limit) = get_params(&query);
+    let mut conn = match pool.get() {
+        Ok(conn) => conn,
+        Err(_) => return Ok(HttpResponse::InternalServerError().body("Failed to connect to the database"))
+    };
+
+    match (
+        get_total(&mut conn, guild_id, period),
+        get_top_tracks(&mut conn, guild_id, period, limit),
+        get_top_requesters(&mut conn, guild_id, period, limit),
+        get_most_skipped(&mut conn, guild_id, period, limit)
+    ) {
+        (Ok(total), Ok(top_tracks), Ok(top_requesters), Ok(most_skipped)) => Ok(HttpResponse::Ok().json(StatsResponse {
+            total,
+            top_tracks,
+            top_requesters,
+            most_skipped
+        })),
+        _ => Ok(HttpResponse::InternalServerError().body("Failed to get stats"))
+    }
+}
+
+#[get("/tracks")]
+async fn tracks(path: web::Path<u64>, query: web::Query<StatsQuery>, cache: web::Data<Arc<Cache>>, pool: web::Data<Pool<ConnectionManager<SqliteConnection>>>) -> Result<impl Responder> {
+    let guild_id = match cache.guild(GuildId::from(path.into_inner())) {
+        Some(guild) => guild.id.get(),
+        None => return Ok(HttpResponse::NotFound().body("Guild not found"))
+    };
+    let (period, limit) = get_params(&query);
+    match pool.get().map(|mut conn| get_top_tracks(&mut conn, guild_id, period, limit)) {
+        Ok(Ok(stats)) => Ok(HttpResponse::Ok().json(stats)),
+        _ => Ok(HttpResponse::InternalServerError().body("Failed to get stats"))
+    }
+}
+
+#[get("/requesters")]
+async fn requesters(path: web::Path<u64>, query: web::Query<StatsQuery>, cache: web::Data<Arc<Cache>>, pool: web::Data<Pool<ConnectionManager<SqliteConnection>>>) -> Result<impl Responder> {
+    let guild_id = match cache.guild(GuildId::from(path.into_inner())) {
+        Some(guild) => guild.id.get(),
+        None => return Ok(HttpResponse::NotFound().body("Guild not found"))
+    };
+    let (period, limit) = get_params(&query);
+    match pool.get().map(|mut conn| get_top_requesters(&mut conn, guild_id, period, limit)) {
+        Ok(Ok(stats)) => Ok(HttpResponse::Ok().json(stats)),
+        _ => Ok(HttpResponse::InternalServerError().body("Failed to get stats"))
+    }
+}
+
+#[get("/skipped")]
+async fn skipped(path: web::Path<u64>, query: web::Query<StatsQuery>, cache: web::Data<Arc<Cache>>, pool: web::Data<Pool<ConnectionManager<SqliteConnection>>>) -> Result<impl Responder> {
+    let guild_id = match cache.guild(GuildId::from(path.into_inner())) {
+        Some(guild) => guild.id.get(),
+        None => return Ok(HttpResponse::NotFound().body("Guild not found"))
+    };
+    let (period, limit) = get_params(&query);
+    match pool.get().map(|mut conn| get_most_skipped(&mut conn, guild_id, period, limit)) {
+        Ok(Ok(stats)) => Ok(HttpResponse::Ok().json(stats)),
+        _ => Ok(HttpResponse::InternalServerError().body("Failed to get stats"))
+    }
+}
+
+pub fn api_scope() -> Scope {
+    web::scope("/{guild_id}/stats")
+        .service(get)
+        .service(tracks)
+        .service(requesters)
+        .service(skipped)
+}
@@ -0,0 +1,24 @@
+use serenity::{client::Context, all::CommandInteraction, builder::{CreateInteractionResponse, CreateAutocompleteResponse}};
+
+use crate::bot::utils::{library::{get_library_track_str, search_library}, player::PlayerDataBase, check_msg};
+
+pub async fn run(ctx: Context, command: CommandInteraction) {
+    let input = command.data.autocomplete().unwrap();
+
+    let pool = {
+        let data_read = ctx.data.read().await;
+        let conn = data_read.get::<PlayerDataBase>().expect("Expected PlayerDataBase in TypeMap.").clone();
+        conn.get()
+    };
+    let found = match pool {
+        Ok(mut conn) => search_library(&mut conn, input.value, 25).unwrap_or_default(),
+        Err(_) => Vec::new()
+    };
+
+    let mut choices = CreateAutocompleteResponse::new();
+    found.iter().for_each(|track| {
+        choices = choices.clone().add_string_choice(get_library_track_str(track), track.id.to_string());
+    });
+    let builder = CreateInteractionResponse::Autocomplete(choices);
+    check_msg(command.create_response(&ctx.http, builder).await);
+}
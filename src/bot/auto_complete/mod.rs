@@ -1,2 +1,7 @@
 pub mod skip;
-pub mod r#move;
\ No newline at end of file
+pub mod r#move;
+pub mod playlist;
+pub mod library;
+pub mod play;
+pub mod radio;
+pub mod podcast;
\ No newline at end of file
@@ -14,6 +14,12 @@ pub async fn run(ctx: Context, command: CommandInteraction) {
     let player_playlist = player.playlist.read().await;
     let track = player_playlist.current.clone().unwrap();
 
+    if track.is_live {
+        let builder = CreateInteractionResponse::Autocomplete(CreateAutocompleteResponse::new());
+        check_msg(command.create_response(&ctx.http, builder).await);
+        return ;
+    }
+
     if position.value.is_empty() {
         let mut choices = CreateAutocompleteResponse::new();
         track.chapters.iter().take(25)
@@ -0,0 +1,146 @@
+use std::collections::{HashMap, HashSet};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+use serenity::{client::Context, all::CommandInteraction, builder::{CreateInteractionResponse, CreateAutocompleteResponse}};
+use tokio::time::{sleep, timeout};
+
+use crate::bot::utils::{player::PlayerDataBase, resolver::source_registry, saved_playlist::search_playlist_tracks, stats::get_recent_tracks, check_msg};
+
+const MAX_CHOICES: usize = 25;
+const CHOICE_LIMIT: usize = 100;
+const HISTORY_LIMIT: i64 = 100;
+const SEARCH_MIN_LENGTH: usize = 3;
+const SEARCH_RESULTS: usize = 5;
+const SEARCH_CACHE_TTL: Duration = Duration::from_secs(600);
+const SEARCH_CACHE_LIMIT: usize = 256;
+const DEBOUNCE: Duration = Duration::from_millis(350);
+const DEADLINE: Duration = Duration::from_millis(2500);
+
+type Suggestions = Vec<(String, String)>;
+
+static LAST_REQUEST: AtomicU64 = AtomicU64::new(0);
+
+fn pending_requests() -> &'static Mutex<HashMap<u64, u64>> {
+    static PENDING: OnceLock<Mutex<HashMap<u64, u64>>> = OnceLock::new();
+    PENDING.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+fn search_cache() -> &'static Mutex<HashMap<String, (Instant, Suggestions)>> {
+    static CACHE: OnceLock<Mutex<HashMap<String, (Instant, Suggestions)>>> = OnceLock::new();
+    CACHE.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+fn get_cached_search(key: &str) -> Option<Suggestions> {
+    let cache = search_cache().lock().unwrap();
+    cache.get(key)
+        .filter(|(cached_at, _)| cached_at.elapsed() < SEARCH_CACHE_TTL)
+        .map(|(_, suggestions)| suggestions.clone())
+}
+
+fn cache_search(key: String, suggestions: Suggestions) {
+    let mut cache = search_cache().lock().unwrap();
+    cache.retain(|_, (cached_at, _)| cached_at.elapsed() < SEARCH_CACHE_TTL);
+    if cache.len() >= SEARCH_CACHE_LIMIT {
+        if let Some(oldest) = cache.iter().min_by_key(|(_, (cached_at, _))| *cached_at).map(|(key, _)| key.clone()) {
+            cache.remove(&oldest);
+        }
+    }
+    cache.insert(key, (Instant::now(), suggestions));
+}
+
+fn get_choice_name(prefix: &str, title: Option<String>, url: &str) -> String {
+    let name = format!("{} {}", prefix, title.unwrap_or(url.to_string()));
+    match name.chars().count() > CHOICE_LIMIT {
+        true => name.chars().take(CHOICE_LIMIT - 1).chain(std::iter::once('…')).collect(),
+        false => name
+    }
+}
+
+async fn search(query: String) -> Suggestions {
+    let key = query.to_lowercase();
+    if let Some(suggestions) = get_cached_search(&key) {
+        return suggestions;
+    }
+    let suggestions: Suggestions = source_registry().search_many(&query, None, SEARCH_RESULTS).await
+        .into_iter()
+        .map(|track| (get_choice_name("🔎", track.title, &track.webpage_url), track.webpage_url))
+        .collect();
+    if !suggestions.is_empty() {
+        cache_search(key, suggestions.clone());
+    }
+    suggestions
+}
+
+pub async fn run(ctx: Context, command: CommandInteraction) {
+    let start = Instant::now();
+    let input = command.data.autocomplete().unwrap();
+    let query = input.value.trim().to_string();
+    let user_id = command.user.id.get();
+
+    let request_id = LAST_REQUEST.fetch_add(1, Ordering::Relaxed) + 1;
+    pending_requests().lock().unwrap().insert(user_id, request_id);
+
+    let pool = {
+        let data_read = ctx.data.read().await;
+        let conn = data_read.get::<PlayerDataBase>().expect("Expected PlayerDataBase in TypeMap.").clone();
+        conn.get()
+    };
+    let (recent, saved) = match pool {
+        Ok(mut conn) => (
+            command.guild_id.map(|guild_id| get_recent_tracks(&mut conn, guild_id.get(), HISTORY_LIMIT).unwrap_or_default()).unwrap_or_default(),
+            search_playlist_tracks(&mut conn, user_id, &query, MAX_CHOICES as i64).unwrap_or_default()
+        ),
+        Err(_) => (Vec::new(), Vec::new())
+    };
+
+    let lowercase_query = query.to_lowercase();
+    let mut suggestions: Suggestions = recent.into_iter()
+        .filter(|(url, title)| {
+            lowercase_query.is_empty()
+                || url.to_lowercase().contains(&lowercase_query)
+                || title.as_ref().map(|title| title.to_lowercase().contains(&lowercase_query)).unwrap_or(false)
+        })
+        .map(|(url, title)| (get_choice_name("🕘", title, &url), url))
+        .chain(saved.into_iter().map(|(url, title)| (get_choice_name("📁", title, &url), url)))
+        .collect();
+
+    if query.chars().count() >= SEARCH_MIN_LENGTH && !source_registry().is_url(&query) {
+        let cached = get_cached_search(&lowercase_query);
+        let found = match cached {
+            Some(found) => found,
+            None => {
+                sleep(DEBOUNCE).await;
+                let is_latest = pending_requests().lock().unwrap().get(&user_id) == Some(&request_id);
+                match is_latest {
+                    true => {
+                        let remaining = DEADLINE.saturating_sub(start.elapsed());
+                        let handle = tokio::spawn(search(query.clone()));
+                        timeout(remaining, handle).await.ok().and_then(|found| found.ok()).unwrap_or_default()
+                    },
+                    false => Vec::new()
+                }
+            }
+        };
+        suggestions.splice(0..0, found);
+    }
+
+    {
+        let mut pending = pending_requests().lock().unwrap();
+        if pending.get(&user_id) == Some(&request_id) {
+            pending.remove(&user_id);
+        }
+    }
+
+    let mut seen = HashSet::new();
+    let mut choices = CreateAutocompleteResponse::new();
+    suggestions.into_iter()
+        .filter(|(_, url)| url.len() <= CHOICE_LIMIT && seen.insert(url.clone()))
+        .take(MAX_CHOICES)
+        .for_each(|(name, url)| {
+            choices = choices.clone().add_string_choice(name, url);
+        });
+    let builder = CreateInteractionResponse::Autocomplete(choices);
+    check_msg(command.create_response(&ctx.http, builder).await);
+}
@@ -0,0 +1,33 @@
+use serenity::{client::Context, all::CommandInteraction, builder::{CreateInteractionResponse, CreateAutocompleteResponse}};
+use strsim::normalized_damerau_levenshtein;
+
+use crate::bot::utils::{player::PlayerDataBase, saved_playlist::list_playlists, check_msg};
+
+pub async fn run(ctx: Context, command: CommandInteraction) {
+    let input = command.data.autocomplete().unwrap();
+
+    let pool = {
+        let data_read = ctx.data.read().await;
+        let conn = data_read.get::<PlayerDataBase>().expect("Expected PlayerDataBase in TypeMap.").clone();
+        conn.get()
+    };
+    let playlists = match pool {
+        Ok(mut conn) => list_playlists(&mut conn, command.user.id.get()).unwrap_or_default(),
+        Err(_) => Vec::new()
+    };
+
+    let mut distances: Vec<(f64, String)> = playlists
+        .into_iter()
+        .map(|(playlist, _)| (normalized_damerau_levenshtein(input.value.to_lowercase().as_str(), &playlist.name.to_lowercase()), playlist.name))
+        .collect();
+    if !input.value.is_empty() {
+        distances.sort_by(|a, b| b.0.total_cmp(&a.0));
+    }
+
+    let mut choices = CreateAutocompleteResponse::new();
+    distances.into_iter().take(25).for_each(|(_, name)| {
+        choices = choices.clone().add_string_choice(name.clone(), name);
+    });
+    let builder = CreateInteractionResponse::Autocomplete(choices);
+    check_msg(command.create_response(&ctx.http, builder).await);
+}
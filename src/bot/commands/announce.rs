@@ -0,0 +1,90 @@
+use std::collections::HashMap;
+
+use serenity::all::{ChannelType, Permissions, ResolvedValue};
+use serenity::builder::{CreateCommand, CreateCommandOption, CreateInteractionResponseMessage, CreateInteractionResponse};
+use serenity::client::Context;
+use serenity::model::application::{CommandOptionType, CommandInteraction};
+
+use crate::bot::utils::check_msg;
+use crate::bot::utils::player::{initialize_guild_player, PlayerData};
+
+pub async fn run(ctx: Context, command: CommandInteraction) {
+    initialize_guild_player(&ctx, command.guild_id.unwrap()).await;
+
+    let player = ctx.data.read().await.get::<PlayerData>().unwrap().clone();
+    let player = player.read().await.clone();
+    let player = player.get(&command.guild_id.unwrap().get()).unwrap().clone();
+    let mut settings = player.settings.write().await;
+
+    let text = match command.data.options().first() {
+        Some(subcommand) => match (subcommand.name, &subcommand.value) {
+            ("set", ResolvedValue::SubCommand(options)) => {
+                let options: HashMap<_, _> = options.iter().map(|param| (param.name, &param.value)).collect();
+                let channel = match options.get("channel") {
+                    Some(ResolvedValue::Channel(channel)) => channel.id,
+                    _ => {
+                        let data = CreateInteractionResponseMessage::new().content(match command.locale.as_str() {
+                            "ru" => "Неверный канал!",
+                            _ => "Invalid channel!"
+                        }).ephemeral(true);
+                        check_msg(command.create_response(&ctx.http, CreateInteractionResponse::Message(data)).await);
+                        return ;
+                    }
+                };
+                let delete_previous = match options.get("delete_previous") {
+                    Some(ResolvedValue::Boolean(delete_previous)) => *delete_previous,
+                    _ => settings.announce_delete_previous
+                };
+                settings.set_announce(&ctx, Some(channel.get()), delete_previous).await;
+                Some(match command.locale.as_str() {
+                    "ru" => format!("Текущий трек будет объявляться в <#{}>.", channel.get()),
+                    _ => format!("Now playing will be announced in <#{}>.", channel.get())
+                })
+            },
+            ("off", _) => {
+                let delete_previous = settings.announce_delete_previous;
+                settings.set_announce(&ctx, None, delete_previous).await;
+                Some(match command.locale.as_str() {
+                    "ru" => "Объявления отключены.",
+                    _ => "Announcements are disabled."
+                }.to_string())
+            },
+            _ => None
+        },
+        None => None
+    };
+    let text = text.unwrap_or(match command.locale.as_str() {
+        "ru" => "Неизвестная подкоманда!",
+        _ => "Unknown subcommand!"
+    }.to_string());
+
+    let data = CreateInteractionResponseMessage::new().content(text).ephemeral(true);
+    let builder = CreateInteractionResponse::Message(data);
+    check_msg(command.create_response(&ctx.http, builder).await);
+}
+
+pub fn register() -> CreateCommand {
+    CreateCommand::new("announce")
+        .description("Now playing announcements")
+        .description_localized("ru", "Объявления о текущем треке")
+        .add_option(
+            CreateCommandOption::new(CommandOptionType::SubCommand, "set", "Announce every new track in a text channel")
+                .description_localized("ru", "Объявлять каждый новый трек в текстовом канале")
+                .add_sub_option(
+                    CreateCommandOption::new(CommandOptionType::Channel, "channel", "Channel for announcements")
+                        .description_localized("ru", "Канал для объявлений")
+                        .channel_types(vec![ChannelType::Text])
+                        .required(true)
+                )
+                .add_sub_option(
+                    CreateCommandOption::new(CommandOptionType::Boolean, "delete_previous", "Delete the previous announcement")
+                        .description_localized("ru", "Удалять предыдущее объявление")
+                )
+        )
+        .add_option(
+            CreateCommandOption::new(CommandOptionType::SubCommand, "off", "Disable announcements")
+                .description_localized("ru", "Отключить объявления")
+        )
+        .default_member_permissions(Permissions::MANAGE_GUILD)
+        .dm_permission(false)
+}
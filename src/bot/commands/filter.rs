@@ -0,0 +1,87 @@
+use std::collections::HashMap;
+
+use serenity::all::ResolvedValue;
+use serenity::builder::{CreateCommand, CreateCommandOption, CreateInteractionResponseMessage, CreateInteractionResponse};
+use serenity::client::Context;
+use serenity::model::application::{CommandOptionType, CommandInteraction};
+
+use crate::bot::utils::check_msg;
+use crate::bot::utils::filters::{Effect, Effects};
+use crate::bot::utils::player::{initialize_guild_player, PlayerData};
+
+pub async fn run(ctx: Context, command: CommandInteraction) {
+    let options: &HashMap<_, _> = &command.data.options().into_iter().map(|param| (param.name, param.value)).collect();
+    let locale = command.locale.as_str();
+
+    let effect = match options.get("effect") {
+        Some(ResolvedValue::String(effect)) => Some(*effect),
+        _ => None
+    };
+    let enable = match options.get("enable") {
+        Some(ResolvedValue::Boolean(enable)) => Some(*enable),
+        _ => None
+    };
+
+    initialize_guild_player(&ctx, command.guild_id.unwrap()).await;
+
+    let player = ctx.data.read().await.get::<PlayerData>().unwrap().clone();
+    let player = player.read().await.clone();
+    let player = player.get(&command.guild_id.unwrap().get()).unwrap().clone();
+
+    let (effects, rate, restart) = {
+        let mut settings = player.settings.write().await;
+        let mut ffmpeg = player.ffmpeg.write().await;
+        let rate = settings.get_rate();
+        let mut effects = settings.effects;
+        match effect {
+            Some("off") => effects = Effects::default(),
+            Some(name) => if let Some(effect) = Effect::from_name(name) {
+                effects.set(effect, enable.unwrap_or(!effects.get(effect)));
+            },
+            None => {}
+        }
+        let restart = match effects != settings.effects {
+            true => settings.set_effects(&ctx, effects, ffmpeg.as_mut()).await,
+            false => false
+        };
+        (effects, rate, restart)
+    };
+    let restarted = restart && player.restart(rate).await.is_ok();
+
+    let enabled = effects.enabled()
+        .map(|effect| format!("`{}`", effect.display_name(locale)))
+        .collect::<Vec<String>>();
+    let mut text = match (enabled.is_empty(), locale) {
+        (true, "ru") => "Эффекты выключены.".to_string(),
+        (true, _) => "Effects are disabled.".to_string(),
+        (false, "ru") => format!("Включены эффекты: {}.", enabled.join(", ")),
+        (false, _) => format!("Enabled effects: {}.", enabled.join(", "))
+    };
+    if restarted {
+        text.push_str(match locale {
+            "ru" => " Трек перезапущен с текущей позиции.",
+            _ => " The track was restarted from the current position."
+        });
+    }
+
+    let data = CreateInteractionResponseMessage::new().content(text).ephemeral(true);
+    let builder = CreateInteractionResponse::Message(data);
+    check_msg(command.create_response(&ctx.http, builder).await);
+}
+
+pub fn register() -> CreateCommand {
+    let mut effect = CreateCommandOption::new(CommandOptionType::String, "effect", "Effect to toggle")
+        .description_localized("ru", "Переключаемый эффект")
+        .add_string_choice_localized("Disable all", "off", [("ru", "Выключить все")]);
+    for value in Effect::ALL {
+        effect = effect.add_string_choice_localized(value.display_name(""), value.name(), [("ru", value.display_name("ru"))]);
+    }
+    CreateCommand::new("filter")
+        .description("Audio effects")
+        .description_localized("ru", "Звуковые эффекты")
+        .add_option(effect)
+        .add_option(
+            CreateCommandOption::new(CommandOptionType::Boolean, "enable", "Enabling/disabling the effect(toggles by default)")
+                .description_localized("ru", "Включение/выключение эффекта(по умолчанию переключает)")
+        ).dm_permission(false)
+}
@@ -0,0 +1,91 @@
+use std::collections::HashMap;
+
+use serenity::all::ResolvedValue;
+use serenity::builder::{CreateCommand, CreateCommandOption, CreateEmbed, EditInteractionResponse};
+use serenity::client::Context;
+use serenity::model::application::{CommandOptionType, CommandInteraction};
+
+use crate::bot::utils::jobs::{cancel_jobs, get_jobs, JobState};
+use crate::bot::utils::player::{initialize_guild_player, PlayerData};
+use crate::bot::utils::check_msg;
+
+pub async fn run(ctx: Context, command: CommandInteraction) {
+    check_msg(command.defer_ephemeral(&ctx.http).await);
+    initialize_guild_player(&ctx, command.guild_id.unwrap()).await;
+
+    let locale = command.locale.as_str();
+    let player = ctx.data.read().await.get::<PlayerData>().unwrap().clone();
+    let player = player.read().await.clone();
+    let player = player.get(&command.guild_id.unwrap().get()).unwrap().clone();
+
+    let builder = match command.data.options().first() {
+        Some(subcommand) => match (subcommand.name, &subcommand.value) {
+            ("cancel", ResolvedValue::SubCommand(options)) => {
+                let options: HashMap<_, _> = options.iter().map(|param| (param.name, &param.value)).collect();
+                let job_id = match options.get("job") {
+                    Some(ResolvedValue::Integer(job_id)) => Some(*job_id as u64),
+                    _ => None
+                };
+                match cancel_jobs(&player, job_id).await {
+                    0 => EditInteractionResponse::new().content(match locale {
+                        "ru" => "Нет активных импортов!",
+                        _ => "There are no running imports!"
+                    }),
+                    count => EditInteractionResponse::new().content(match locale {
+                        "ru" => format!("Отменено импортов: {}", count),
+                        _ => format!("Imports cancelled: {}", count)
+                    })
+                }
+            },
+            ("status", ResolvedValue::SubCommand(_)) => {
+                let jobs = get_jobs(&player).await;
+                if jobs.is_empty() {
+                    EditInteractionResponse::new().content(match locale {
+                        "ru" => "Импортов ещё не было!",
+                        _ => "There have been no imports yet!"
+                    })
+                } else {
+                    let description = jobs.iter().rev()
+                        .map(|job| format!("`#{}` {} {}", job.id, match job.state {
+                            JobState::Running => "⏳",
+                            JobState::Finished => "✅",
+                            JobState::Cancelled => "⛔"
+                        }, job.get_progress_str(locale)))
+                        .collect::<Vec<String>>()
+                        .join("\n");
+                    let embed = CreateEmbed::new()
+                        .color(14441063)
+                        .title(match locale {
+                            "ru" => "Импорты:",
+                            _ => "Imports:"
+                        })
+                        .description(description);
+                    EditInteractionResponse::new().embed(embed)
+                }
+            },
+            _ => return
+        },
+        None => return
+    };
+    check_msg(command.edit_response(&ctx.http, builder).await);
+}
+
+pub fn register() -> CreateCommand {
+    CreateCommand::new("import")
+        .description("Manage background imports")
+        .description_localized("ru", "Управление фоновыми импортами")
+        .add_option(
+            CreateCommandOption::new(CommandOptionType::SubCommand, "cancel", "Cancel running imports")
+                .description_localized("ru", "Отменить активные импорты")
+                .add_sub_option(
+                    CreateCommandOption::new(CommandOptionType::Integer, "job", "Import number(default all)")
+                        .description_localized("ru", "Номер импорта(по умолчанию все)")
+                        .min_int_value(1)
+                )
+        )
+        .add_option(
+            CreateCommandOption::new(CommandOptionType::SubCommand, "status", "Show import progress")
+                .description_localized("ru", "Показать прогресс импортов")
+        )
+        .dm_permission(false)
+}
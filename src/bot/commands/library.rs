@@ -0,0 +1,173 @@
+use std::collections::HashMap;
+
+use serenity::all::{Permissions, ResolvedValue};
+use serenity::builder::{CreateCommand, CreateCommandOption, CreateEmbed, EditInteractionResponse};
+use serenity::client::Context;
+use serenity::model::application::{CommandOptionType, CommandInteraction};
+
+use crate::bot::utils::library::{get_library_paths, get_library_track, get_library_track_str, get_track, scan_library, search_library};
+use crate::bot::utils::parser::get_time_str;
+use crate::bot::utils::player::{enqueue_track, initialize_guild_player, PlayerData, PlayerDataBase};
+use crate::bot::utils::{check_msg, join_user_channel};
+
+const SEARCH_LIMIT: i64 = 10;
+
+pub async fn run(ctx: Context, command: CommandInteraction) {
+    let locale = command.locale.as_str();
+    let pool = {
+        let data_read = ctx.data.read().await;
+        data_read.get::<PlayerDataBase>().expect("Expected PlayerDataBase in TypeMap.").clone()
+    };
+
+    let (subcommand, options) = match command.data.options().first() {
+        Some(subcommand) => match &subcommand.value {
+            ResolvedValue::SubCommand(options) => (subcommand.name, options.iter().map(|param| (param.name, param.value.clone())).collect::<HashMap<_, _>>()),
+            _ => return
+        },
+        None => return
+    };
+
+    let builder = match subcommand {
+        "search" => {
+            check_msg(command.defer_ephemeral(&ctx.http).await);
+            let query = match options.get("query") {
+                Some(ResolvedValue::String(query)) => *query,
+                _ => return
+            };
+            let found = match query.parse::<i32>() {
+                Ok(id) => pool.get().ok().and_then(|mut conn| get_library_track(&mut conn, id).ok().flatten()).map(|track| vec![track]),
+                Err(_) => None
+            }.or(pool.get().ok().and_then(|mut conn| search_library(&mut conn, query, SEARCH_LIMIT).ok()));
+            match found {
+                Some(found) if !found.is_empty() => {
+                    let description = found.iter()
+                        .map(|track| match track.duration {
+                            Some(duration) => format!("`{}` {} `{}`", track.id, get_library_track_str(track), get_time_str(duration)),
+                            None => format!("`{}` {}", track.id, get_library_track_str(track))
+                        })
+                        .collect::<Vec<String>>()
+                        .join("\n");
+                    let embed = CreateEmbed::new()
+                        .color(14441063)
+                        .title(match locale {
+                            "ru" => "Найдено в библиотеке:",
+                            _ => "Found in the library:"
+                        })
+                        .description(description);
+                    EditInteractionResponse::new().embed(embed)
+                },
+                _ => EditInteractionResponse::new().content(match locale {
+                    "ru" => "Ничего не найдено!",
+                    _ => "Nothing was found!"
+                })
+            }
+        },
+        "play" => {
+            check_msg(command.defer(&ctx.http).await);
+            let query = match options.get("track") {
+                Some(ResolvedValue::String(query)) => *query,
+                _ => return
+            };
+            let library_track = match query.parse::<i32>() {
+                Ok(id) => pool.get().ok().and_then(|mut conn| get_library_track(&mut conn, id).ok().flatten()),
+                Err(_) => None
+            }.or(pool.get().ok().and_then(|mut conn| search_library(&mut conn, query, 1).ok()).and_then(|mut found| found.pop()));
+            match library_track {
+                Some(library_track) => {
+                    initialize_guild_player(&ctx, command.guild_id.unwrap()).await;
+                    let player = ctx.data.read().await.get::<PlayerData>().unwrap().clone();
+                    let player = player.read().await.clone();
+                    let player = player.get(&command.guild_id.unwrap().get()).unwrap().clone();
+                    let mut last_id = player.playlist_sync_and_last_id.lock().await;
+
+                    if !join_user_channel(&ctx, &command).await {
+                        return;
+                    }
+
+                    *last_id+=1;
+                    let mut track = get_track(&library_track, *last_id);
+                    track.requester = Some(command.user.id.get());
+                    let embed = track.get_embed(locale)
+                        .color(14441063)
+                        .title(match locale {
+                            "ru" => "Добавлен трек:",
+                            _ => "Added track:"
+                        });
+                    enqueue_track(&ctx, &player, track).await;
+                    EditInteractionResponse::new().embed(embed)
+                },
+                None => EditInteractionResponse::new().content(match locale {
+                    "ru" => "Трек не найден в библиотеке!",
+                    _ => "The track was not found in the library!"
+                })
+            }
+        },
+        "rescan" => {
+            check_msg(command.defer_ephemeral(&ctx.http).await);
+            let allowed = command.member.as_ref()
+                .and_then(|member| member.permissions)
+                .map(|permissions| permissions.contains(Permissions::MANAGE_GUILD))
+                .unwrap_or(false);
+            let paths = get_library_paths();
+            if !allowed {
+                EditInteractionResponse::new().content(match locale {
+                    "ru" => "Для пересканирования нужно право «Управлять сервером»!",
+                    _ => "You need the Manage Server permission to rescan the library!"
+                })
+            } else if paths.is_empty() {
+                EditInteractionResponse::new().content(match locale {
+                    "ru" => "Папки библиотеки не настроены!",
+                    _ => "No library directories are configured!"
+                })
+            } else {
+                let result = tokio::task::spawn_blocking(move || {
+                    let mut conn = pool.get().ok()?;
+                    scan_library(&mut conn, &paths).ok()
+                }).await.ok().flatten();
+                match result {
+                    Some(result) => EditInteractionResponse::new().content(match locale {
+                        "ru" => format!("Библиотека обновлена: добавлено {}, обновлено {}, удалено {}, без изменений {}, ошибок {}.", result.added, result.updated, result.removed, result.unchanged, result.failed),
+                        _ => format!("Library rescanned: {} added, {} updated, {} removed, {} unchanged, {} failed.", result.added, result.updated, result.removed, result.unchanged, result.failed)
+                    }),
+                    None => EditInteractionResponse::new().content(match locale {
+                        "ru" => "Не удалось просканировать библиотеку!",
+                        _ => "Failed to scan the library!"
+                    })
+                }
+            }
+        },
+        _ => return
+    };
+    check_msg(command.edit_response(&ctx.http, builder).await);
+}
+
+pub fn register() -> CreateCommand {
+    CreateCommand::new("library")
+        .description("Local music library")
+        .description_localized("ru", "Локальная музыкальная библиотека")
+        .add_option(
+            CreateCommandOption::new(CommandOptionType::SubCommand, "search", "Search the library")
+                .description_localized("ru", "Поиск по библиотеке")
+                .add_sub_option(
+                    CreateCommandOption::new(CommandOptionType::String, "query", "Title, artist or album")
+                        .description_localized("ru", "Название, исполнитель или альбом")
+                        .set_autocomplete(true)
+                        .required(true)
+                )
+        )
+        .add_option(
+            CreateCommandOption::new(CommandOptionType::SubCommand, "play", "Play a track from the library")
+                .description_localized("ru", "Воспроизвести трек из библиотеки")
+                .add_sub_option(
+                    CreateCommandOption::new(CommandOptionType::String, "track", "Track to play")
+                        .description_localized("ru", "Трек для воспроизведения")
+                        .set_autocomplete(true)
+                        .required(true)
+                )
+        )
+        .add_option(
+            CreateCommandOption::new(CommandOptionType::SubCommand, "rescan", "Rescan library directories for changes")
+                .description_localized("ru", "Пересканировать папки библиотеки")
+        )
+        .dm_permission(false)
+}
@@ -0,0 +1,28 @@
+use serenity::builder::{CreateCommand, CreateInteractionResponseMessage, CreateInteractionResponse};
+use serenity::client::Context;
+use serenity::model::application::CommandInteraction;
+
+use crate::bot::utils::check_msg;
+use crate::bot::utils::likes::like_current_track;
+use crate::bot::utils::player::{initialize_guild_player, PlayerData};
+
+pub async fn run(ctx: Context, command: CommandInteraction) {
+    initialize_guild_player(&ctx, command.guild_id.unwrap()).await;
+
+    let player = ctx.data.read().await.get::<PlayerData>().unwrap().clone();
+    let player = player.read().await.clone();
+    let player = player.get(&command.guild_id.unwrap().get()).unwrap().clone();
+
+    let text = like_current_track(&ctx, player.playlist.read().await.current.clone(), command.user.id.get(), command.locale.as_str()).await;
+
+    let data = CreateInteractionResponseMessage::new().content(text).ephemeral(true);
+    let builder = CreateInteractionResponse::Message(data);
+    check_msg(command.create_response(&ctx.http, builder).await);
+}
+
+pub fn register() -> CreateCommand {
+    CreateCommand::new("like")
+        .description("Adds the current track to your likes (or removes it)")
+        .description_localized("ru", "Добавляет текущий трек в избранное (или удаляет из него)")
+        .dm_permission(false)
+}
@@ -0,0 +1,137 @@
+use std::collections::HashMap;
+
+use rand::seq::SliceRandom;
+use serenity::all::ResolvedValue;
+use serenity::builder::{CreateCommand, CreateCommandOption, EditInteractionResponse};
+use serenity::client::Context;
+use serenity::model::application::{CommandOptionType, CommandInteraction};
+
+use crate::bot::utils::likes::{get_liked_tracks, get_likes_message, get_likes_page};
+use crate::bot::utils::player::{enqueue_track, initialize_guild_player, PlayerData, PlayerDataBase};
+use crate::bot::utils::{check_msg, join_user_channel};
+
+pub async fn run(ctx: Context, command: CommandInteraction) {
+    check_msg(command.defer_ephemeral(&ctx.http).await);
+
+    let locale = command.locale.as_str();
+    let user = command.user.id.get();
+    let pool = {
+        let data_read = ctx.data.read().await;
+        let conn = data_read.get::<PlayerDataBase>().expect("Expected PlayerDataBase in TypeMap.").clone();
+        conn.get()
+    };
+    let mut conn = match pool {
+        Ok(conn) => conn,
+        Err(_) => {
+            let builder = EditInteractionResponse::new().content(match locale {
+                "ru" => "Не удалось подключиться к базе данных!",
+                _ => "Failed to connect to the database!"
+            });
+            check_msg(command.edit_response(&ctx.http, builder).await);
+            return ;
+        }
+    };
+
+    let builder = match command.data.options().first() {
+        Some(subcommand) => match (subcommand.name, &subcommand.value) {
+            ("list", ResolvedValue::SubCommand(options)) => {
+                let options: HashMap<_, _> = options.iter().map(|param| (param.name, &param.value)).collect();
+                let page = match options.get("page") {
+                    Some(ResolvedValue::Integer(page)) => *page - 1,
+                    _ => 0
+                };
+                match get_likes_page(&mut conn, user, page) {
+                    Ok((liked, total)) => {
+                        let (embed, components) = get_likes_message(&liked, total, page, user, locale);
+                        EditInteractionResponse::new().embed(embed).components(components)
+                    },
+                    Err(_) => EditInteractionResponse::new().content(match locale {
+                        "ru" => "Не удалось получить избранное!",
+                        _ => "Failed to get your likes!"
+                    })
+                }
+            },
+            ("play", ResolvedValue::SubCommand(options)) => {
+                let options: HashMap<_, _> = options.iter().map(|param| (param.name, &param.value)).collect();
+                let shuffle = match options.get("shuffle") {
+                    Some(ResolvedValue::Boolean(shuffle)) => *shuffle,
+                    _ => false
+                };
+                let count = match options.get("count") {
+                    Some(ResolvedValue::Integer(count)) => Some(*count as usize),
+                    _ => None
+                };
+                let mut tracks = match get_liked_tracks(&mut conn, user) {
+                    Ok(tracks) => tracks,
+                    Err(_) => Vec::new()
+                };
+                if tracks.is_empty() {
+                    EditInteractionResponse::new().content(match locale {
+                        "ru" => "Список избранного пуст.",
+                        _ => "You have no liked tracks."
+                    })
+                } else {
+                    initialize_guild_player(&ctx, command.guild_id.unwrap()).await;
+                    let player = ctx.data.read().await.get::<PlayerData>().unwrap().clone();
+                    let player = player.read().await.clone();
+                    let player = player.get(&command.guild_id.unwrap().get()).unwrap().clone();
+                    let mut last_id = player.playlist_sync_and_last_id.lock().await;
+
+                    if !join_user_channel(&ctx, &command).await {
+                        return;
+                    }
+
+                    if shuffle {
+                        tracks.shuffle(&mut rand::thread_rng());
+                    }
+                    if let Some(count) = count {
+                        tracks.truncate(count);
+                    }
+                    let added = tracks.len();
+                    for mut track in tracks {
+                        *last_id+=1;
+                        track.id = *last_id;
+                        track.requester = Some(user);
+                        enqueue_track(&ctx, &player, track).await;
+                    }
+                    EditInteractionResponse::new().content(match locale {
+                        "ru" => format!("Добавлено треков из избранного: {}.", added),
+                        _ => format!("Added {} liked tracks.", added)
+                    })
+                }
+            },
+            _ => return
+        },
+        None => return
+    };
+    check_msg(command.edit_response(&ctx.http, builder).await);
+}
+
+pub fn register() -> CreateCommand {
+    CreateCommand::new("likes")
+        .description("Your liked tracks")
+        .description_localized("ru", "Ваши избранные треки")
+        .add_option(
+            CreateCommandOption::new(CommandOptionType::SubCommand, "list", "Show your liked tracks")
+                .description_localized("ru", "Показать избранные треки")
+                .add_sub_option(
+                    CreateCommandOption::new(CommandOptionType::Integer, "page", "Page number")
+                        .description_localized("ru", "Номер страницы")
+                        .min_int_value(1)
+                )
+        )
+        .add_option(
+            CreateCommandOption::new(CommandOptionType::SubCommand, "play", "Add your liked tracks to the queue")
+                .description_localized("ru", "Добавить избранные треки в очередь")
+                .add_sub_option(
+                    CreateCommandOption::new(CommandOptionType::Boolean, "shuffle", "Shuffle the tracks")
+                        .description_localized("ru", "Перемешать треки")
+                )
+                .add_sub_option(
+                    CreateCommandOption::new(CommandOptionType::Integer, "count", "How many tracks to add(default all)")
+                        .description_localized("ru", "Сколько треков добавить(по умолчанию все)")
+                        .min_int_value(1)
+                )
+        )
+        .dm_permission(false)
+}
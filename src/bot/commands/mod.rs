@@ -10,4 +10,18 @@ pub mod disconnect;
 pub mod join;
 pub mod password;
 pub mod speed;
-pub mod bass;
\ No newline at end of file
+pub mod bass;
+pub mod nowplaying;
+pub mod announce;
+pub mod sleep;
+pub mod playlist;
+pub mod like;
+pub mod likes;
+pub mod stats;
+pub mod queue;
+pub mod library;
+pub mod import;
+pub mod search;
+pub mod radio;
+pub mod podcast;
+pub mod filter;
\ No newline at end of file
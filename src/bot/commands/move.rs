@@ -23,6 +23,18 @@ pub async fn run(ctx: Context, command: CommandInteraction) {
     let player = player.read().await.clone();
     let player = player.get(&command.guild_id.unwrap().get()).unwrap().clone();
 
+    let is_live = player.playlist.read().await.current.as_ref().map(|track| track.is_live).unwrap_or(false);
+    if is_live {
+        let data = CreateInteractionResponseMessage::new()
+            .content(match command.locale.as_str() {
+                "ru" => "Перемещение недоступно в прямой трансляции!",
+                _ => "Seeking is not available in a live stream!"
+            })
+            .ephemeral(true);
+        check_msg(command.create_response(&ctx.http, CreateInteractionResponse::Message(data)).await);
+        return;
+    }
+
     let mut state = player.state.write().await;
     let player_handler = player.player.write().await.clone();
 
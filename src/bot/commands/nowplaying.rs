@@ -0,0 +1,28 @@
+use serenity::builder::{CreateCommand, CreateInteractionResponseMessage, CreateInteractionResponse};
+use serenity::client::Context;
+use serenity::model::application::CommandInteraction;
+
+use crate::bot::utils::check_msg;
+use crate::bot::utils::controls::get_player_message;
+use crate::bot::utils::player::{PlayerData, initialize_guild_player};
+
+pub async fn run(ctx: Context, command: CommandInteraction) {
+    initialize_guild_player(&ctx, command.guild_id.unwrap()).await;
+
+    let player = ctx.data.read().await.get::<PlayerData>().unwrap().clone();
+    let player = player.read().await.clone();
+    let player = player.get(&command.guild_id.unwrap().get()).unwrap().clone();
+
+    let (embed, components) = get_player_message(&player, command.locale.as_str(), None).await;
+
+    let data = CreateInteractionResponseMessage::new().embed(embed).components(components);
+    let builder = CreateInteractionResponse::Message(data);
+    check_msg(command.create_response(&ctx.http, builder).await);
+}
+
+pub fn register() -> CreateCommand {
+    CreateCommand::new("nowplaying")
+        .description("Shows the current track with player controls")
+        .description_localized("ru", "Показывает текущий трек с кнопками управления")
+        .dm_permission(false)
+}
@@ -5,7 +5,12 @@ use serenity::builder::{CreateCommand, CreateCommandOption, EditInteractionRespo
 use serenity::client::Context;
 use serenity::model::application::{CommandOptionType, CommandInteraction};
 
+use diesel::{ExpressionMethods, RunQueryDsl};
+
+use crate::api::auth_service::hash_password;
 use crate::bot::utils::check_msg;
+use crate::bot::utils::player::PlayerDataBase;
+use crate::models::UserDB;
 
 pub async fn run(ctx: Context, command: CommandInteraction) {
     let options: &HashMap<_, _> = &command.data.options().into_iter().map(|param| (param.name, param.value)).collect();
@@ -14,10 +19,30 @@ pub async fn run(ctx: Context, command: CommandInteraction) {
         _ => None
     }.expect("Cannot find password option");
     check_msg(command.defer_ephemeral(&ctx.http).await);
+    let pool = {
+        let data_read = ctx.data.read().await;
+        let conn = data_read.get::<PlayerDataBase>().expect("Expected PlayerDataBase in TypeMap.").clone();
+        conn.get()
+    };
+    let result = match (pool, hash_password(password)) {
+        (Ok(mut pool), Some(hash)) => {
+            use crate::schema::users;
+            diesel::insert_into(users::table)
+                .values(UserDB { id: command.user.id.get() as i64, password: hash.clone(), is_admin: false })
+                .on_conflict(users::id)
+                .do_update()
+                .set(users::password.eq(hash))
+                .execute(&mut pool)
+                .is_ok()
+        },
+        _ => false
+    };
     let data = EditInteractionResponse::new().content(
-        match command.locale.as_str() {
-            "ru" => "Установлен новый пароль.",
-            _ => "New password is set."
+        match (command.locale.as_str(), result) {
+            ("ru", true) => "Установлен новый пароль.",
+            (_, true) => "New password is set.",
+            ("ru", false) => "Не удалось установить пароль!",
+            (_, false) => "Failed to set the password!"
         }
     );
     check_msg(command.edit_response(&ctx.http, data).await);
@@ -1,20 +1,21 @@
 use std::collections::HashMap;
-use std::time::Duration;
 
+use rand::seq::SliceRandom;
 use serenity::all::ResolvedValue;
-use serenity::builder::{CreateCommand, CreateCommandOption, EditInteractionResponse, CreateEmbed};
+use serenity::builder::{CreateCommand, CreateCommandOption, CreateInteractionResponse, CreateInteractionResponseMessage, EditInteractionResponse, CreateEmbed, CreateEmbedAuthor};
 use serenity::client::Context;
 use serenity::model::application::{CommandOptionType, CommandInteraction};
-use songbird::{TrackEvent, Event, CoreEvent};
-use songbird::driver::Bitrate;
-use tokio::time::sleep;
-use youtube_dl::SearchType;
 
-use crate::bot::events::TrackEndNotifier;
-use crate::bot::utils::parser::{parse_url, ParsedDataType, parse_track_yt, search_track_yt, search_track_vk};
-use crate::bot::utils::player::{PlayerData, initialize_guild_player, PlayerState, Position};
+use crate::bot::utils::parser::{parse_url, ParsedDataType, parse_time};
+use crate::bot::utils::jobs::spawn_import_job_ctx;
+use crate::bot::utils::queue_file::QueueEntry;
+use crate::bot::utils::player::{PlayerData, initialize_guild_player, enqueue_track};
+use crate::bot::utils::podcast::{get_episode_response, store_picker};
+use crate::bot::utils::resolver::{source_registry, DEFAULT_SEARCH_SOURCE};
 use crate::bot::utils::track::{Track, PlaylistType};
-use crate::bot::utils::{get_voice_channel, check_msg};
+use crate::bot::utils::{join_user_channel, check_msg};
+
+const PLAYLIST_LIMIT: u64 = 500;
 
 pub async fn run(ctx: Context, command: CommandInteraction) {
     let options: &HashMap<_, _> = &command.data.options().into_iter().map(|param| (param.name, param.value)).collect();
@@ -27,10 +28,55 @@ pub async fn run(ctx: Context, command: CommandInteraction) {
         Some(ResolvedValue::Integer(limit)) => *limit,
         _ => 25
     } as usize;
-    let search_type = match options.get("search") {
-        Some(ResolvedValue::Integer(value)) => Some(*value),
+    let search_source = match options.get("search") {
+        Some(ResolvedValue::String(value)) => Some(*value),
+        _ => None
+    };
+    let start = match options.get("start") {
+        Some(ResolvedValue::String(start)) => Some(parse_time(start)),
+        _ => None
+    };
+    let end = match options.get("end") {
+        Some(ResolvedValue::String(end)) => Some(parse_time(end)),
+        _ => None
+    };
+    if matches!(start, Some(None)) || matches!(end, Some(None)) {
+        let builder = CreateInteractionResponseMessage::new()
+            .content(match command.locale.as_str() {
+                "ru" => "Неверное время фрагмента! Используйте формат 90 | 1:30 | 1m30s",
+                _ => "Invalid fragment time! Use the format 90 | 1:30 | 1m30s"
+            })
+            .ephemeral(true);
+        check_msg(command.create_response(&ctx.http, CreateInteractionResponse::Message(builder)).await);
+        return ;
+    }
+    let (start, end) = (start.flatten(), end.flatten());
+    let playlist_start = match options.get("playlist_start") {
+        Some(ResolvedValue::Integer(playlist_start)) => *playlist_start as usize,
+        _ => 1
+    };
+    let playlist_end = match options.get("playlist_end") {
+        Some(ResolvedValue::Integer(playlist_end)) => Some(*playlist_end as usize),
         _ => None
     };
+    let reverse = match options.get("reverse") {
+        Some(ResolvedValue::Boolean(reverse)) => *reverse,
+        _ => false
+    };
+    let shuffle = match options.get("shuffle") {
+        Some(ResolvedValue::Boolean(shuffle)) => *shuffle,
+        _ => false
+    };
+    if playlist_end.map(|playlist_end| playlist_end < playlist_start).unwrap_or(false) {
+        let builder = CreateInteractionResponseMessage::new()
+            .content(match command.locale.as_str() {
+                "ru" => "Конец диапазона плейлиста должен быть не раньше начала!",
+                _ => "The end of the playlist range must not be before the start!"
+            })
+            .ephemeral(true);
+        check_msg(command.create_response(&ctx.http, CreateInteractionResponse::Message(builder)).await);
+        return ;
+    }
     check_msg(command.defer(&ctx.http).await);
 
     initialize_guild_player(&ctx, command.guild_id.unwrap()).await;
@@ -40,89 +86,14 @@ pub async fn run(ctx: Context, command: CommandInteraction) {
     let player = player.get(&command.guild_id.unwrap().get()).unwrap().clone();
     let mut last_id = player.playlist_sync_and_last_id.lock().await;
 
-    let manager = songbird::get(&ctx).await.expect("Songbird Voice client placed in at initialisation.").clone();
-    
-    match manager.get(command.guild_id.unwrap()) {
-        Some(handler) => {
-            let mut handler = handler.lock().await;
-            match handler.current_channel() {
-                Some(_) => {},
-                None => {
-                    let (channel, message) = get_voice_channel(&ctx, &command).await;
-                    if channel.is_none() {
-                        let builder = EditInteractionResponse::new()
-                            .content(message.unwrap());
-                        check_msg(command.edit_response(&ctx.http, builder).await);
-                        return;
-                    };
-                    let channel = channel.unwrap();
-                    if let Ok(_) = handler.join(channel).await{
-                        let text = match command.locale.as_str() {
-                            "ru" => format!("Подключился к \"<#{}>\"", channel.get()),
-                            _ => format!("Connected to \"<#{}>\"", channel.get())
-                        };
-                        let builder = EditInteractionResponse::new()
-                            .content(text);
-                        check_msg(command.edit_response(&ctx.http, builder).await);
-                    };
-                    handler.set_bitrate(Bitrate::BitsPerSecond(320000));
-                }
-            }
-        },
-        None => {
-            let (channel, message) = get_voice_channel(&ctx, &command).await;
-            if channel.is_none() {
-                let builder = EditInteractionResponse::new()
-                    .content(message.unwrap());
-                check_msg(command.edit_response(&ctx.http, builder).await);
-                return;
-            };
-
-            let channel = channel.unwrap();
-            match manager.join(command.guild_id.unwrap(), channel).await {
-                Ok(handler) => {
-                    let text = match command.locale.as_str() {
-                        "ru" => format!("Подключился к \"<#{}>\"", channel.get()),
-                        _ => format!("Connected to \"<#{}>\"", channel.get())
-                    };
-                    let builder = EditInteractionResponse::new().content(text);
-                    check_msg(command.edit_response(&ctx.http, builder).await);
-                    let mut handler = handler.lock().await;
-                    let _ = handler.deafen(true).await;
-                    handler.set_bitrate(Bitrate::BitsPerSecond(256000));
-                    handler.remove_all_global_events();
-                    handler.add_global_event(
-                        Event::Track(TrackEvent::End),
-                        TrackEndNotifier {
-                            guild_id: command.guild_id.unwrap(),
-                            ctx_clone: ctx.clone()
-                        }
-                    );
-                    handler.add_global_event(
-                        Event::Core(CoreEvent::DriverConnect),
-                        TrackEndNotifier {
-                            guild_id: command.guild_id.unwrap(),
-                            ctx_clone: ctx.clone()
-                        }
-                    )
-                },
-                Err(e) => {
-                    println!("Error joining {:?}", e);
-                    let text = match command.locale.as_str() {
-                        "ru" => "Ошибка подключения!",
-                        _ => "Connection error!"
-                    };
-                    let builder = EditInteractionResponse::new().content(text);
-                    check_msg(command.edit_response(&ctx.http, builder).await);
-                    return;
-                }
-            }
-        }
+    if !join_user_channel(&ctx, &command).await {
+        return;
     }
 
     let mut playlist: PlaylistType = PlaylistType::None;
+    let mut podcast = None;
     *last_id+=1;
-    let not_query = url.starts_with("http://") || url.starts_with("https://") || url.starts_with("ftp://");
+    let not_query = source_registry().is_url(url);
     let track = if not_query {
         match parse_url(url, *last_id).await {
             ParsedDataType::Track(track) => Some(track),
@@ -134,15 +105,15 @@ pub async fn run(ctx: Context, command: CommandInteraction) {
                 playlist = PlaylistType::Vk(parsed_playlist);
                 Some(track)
             },
+            ParsedDataType::Podcast((feed, track)) => {
+                podcast = Some(feed);
+                Some(track)
+            },
             ParsedDataType::None => None
         }
     } else {
-        println!("{:?}", search_type);
-        match search_type {
-            Some(1) => search_track_yt(url, *last_id, SearchType::SoundCloud).await,
-            Some(2) => search_track_vk(url, *last_id).await,
-            _ => search_track_yt(url, *last_id, SearchType::Youtube).await
-        }
+        println!("{:?}", search_source);
+        source_registry().search(url, search_source, *last_id).await
     };
     if track.is_none() {
         let builder = EditInteractionResponse::new().content(match command.locale.as_str() {
@@ -152,157 +123,147 @@ pub async fn run(ctx: Context, command: CommandInteraction) {
         check_msg(command.edit_response(&ctx.http, builder).await);
         return ;
     }
-    let track = track.unwrap();
-    
-    let builder = match &playlist {
+    let mut track = track.unwrap();
+    track.requester = Some(command.user.id.get());
+
+    let (mut entries, playlist_info) = match playlist {
         PlaylistType::YtDl(playlist) => {
-            let locale = command.locale.as_str();
-            let embed = CreateEmbed::new()
-                .color(14441063)
-                .title(match locale {
-                    "ru" => "Добавлен плейлист:",
-                    _ => "Added playlist:"
-                });
-            
-            EditInteractionResponse::new().embed(embed)
+            let entries = std::iter::once(QueueEntry::Track(track.clone()))
+                .chain(playlist.tracks.into_iter().skip(1).map(|entry| QueueEntry::Track(Track::from_flat(entry, 0))))
+                .collect();
+            (entries, Some((playlist.title, playlist.webpage_url, playlist.author.name, playlist.thumbnail, 0)))
         },
         PlaylistType::Vk(playlist) => {
-            let locale = command.locale.as_str();
-            let embed = CreateEmbed::new()
-                .color(14441063)
-                .title(match locale {
-                    "ru" => "Добавлен плейлист:",
-                    _ => "Added playlist:"
+            let entries = std::iter::once(QueueEntry::Track(track.clone()))
+                .chain(playlist.tracks.into_iter().map(|track| QueueEntry::Track(Track::from_vk(track, 0))))
+                .collect();
+            (entries, Some((playlist.title, playlist.webpage_url, playlist.author.name, playlist.thumbnail, playlist.failed)))
+        },
+        PlaylistType::None => (Vec::new(), None)
+    };
+    if playlist_info.is_some() {
+        entries = select_playlist_entries(entries, playlist_start, playlist_end, reverse, shuffle, limit);
+        track = match entries.is_empty() {
+            true => {
+                let builder = EditInteractionResponse::new().content(match command.locale.as_str() {
+                    "ru" => "В указанном диапазоне плейлиста нет треков!",
+                    _ => "There are no tracks in the specified playlist range!"
                 });
-            
-            EditInteractionResponse::new().embed(embed)
+                check_msg(command.edit_response(&ctx.http, builder).await);
+                return ;
+            },
+            false => match entries.remove(0) {
+                QueueEntry::Track(mut first) => {
+                    first.id = *last_id;
+                    first.requester = Some(command.user.id.get());
+                    first
+                },
+                _ => track
+            }
+        };
+    }
+
+    if start.is_some() {
+        track.start = start;
+    }
+    if end.is_some() {
+        track.end = end;
+    }
+    if let (Some(start), Some(end)) = (track.start, track.end) {
+        if end <= start {
+            let builder = EditInteractionResponse::new().content(match command.locale.as_str() {
+                "ru" => "Конец фрагмента должен быть позже начала!",
+                _ => "The end of the fragment must be after the start!"
+            });
+            check_msg(command.edit_response(&ctx.http, builder).await);
+            return ;
         }
-        PlaylistType::None => {
-            let locale = command.locale.as_str();
-            let embed = track.get_embed(command.locale.as_str())
-                .color(14441063)
-                .title(match locale {
-                    "ru" => "Добавлен трек:",
-                    _ => "Added track:"
-                });
+    }
+
+    let builder = match &playlist_info {
+        Some((title, webpage_url, author, thumbnail, failed)) => {
+            let embed = get_playlist_embed(command.locale.as_str(), title.as_ref(), webpage_url, author.as_ref(), thumbnail.as_ref(), entries.len() + 1, *failed);
             EditInteractionResponse::new().embed(embed)
+        },
+        None => match &podcast {
+            Some(feed) => get_episode_response(feed, &track, command.locale.as_str()),
+            None => {
+                let locale = command.locale.as_str();
+                let embed = track.get_embed(command.locale.as_str())
+                    .color(14441063)
+                    .title(match locale {
+                        "ru" => "Добавлен трек:",
+                        _ => "Added track:"
+                    });
+                EditInteractionResponse::new().embed(embed)
+            }
         }
     };
-    check_msg(command.edit_response(&ctx.http, builder).await);
+    match command.edit_response(&ctx.http, builder).await {
+        Ok(message) => if let Some(feed) = &podcast {
+            store_picker(message.id.get(), feed);
+        },
+        Err(why) => println!("Error sending message: {:?}", why)
+    }
 
     println!("{:#?}", track);
-    {
-        let mut player_playlist = player.playlist.write().await;
-        let mut state = player.state.write().await;
-        match *state {
-            PlayerState::Ended => {
-                let mut last_updated_position = player.position.write().await;
-                player_playlist.current = Some(track.clone());
-                *state = PlayerState::Playing;
-                *last_updated_position = Position::default();
-
-                let mut child = track.get_child(&ctx, &command.guild_id.unwrap().get(), 0.0).await.unwrap();
-                let stdin = child.stdin.take().unwrap();
-                let data = songbird::input::Input::from(songbird::input::ChildContainer::from(child));
-
-                if let Some(handler_lock) = manager.get(command.guild_id.unwrap()) {
-                    let mut handler = handler_lock.lock().await;
-                    let mut ffmpeg = player.ffmpeg.write().await;
-                    let mut player_handler = player.player.write().await;
+    enqueue_track(&ctx, &player, track).await;
+    drop(last_id);
 
-                    let _ = ffmpeg.insert(stdin);
-
-                    let handle = handler.play_only_input(data);
-                    let _ = player_handler.insert(handle);
-                }
-            },
-            _ => {
-                player_playlist.tracks.push_back(track);
-            }
+    if let Some((_, source, _, _, _)) = playlist_info {
+        if !entries.is_empty() {
+            spawn_import_job_ctx(&ctx, &command, player.clone(), source, entries).await;
         }
     }
+}
 
-    match &playlist {
-        PlaylistType::YtDl(playlist) => {
-            for url in playlist.tracks.iter().skip(1).take(limit-1) {
-                match youtube_dl::YoutubeDl::new(url).flat_playlist(true).socket_timeout("15").run_raw_async().await {
-                    Ok(src) => {
-                        *last_id+=1;
-                        match parse_track_yt(src, *last_id).await {
-                            Some(track) => {
-                                let mut player_playlist = player.playlist.write().await;
-                                let mut state = player.state.write().await;
-                                match *state {
-                                    PlayerState::Ended => {
-                                        let mut last_updated_position = player.position.write().await;
-                                        player_playlist.current = Some(track.clone());
-                                        *state = PlayerState::Playing;
-                                        *last_updated_position = Position::default();
-                        
-                                        let mut child = track.get_child(&ctx, &command.guild_id.unwrap().get(), 0.0).await.unwrap();
-                                        let stdin = child.stdin.take().unwrap();
-                                        let data = songbird::input::Input::from(songbird::input::ChildContainer::from(child));
-                        
-                                        if let Some(handler_lock) = manager.get(command.guild_id.unwrap()) {
-                                            let mut handler = handler_lock.lock().await;
-                                            let mut ffmpeg = player.ffmpeg.write().await;
-                                            let mut player_handler = player.player.write().await;
-                        
-                                            let _ = ffmpeg.insert(stdin);
-                        
-                                            let handle = handler.play_only_input(data);
-                                            let _ = player_handler.insert(handle);
-                                        }
-                                    },
-                                    _ => player_playlist.tracks.push_back(track)
-                                }
-                            },
-                            None => {}
-                        };
-                    },
-                    Err(_) => {}
-                };
-                sleep(Duration::from_millis(200)).await;
-            }
-        },
-        PlaylistType::Vk(playlist) => {
-            for track in playlist.tracks.iter().skip(1).take(limit-1) {
-                *last_id+=1;
-                let track = Track::from_vk(track.clone(), *last_id);
-                let mut player_playlist = player.playlist.write().await;
-                let mut state = player.state.write().await;
-                match *state {
-                    PlayerState::Ended => {
-                        let mut last_updated_position = player.position.write().await;
-                        player_playlist.current = Some(track.clone());
-                        *state = PlayerState::Playing;
-                        *last_updated_position = Position::default();
-        
-                        let mut child = track.get_child(&ctx, &command.guild_id.unwrap().get(), 0.0).await.unwrap();
-                        let stdin = child.stdin.take().unwrap();
-                        let data = songbird::input::Input::from(songbird::input::ChildContainer::from(child));
-        
-                        if let Some(handler_lock) = manager.get(command.guild_id.unwrap()) {
-                            let mut handler = handler_lock.lock().await;
-                            let mut ffmpeg = player.ffmpeg.write().await;
-                            let mut player_handler = player.player.write().await;
-        
-                            let _ = ffmpeg.insert(stdin);
-        
-                            let handle = handler.play_only_input(data);
-                            let _ = player_handler.insert(handle);
-                        }
-                    },
-                    _ => player_playlist.tracks.push_back(track)
-                }
-            }
-        },
-        _ => {}
+fn select_playlist_entries<T>(entries: Vec<T>, start: usize, end: Option<usize>, reverse: bool, shuffle: bool, limit: usize) -> Vec<T> {
+    let end = end.unwrap_or(entries.len()).min(entries.len());
+    let mut entries: Vec<T> = entries.into_iter().take(end).skip(start.saturating_sub(1)).collect();
+    if reverse {
+        entries.reverse();
+    }
+    if shuffle {
+        entries.shuffle(&mut rand::thread_rng());
     }
-    
+    entries.truncate(limit);
+    entries
+}
+
+fn get_playlist_embed(locale: &str, title: Option<&String>, webpage_url: &str, author: Option<&String>, thumbnail: Option<&String>, added: usize, failed: usize) -> CreateEmbed {
+    let mut embed = CreateEmbed::new()
+        .color(14441063)
+        .title(match locale {
+            "ru" => "Добавлен плейлист:",
+            _ => "Added playlist:"
+        })
+        .description(format!("[{}]({})", title.cloned().unwrap_or(webpage_url.to_string()), webpage_url))
+        .field(match locale {
+            "ru" => "Добавлено треков",
+            _ => "Tracks added"
+        }, added.to_string(), true);
+    if failed > 0 {
+        embed = embed.field(match locale {
+            "ru" => "Не удалось загрузить",
+            _ => "Failed to load"
+        }, failed.to_string(), true);
+    }
+    if let Some(author) = author {
+        embed = embed.author(CreateEmbedAuthor::new(author));
+    }
+    if let Some(thumbnail) = thumbnail {
+        embed = embed.thumbnail(thumbnail);
+    }
+    embed
 }
 
 pub fn register() -> CreateCommand {
+    let mut search = CreateCommandOption::new(CommandOptionType::String, "search", format!("Search source(default \"{}\")", source_registry().get(DEFAULT_SEARCH_SOURCE).map(|resolver| resolver.display_name()).unwrap_or(DEFAULT_SEARCH_SOURCE)))
+        .description_localized("ru", format!("Источник поиска(по умолчанию \"{}\")", source_registry().get(DEFAULT_SEARCH_SOURCE).map(|resolver| resolver.display_name()).unwrap_or(DEFAULT_SEARCH_SOURCE)))
+        .required(false);
+    for resolver in source_registry().searchable() {
+        search = search.add_string_choice(resolver.display_name(), resolver.name());
+    }
     CreateCommand::new("play")
         .description("Plays/adds track(s) to the playlist")
         .description_localized("ru", "Проигрывает/добавляет в плейлист трек(и)")
@@ -310,18 +271,39 @@ pub fn register() -> CreateCommand {
             CreateCommandOption::new(CommandOptionType::String, "url", "URL or search query")
                 .description_localized("ru", "Ссылка или запрос")
                 .required(true)
+                .set_autocomplete(true)
         ).add_option(
             CreateCommandOption::new(CommandOptionType::Integer, "limit", "Limit of tracks to add(default 25)")
                 .description_localized("ru", "Лимит добавляемых треков(по умолчанию 25)")
                 .min_int_value(1)
-                .max_int_value(35)
+                .max_int_value(PLAYLIST_LIMIT)
+                .required(false)
+        ).add_option(search)
+        .add_option(
+            CreateCommandOption::new(CommandOptionType::String, "start", "Start position( 90 | 1:30 | 0:01:30 )")
+                .description_localized("ru", "Начало воспроизведения( 90 | 1:30 | 0:01:30 )")
+                .required(false)
+        ).add_option(
+            CreateCommandOption::new(CommandOptionType::String, "end", "End position( 90 | 1:30 | 0:01:30 )")
+                .description_localized("ru", "Конец воспроизведения( 90 | 1:30 | 0:01:30 )")
                 .required(false)
         ).add_option(
-            CreateCommandOption::new(CommandOptionType::Integer, "search", "Search type(default \"YouTube)\"")
-                .description_localized("ru", "Тип поиска(по умолчанию \"YouTube)\"")
-                .add_int_choice("YouTube", 0)
-                .add_int_choice("SoundCloud", 1)
-                .add_int_choice("VK", 2)
-                .required(false),
+            CreateCommandOption::new(CommandOptionType::Integer, "playlist_start", "First playlist item to add(default 1)")
+                .description_localized("ru", "Первый добавляемый элемент плейлиста(по умолчанию 1)")
+                .min_int_value(1)
+                .required(false)
+        ).add_option(
+            CreateCommandOption::new(CommandOptionType::Integer, "playlist_end", "Last playlist item to add")
+                .description_localized("ru", "Последний добавляемый элемент плейлиста")
+                .min_int_value(1)
+                .required(false)
+        ).add_option(
+            CreateCommandOption::new(CommandOptionType::Boolean, "reverse", "Add playlist items in reverse order")
+                .description_localized("ru", "Добавить элементы плейлиста в обратном порядке")
+                .required(false)
+        ).add_option(
+            CreateCommandOption::new(CommandOptionType::Boolean, "shuffle", "Shuffle playlist items before adding")
+                .description_localized("ru", "Перемешать элементы плейлиста перед добавлением")
+                .required(false)
         ).dm_permission(false)
 }
@@ -0,0 +1,298 @@
+use std::collections::HashMap;
+
+use serenity::all::ResolvedValue;
+use serenity::builder::{CreateCommand, CreateCommandOption, CreateEmbed, EditInteractionResponse};
+use serenity::client::Context;
+use serenity::model::application::{CommandOptionType, CommandInteraction};
+
+use crate::bot::utils::parser::{parse_url, ParsedDataType};
+use crate::bot::utils::player::{enqueue_track, initialize_guild_player, PlayerData, PlayerDataBase};
+use crate::bot::utils::resolver::source_registry;
+use crate::bot::utils::saved_playlist::{add_to_playlist, delete_playlist, list_playlists, load_playlist, save_playlist, PLAYLIST_TRACKS_LIMIT};
+use crate::bot::utils::track::Track;
+use crate::bot::utils::{check_msg, join_user_channel};
+
+pub async fn run(ctx: Context, command: CommandInteraction) {
+    check_msg(command.defer_ephemeral(&ctx.http).await);
+    initialize_guild_player(&ctx, command.guild_id.unwrap()).await;
+
+    let locale = command.locale.as_str();
+    let owner = command.user.id.get();
+    let pool = {
+        let data_read = ctx.data.read().await;
+        let conn = data_read.get::<PlayerDataBase>().expect("Expected PlayerDataBase in TypeMap.").clone();
+        conn.get()
+    };
+    let mut conn = match pool {
+        Ok(conn) => conn,
+        Err(_) => {
+            let builder = EditInteractionResponse::new().content(match locale {
+                "ru" => "Не удалось подключиться к базе данных!",
+                _ => "Failed to connect to the database!"
+            });
+            check_msg(command.edit_response(&ctx.http, builder).await);
+            return ;
+        }
+    };
+
+    let player = ctx.data.read().await.get::<PlayerData>().unwrap().clone();
+    let player = player.read().await.clone();
+    let player = player.get(&command.guild_id.unwrap().get()).unwrap().clone();
+
+    let builder = match command.data.options().first() {
+        Some(subcommand) => match (subcommand.name, &subcommand.value) {
+            ("save", ResolvedValue::SubCommand(options)) => {
+                let options: HashMap<_, _> = options.iter().map(|param| (param.name, &param.value)).collect();
+                let name = match options.get("name") {
+                    Some(ResolvedValue::String(name)) if !name.trim().is_empty() => name.trim(),
+                    _ => {
+                        check_msg(command.edit_response(&ctx.http, get_invalid_name_response(locale)).await);
+                        return ;
+                    }
+                };
+                let tracks: Vec<Track> = {
+                    let player_playlist = player.playlist.read().await;
+                    player_playlist.current.iter().chain(player_playlist.tracks.iter()).cloned().collect()
+                };
+                if tracks.is_empty() {
+                    EditInteractionResponse::new().content(match locale {
+                        "ru" => "Очередь пуста!",
+                        _ => "The queue is empty!"
+                    })
+                } else {
+                    match save_playlist(&mut conn, owner, name, &tracks) {
+                        Ok(count) => EditInteractionResponse::new().content(match locale {
+                            "ru" => format!("Плейлист **{}** сохранён, треков: {}.", name, count),
+                            _ => format!("Saved playlist **{}** with {} tracks.", name, count)
+                        }),
+                        Err(_) => EditInteractionResponse::new().content(match locale {
+                            "ru" => "Не удалось сохранить плейлист!",
+                            _ => "Failed to save the playlist!"
+                        })
+                    }
+                }
+            },
+            ("load", ResolvedValue::SubCommand(options)) => {
+                let options: HashMap<_, _> = options.iter().map(|param| (param.name, &param.value)).collect();
+                let name = match options.get("name") {
+                    Some(ResolvedValue::String(name)) if !name.trim().is_empty() => name.trim(),
+                    _ => {
+                        check_msg(command.edit_response(&ctx.http, get_invalid_name_response(locale)).await);
+                        return ;
+                    }
+                };
+                let shuffle = match options.get("shuffle") {
+                    Some(ResolvedValue::Boolean(shuffle)) => *shuffle,
+                    _ => false
+                };
+                match load_playlist(&mut conn, owner, name) {
+                    Ok(Some(tracks)) if !tracks.is_empty() => {
+                        if !join_user_channel(&ctx, &command).await {
+                            return;
+                        }
+                        let mut tracks = tracks;
+                        if shuffle {
+                            use rand::seq::SliceRandom;
+                            tracks.shuffle(&mut rand::thread_rng());
+                        }
+                        let count = tracks.len();
+                        for mut track in tracks {
+                            track.id = {
+                                let mut last_id = player.playlist_sync_and_last_id.lock().await;
+                                *last_id+=1;
+                                *last_id
+                            };
+                            track.requester = Some(owner);
+                            enqueue_track(&ctx, &player, track).await;
+                        }
+                        EditInteractionResponse::new().content(match locale {
+                            "ru" => format!("Добавлено треков из плейлиста **{}**: {}.", name, count),
+                            _ => format!("Added {} tracks from playlist **{}**.", count, name)
+                        })
+                    },
+                    Ok(_) => EditInteractionResponse::new().content(match locale {
+                        "ru" => format!("Плейлист **{}** не найден или пуст!", name),
+                        _ => format!("Playlist **{}** was not found or is empty!", name)
+                    }),
+                    Err(_) => EditInteractionResponse::new().content(match locale {
+                        "ru" => "Не удалось загрузить плейлист!",
+                        _ => "Failed to load the playlist!"
+                    })
+                }
+            },
+            ("list", _) => match list_playlists(&mut conn, owner) {
+                Ok(playlists) if !playlists.is_empty() => {
+                    let description = playlists.iter()
+                        .map(|(playlist, count)| match locale {
+                            "ru" => format!("**{}** — треков: {}", playlist.name, count),
+                            _ => format!("**{}** — {} tracks", playlist.name, count)
+                        })
+                        .collect::<Vec<String>>()
+                        .join("\n");
+                    let embed = CreateEmbed::new()
+                        .color(14441063)
+                        .title(match locale {
+                            "ru" => "Ваши плейлисты:",
+                            _ => "Your playlists:"
+                        })
+                        .description(description);
+                    EditInteractionResponse::new().embed(embed)
+                },
+                Ok(_) => EditInteractionResponse::new().content(match locale {
+                    "ru" => "У вас нет сохранённых плейлистов.",
+                    _ => "You have no saved playlists."
+                }),
+                Err(_) => EditInteractionResponse::new().content(match locale {
+                    "ru" => "Не удалось получить плейлисты!",
+                    _ => "Failed to get playlists!"
+                })
+            },
+            ("delete", ResolvedValue::SubCommand(options)) => {
+                let options: HashMap<_, _> = options.iter().map(|param| (param.name, &param.value)).collect();
+                let name = match options.get("name") {
+                    Some(ResolvedValue::String(name)) if !name.trim().is_empty() => name.trim(),
+                    _ => {
+                        check_msg(command.edit_response(&ctx.http, get_invalid_name_response(locale)).await);
+                        return ;
+                    }
+                };
+                match delete_playlist(&mut conn, owner, name) {
+                    Ok(true) => EditInteractionResponse::new().content(match locale {
+                        "ru" => format!("Плейлист **{}** удалён.", name),
+                        _ => format!("Deleted playlist **{}**.", name)
+                    }),
+                    Ok(false) => EditInteractionResponse::new().content(match locale {
+                        "ru" => format!("Плейлист **{}** не найден!", name),
+                        _ => format!("Playlist **{}** was not found!", name)
+                    }),
+                    Err(_) => EditInteractionResponse::new().content(match locale {
+                        "ru" => "Не удалось удалить плейлист!",
+                        _ => "Failed to delete the playlist!"
+                    })
+                }
+            },
+            ("add", ResolvedValue::SubCommand(options)) => {
+                let options: HashMap<_, _> = options.iter().map(|param| (param.name, &param.value)).collect();
+                let name = match options.get("name") {
+                    Some(ResolvedValue::String(name)) if !name.trim().is_empty() => name.trim(),
+                    _ => {
+                        check_msg(command.edit_response(&ctx.http, get_invalid_name_response(locale)).await);
+                        return ;
+                    }
+                };
+                let url = match options.get("url") {
+                    Some(ResolvedValue::String(url)) => *url,
+                    _ => return
+                };
+                let tracks = resolve_tracks(url).await;
+                if tracks.is_empty() {
+                    EditInteractionResponse::new().content(match locale {
+                        "ru" => "Произошла ошибка во время обработки!",
+                        _ => "An error occurred while processing!"
+                    })
+                } else {
+                    match add_to_playlist(&mut conn, owner, name, &tracks) {
+                        Ok(count) => EditInteractionResponse::new().content(match locale {
+                            "ru" => format!("Добавлено треков в плейлист **{}**: {}.", name, count),
+                            _ => format!("Added {} tracks to playlist **{}**.", count, name)
+                        }),
+                        Err(_) => EditInteractionResponse::new().content(match locale {
+                            "ru" => "Не удалось сохранить плейлист!",
+                            _ => "Failed to save the playlist!"
+                        })
+                    }
+                }
+            },
+            _ => return
+        },
+        None => return
+    };
+    check_msg(command.edit_response(&ctx.http, builder).await);
+}
+
+fn get_invalid_name_response(locale: &str) -> EditInteractionResponse {
+    EditInteractionResponse::new().content(match locale {
+        "ru" => "Название плейлиста не может быть пустым!",
+        _ => "The playlist name must not be empty!"
+    })
+}
+
+async fn resolve_tracks(url: &str) -> Vec<Track> {
+    if !source_registry().is_url(url) {
+        return source_registry().search(url, None, 0).await.into_iter().collect();
+    }
+    match parse_url(url, 0).await {
+        ParsedDataType::Track(track) | ParsedDataType::Podcast((_, track)) => vec![track],
+        ParsedDataType::VkPlaylist((playlist, track)) => std::iter::once(track)
+            .chain(playlist.tracks.into_iter().map(|track| Track::from_vk(track, 0)))
+            .take(PLAYLIST_TRACKS_LIMIT)
+            .collect(),
+        ParsedDataType::YtDlPlaylist((playlist, track)) => std::iter::once(track)
+            .chain(playlist.tracks.into_iter().skip(1).map(|entry| Track::from_flat(entry, 0)))
+            .take(PLAYLIST_TRACKS_LIMIT)
+            .collect(),
+        ParsedDataType::None => Vec::new()
+    }
+}
+
+pub fn register() -> CreateCommand {
+    CreateCommand::new("playlist")
+        .description("Saved playlists")
+        .description_localized("ru", "Сохранённые плейлисты")
+        .add_option(
+            CreateCommandOption::new(CommandOptionType::SubCommand, "save", "Save the current queue as a playlist")
+                .description_localized("ru", "Сохранить текущую очередь как плейлист")
+                .add_sub_option(
+                    CreateCommandOption::new(CommandOptionType::String, "name", "Playlist name")
+                        .description_localized("ru", "Название плейлиста")
+                        .max_length(100)
+                        .set_autocomplete(true)
+                        .required(true)
+                )
+        )
+        .add_option(
+            CreateCommandOption::new(CommandOptionType::SubCommand, "load", "Add a saved playlist to the queue")
+                .description_localized("ru", "Добавить сохранённый плейлист в очередь")
+                .add_sub_option(
+                    CreateCommandOption::new(CommandOptionType::String, "name", "Playlist name")
+                        .description_localized("ru", "Название плейлиста")
+                        .set_autocomplete(true)
+                        .required(true)
+                )
+                .add_sub_option(
+                    CreateCommandOption::new(CommandOptionType::Boolean, "shuffle", "Shuffle the tracks")
+                        .description_localized("ru", "Перемешать треки")
+                )
+        )
+        .add_option(
+            CreateCommandOption::new(CommandOptionType::SubCommand, "list", "List your saved playlists")
+                .description_localized("ru", "Список ваших сохранённых плейлистов")
+        )
+        .add_option(
+            CreateCommandOption::new(CommandOptionType::SubCommand, "delete", "Delete a saved playlist")
+                .description_localized("ru", "Удалить сохранённый плейлист")
+                .add_sub_option(
+                    CreateCommandOption::new(CommandOptionType::String, "name", "Playlist name")
+                        .description_localized("ru", "Название плейлиста")
+                        .set_autocomplete(true)
+                        .required(true)
+                )
+        )
+        .add_option(
+            CreateCommandOption::new(CommandOptionType::SubCommand, "add", "Add a track or playlist to a saved playlist")
+                .description_localized("ru", "Добавить трек или плейлист в сохранённый плейлист")
+                .add_sub_option(
+                    CreateCommandOption::new(CommandOptionType::String, "name", "Playlist name")
+                        .description_localized("ru", "Название плейлиста")
+                        .max_length(100)
+                        .set_autocomplete(true)
+                        .required(true)
+                )
+                .add_sub_option(
+                    CreateCommandOption::new(CommandOptionType::String, "url", "URL or search query")
+                        .description_localized("ru", "Ссылка или запрос")
+                        .required(true)
+                )
+        )
+        .dm_permission(false)
+}
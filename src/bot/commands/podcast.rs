@@ -0,0 +1,220 @@
+use std::collections::HashMap;
+
+use serenity::all::{Permissions, ResolvedValue};
+use serenity::builder::{CreateCommand, CreateCommandOption, CreateEmbed, EditInteractionResponse};
+use serenity::client::Context;
+use serenity::model::application::{CommandOptionType, CommandInteraction};
+
+use crate::bot::utils::player::{enqueue_track, initialize_guild_player, PlayerData, PlayerDataBase};
+use crate::bot::utils::podcast::{add_subscription, fetch_feed, get_episode_response, get_episode_track, get_subscription, list_subscriptions, remove_subscription, store_picker};
+use crate::bot::utils::{check_msg, join_user_channel};
+
+const TITLE_LIMIT: usize = 100;
+
+pub async fn run(ctx: Context, command: CommandInteraction) {
+    let locale = command.locale.as_str();
+    let guild_id = command.guild_id.unwrap();
+    let (subcommand, options) = match command.data.options().first() {
+        Some(subcommand) => match &subcommand.value {
+            ResolvedValue::SubCommand(options) => (subcommand.name, options.iter().map(|param| (param.name, param.value.clone())).collect::<HashMap<_, _>>()),
+            _ => return
+        },
+        None => return
+    };
+    match subcommand {
+        "play" => check_msg(command.defer(&ctx.http).await),
+        _ => check_msg(command.defer_ephemeral(&ctx.http).await)
+    }
+
+    let pool = {
+        let data_read = ctx.data.read().await;
+        let conn = data_read.get::<PlayerDataBase>().expect("Expected PlayerDataBase in TypeMap.").clone();
+        conn.get()
+    };
+    let mut conn = match pool {
+        Ok(conn) => conn,
+        Err(_) => {
+            let builder = EditInteractionResponse::new().content(match locale {
+                "ru" => "Не удалось подключиться к базе данных!",
+                _ => "Failed to connect to the database!"
+            });
+            check_msg(command.edit_response(&ctx.http, builder).await);
+            return ;
+        }
+    };
+    let allowed = command.member.as_ref()
+        .and_then(|member| member.permissions)
+        .map(|permissions| permissions.contains(Permissions::MANAGE_GUILD))
+        .unwrap_or(false);
+
+    let builder = match subcommand {
+        "play" => {
+            let podcast = match options.get("podcast") {
+                Some(ResolvedValue::String(podcast)) => podcast.trim(),
+                _ => return
+            };
+            let url = match podcast.parse::<i32>().ok().and_then(|id| get_subscription(&mut conn, guild_id.get(), id).ok().flatten()) {
+                Some(subscription) => subscription.feed_url,
+                None => podcast.to_string()
+            };
+            initialize_guild_player(&ctx, guild_id).await;
+            let player = ctx.data.read().await.get::<PlayerData>().unwrap().clone();
+            let player = player.read().await.clone();
+            let player = player.get(&guild_id.get()).unwrap().clone();
+            let mut last_id = player.playlist_sync_and_last_id.lock().await;
+
+            if !join_user_channel(&ctx, &command).await {
+                return;
+            }
+
+            let feed = match fetch_feed(&url).await {
+                Some(feed) => feed,
+                None => {
+                    let builder = EditInteractionResponse::new().content(match locale {
+                        "ru" => "Не удалось загрузить ленту подкаста!",
+                        _ => "Failed to load the podcast feed!"
+                    });
+                    check_msg(command.edit_response(&ctx.http, builder).await);
+                    return ;
+                }
+            };
+            *last_id+=1;
+            let mut track = get_episode_track(&feed.episodes[0], *last_id).await;
+            track.requester = Some(command.user.id.get());
+            let builder = get_episode_response(&feed, &track, locale);
+            match command.edit_response(&ctx.http, builder).await {
+                Ok(message) => store_picker(message.id.get(), &feed),
+                Err(why) => println!("Error sending message: {:?}", why)
+            }
+            enqueue_track(&ctx, &player, track).await;
+            return ;
+        },
+        "subscribe" => {
+            let url = match options.get("url") {
+                Some(ResolvedValue::String(url)) => url.trim(),
+                _ => return
+            };
+            if !allowed {
+                EditInteractionResponse::new().content(match locale {
+                    "ru" => "Для изменения подписок на подкасты нужно право «Управлять сервером»!",
+                    _ => "You need the Manage Server permission to change the podcast subscriptions!"
+                })
+            } else {
+                match fetch_feed(url).await {
+                    Some(feed) => {
+                        let title: String = feed.title.unwrap_or(url.to_string()).chars().take(TITLE_LIMIT).collect();
+                        match add_subscription(&mut conn, guild_id.get(), &title, url) {
+                            Ok(_) => EditInteractionResponse::new().content(match locale {
+                                "ru" => format!("Подписка на подкаст **{}** сохранена.", title),
+                                _ => format!("Subscribed to podcast **{}**.", title)
+                            }),
+                            Err(_) => EditInteractionResponse::new().content(match locale {
+                                "ru" => "Не удалось сохранить подписку!",
+                                _ => "Failed to save the subscription!"
+                            })
+                        }
+                    },
+                    None => EditInteractionResponse::new().content(match locale {
+                        "ru" => "Не удалось загрузить ленту подкаста!",
+                        _ => "Failed to load the podcast feed!"
+                    })
+                }
+            }
+        },
+        "unsubscribe" => {
+            let podcast = match options.get("podcast") {
+                Some(ResolvedValue::String(podcast)) => podcast.trim(),
+                _ => return
+            };
+            if !allowed {
+                EditInteractionResponse::new().content(match locale {
+                    "ru" => "Для изменения подписок на подкасты нужно право «Управлять сервером»!",
+                    _ => "You need the Manage Server permission to change the podcast subscriptions!"
+                })
+            } else {
+                let subscription = podcast.parse::<i32>().ok()
+                    .and_then(|id| get_subscription(&mut conn, guild_id.get(), id).ok().flatten());
+                match subscription.map(|subscription| (remove_subscription(&mut conn, guild_id.get(), subscription.id), subscription.title)) {
+                    Some((Ok(_), title)) => EditInteractionResponse::new().content(match locale {
+                        "ru" => format!("Подписка на подкаст **{}** удалена.", title),
+                        _ => format!("Unsubscribed from podcast **{}**.", title)
+                    }),
+                    Some((Err(_), _)) => EditInteractionResponse::new().content(match locale {
+                        "ru" => "Не удалось удалить подписку!",
+                        _ => "Failed to remove the subscription!"
+                    }),
+                    None => EditInteractionResponse::new().content(match locale {
+                        "ru" => "Подписка не найдена!",
+                        _ => "Subscription was not found!"
+                    })
+                }
+            }
+        },
+        "list" => match list_subscriptions(&mut conn, guild_id.get()) {
+            Ok(subscriptions) if !subscriptions.is_empty() => {
+                let description = subscriptions.iter()
+                    .map(|subscription| format!("**{}** — {}", subscription.title, subscription.feed_url))
+                    .collect::<Vec<String>>()
+                    .join("\n");
+                let embed = CreateEmbed::new()
+                    .color(14441063)
+                    .title(match locale {
+                        "ru" => "Подкасты сервера:",
+                        _ => "Server podcasts:"
+                    })
+                    .description(description);
+                EditInteractionResponse::new().embed(embed)
+            },
+            Ok(_) => EditInteractionResponse::new().content(match locale {
+                "ru" => "На сервере нет подписок на подкасты.",
+                _ => "There are no podcast subscriptions on this server."
+            }),
+            Err(_) => EditInteractionResponse::new().content(match locale {
+                "ru" => "Не удалось получить подписки!",
+                _ => "Failed to get subscriptions!"
+            })
+        },
+        _ => return
+    };
+    check_msg(command.edit_response(&ctx.http, builder).await);
+}
+
+pub fn register() -> CreateCommand {
+    CreateCommand::new("podcast")
+        .description("Podcast feeds")
+        .description_localized("ru", "Ленты подкастов")
+        .add_option(
+            CreateCommandOption::new(CommandOptionType::SubCommand, "play", "Play the latest episode of a subscribed podcast or a feed URL")
+                .description_localized("ru", "Включить последний выпуск подкаста из подписок или по ссылке")
+                .add_sub_option(
+                    CreateCommandOption::new(CommandOptionType::String, "podcast", "Podcast or feed URL")
+                        .description_localized("ru", "Подкаст или ссылка на ленту")
+                        .set_autocomplete(true)
+                        .required(true)
+                )
+        )
+        .add_option(
+            CreateCommandOption::new(CommandOptionType::SubCommand, "subscribe", "Subscribe this server to a podcast feed")
+                .description_localized("ru", "Подписать сервер на ленту подкаста")
+                .add_sub_option(
+                    CreateCommandOption::new(CommandOptionType::String, "url", "RSS or Atom feed URL")
+                        .description_localized("ru", "Ссылка на RSS или Atom ленту")
+                        .required(true)
+                )
+        )
+        .add_option(
+            CreateCommandOption::new(CommandOptionType::SubCommand, "unsubscribe", "Remove a podcast subscription")
+                .description_localized("ru", "Удалить подписку на подкаст")
+                .add_sub_option(
+                    CreateCommandOption::new(CommandOptionType::String, "podcast", "Podcast")
+                        .description_localized("ru", "Подкаст")
+                        .set_autocomplete(true)
+                        .required(true)
+                )
+        )
+        .add_option(
+            CreateCommandOption::new(CommandOptionType::SubCommand, "list", "List podcast subscriptions")
+                .description_localized("ru", "Список подписок на подкасты")
+        )
+        .dm_permission(false)
+}
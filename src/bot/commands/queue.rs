@@ -0,0 +1,124 @@
+use std::collections::HashMap;
+
+use serenity::all::ResolvedValue;
+use serenity::builder::{CreateAttachment, CreateCommand, CreateCommandOption, EditInteractionResponse};
+use serenity::client::Context;
+use serenity::model::application::{CommandOptionType, CommandInteraction};
+
+use crate::bot::utils::jobs::spawn_import_job_ctx;
+use crate::bot::utils::player::{initialize_guild_player, PlayerData};
+use crate::bot::utils::queue_file::{export_queue, import_queue, QueueFormat, IMPORT_LIMIT};
+use crate::bot::utils::track::Track;
+use crate::bot::utils::{check_msg, join_user_channel};
+
+const IMPORT_MAX_SIZE: u32 = 1024 * 1024;
+
+pub async fn run(ctx: Context, command: CommandInteraction) {
+    initialize_guild_player(&ctx, command.guild_id.unwrap()).await;
+
+    let locale = command.locale.as_str();
+    let player = ctx.data.read().await.get::<PlayerData>().unwrap().clone();
+    let player = player.read().await.clone();
+    let player = player.get(&command.guild_id.unwrap().get()).unwrap().clone();
+
+    let builder = match command.data.options().first() {
+        Some(subcommand) => match (subcommand.name, &subcommand.value) {
+            ("export", ResolvedValue::SubCommand(options)) => {
+                check_msg(command.defer_ephemeral(&ctx.http).await);
+                let options: HashMap<_, _> = options.iter().map(|param| (param.name, &param.value)).collect();
+                let format = match options.get("format") {
+                    Some(ResolvedValue::String(format)) => QueueFormat::from_str(format).unwrap_or(QueueFormat::M3u),
+                    _ => QueueFormat::M3u
+                };
+                let tracks: Vec<Track> = {
+                    let player_playlist = player.playlist.read().await;
+                    player_playlist.current.iter().chain(player_playlist.tracks.iter()).cloned().collect()
+                };
+                if tracks.is_empty() {
+                    EditInteractionResponse::new().content(match locale {
+                        "ru" => "Очередь пуста!",
+                        _ => "The queue is empty!"
+                    })
+                } else {
+                    let data = export_queue(&tracks, format);
+                    let attachment = CreateAttachment::bytes(data.into_bytes(), format!("queue.{}", format.extension()));
+                    EditInteractionResponse::new()
+                        .content(match locale {
+                            "ru" => format!("Треков в очереди: {}", tracks.len()),
+                            _ => format!("Tracks in the queue: {}", tracks.len())
+                        })
+                        .new_attachment(attachment)
+                }
+            },
+            ("import", ResolvedValue::SubCommand(options)) => {
+                check_msg(command.defer(&ctx.http).await);
+                let options: HashMap<_, _> = options.iter().map(|param| (param.name, &param.value)).collect();
+                let attachment = match options.get("file") {
+                    Some(ResolvedValue::Attachment(attachment)) => *attachment,
+                    _ => return
+                };
+                let data = match attachment.size <= IMPORT_MAX_SIZE {
+                    true => attachment.download().await.ok().and_then(|data| String::from_utf8(data).ok()),
+                    false => None
+                };
+                let entries = data.map(|data| {
+                    let format = QueueFormat::from_filename(&attachment.filename).unwrap_or(QueueFormat::detect(&data));
+                    import_queue(&data, format)
+                });
+                match entries {
+                    Some(Ok(entries)) if !entries.is_empty() => {
+                        if !join_user_channel(&ctx, &command).await {
+                            return;
+                        }
+                        let entries: Vec<_> = entries.into_iter().take(IMPORT_LIMIT).collect();
+                        let builder = EditInteractionResponse::new().content(match locale {
+                            "ru" => format!("Импорт {} треков...", entries.len()),
+                            _ => format!("Importing {} tracks...", entries.len())
+                        });
+                        check_msg(command.edit_response(&ctx.http, builder).await);
+                        spawn_import_job_ctx(&ctx, &command, player.clone(), attachment.filename.clone(), entries).await;
+                        return;
+                    },
+                    Some(Ok(_)) => EditInteractionResponse::new().content(match locale {
+                        "ru" => "В файле не найдено треков!",
+                        _ => "No tracks were found in the file!"
+                    }),
+                    _ => EditInteractionResponse::new().content(match locale {
+                        "ru" => "Не удалось прочитать файл!",
+                        _ => "Failed to read the file!"
+                    })
+                }
+            },
+            _ => return
+        },
+        None => return
+    };
+    check_msg(command.edit_response(&ctx.http, builder).await);
+}
+
+pub fn register() -> CreateCommand {
+    CreateCommand::new("queue")
+        .description("Export or import the queue")
+        .description_localized("ru", "Экспорт или импорт очереди")
+        .add_option(
+            CreateCommandOption::new(CommandOptionType::SubCommand, "export", "Export the queue to a file")
+                .description_localized("ru", "Экспортировать очередь в файл")
+                .add_sub_option(
+                    CreateCommandOption::new(CommandOptionType::String, "format", "File format(default M3U)")
+                        .description_localized("ru", "Формат файла(по умолчанию M3U)")
+                        .add_string_choice("M3U", "m3u")
+                        .add_string_choice("XSPF", "xspf")
+                        .add_string_choice("JSON", "json")
+                )
+        )
+        .add_option(
+            CreateCommandOption::new(CommandOptionType::SubCommand, "import", "Import tracks from a M3U, XSPF or JSON file")
+                .description_localized("ru", "Импортировать треки из файла M3U, XSPF или JSON")
+                .add_sub_option(
+                    CreateCommandOption::new(CommandOptionType::Attachment, "file", "Playlist file")
+                        .description_localized("ru", "Файл плейлиста")
+                        .required(true)
+                )
+        )
+        .dm_permission(false)
+}
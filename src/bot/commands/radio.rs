@@ -0,0 +1,220 @@
+use std::collections::HashMap;
+
+use serenity::all::{Permissions, ResolvedValue};
+use serenity::builder::{CreateCommand, CreateCommandOption, CreateEmbed, EditInteractionResponse};
+use serenity::client::Context;
+use serenity::model::application::{CommandOptionType, CommandInteraction};
+
+use crate::bot::utils::player::{enqueue_track, initialize_guild_player, PlayerData, PlayerDataBase};
+use crate::bot::utils::radio::{add_station, get_station, list_stations, remove_station, resolve_radio};
+use crate::bot::utils::{check_msg, join_user_channel};
+
+pub async fn run(ctx: Context, command: CommandInteraction) {
+    let locale = command.locale.as_str();
+    let guild_id = command.guild_id.unwrap();
+    let (subcommand, options) = match command.data.options().first() {
+        Some(subcommand) => match &subcommand.value {
+            ResolvedValue::SubCommand(options) => (subcommand.name, options.iter().map(|param| (param.name, param.value.clone())).collect::<HashMap<_, _>>()),
+            _ => return
+        },
+        None => return
+    };
+    match subcommand {
+        "play" => check_msg(command.defer(&ctx.http).await),
+        _ => check_msg(command.defer_ephemeral(&ctx.http).await)
+    }
+
+    let pool = {
+        let data_read = ctx.data.read().await;
+        let conn = data_read.get::<PlayerDataBase>().expect("Expected PlayerDataBase in TypeMap.").clone();
+        conn.get()
+    };
+    let mut conn = match pool {
+        Ok(conn) => conn,
+        Err(_) => {
+            let builder = EditInteractionResponse::new().content(match locale {
+                "ru" => "Не удалось подключиться к базе данных!",
+                _ => "Failed to connect to the database!"
+            });
+            check_msg(command.edit_response(&ctx.http, builder).await);
+            return ;
+        }
+    };
+    let allowed = command.member.as_ref()
+        .and_then(|member| member.permissions)
+        .map(|permissions| permissions.contains(Permissions::MANAGE_GUILD))
+        .unwrap_or(false);
+
+    let builder = match subcommand {
+        "play" => {
+            let station = match options.get("station") {
+                Some(ResolvedValue::String(station)) => station.trim(),
+                _ => return
+            };
+            let (name, url) = match get_station(&mut conn, guild_id.get(), station) {
+                Ok(Some(saved)) => (Some(saved.name), saved.url),
+                _ => (None, station.to_string())
+            };
+            initialize_guild_player(&ctx, guild_id).await;
+            let player = ctx.data.read().await.get::<PlayerData>().unwrap().clone();
+            let player = player.read().await.clone();
+            let player = player.get(&guild_id.get()).unwrap().clone();
+            let mut last_id = player.playlist_sync_and_last_id.lock().await;
+
+            if !join_user_channel(&ctx, &command).await {
+                return;
+            }
+
+            *last_id+=1;
+            match resolve_radio(&url, *last_id).await {
+                Some(mut track) => {
+                    track.title = name.or(track.title);
+                    track.requester = Some(command.user.id.get());
+                    let embed = track.get_embed(locale)
+                        .color(14441063)
+                        .title(match locale {
+                            "ru" => "Добавлена радиостанция:",
+                            _ => "Added radio station:"
+                        });
+                    enqueue_track(&ctx, &player, track).await;
+                    EditInteractionResponse::new().embed(embed)
+                },
+                None => EditInteractionResponse::new().content(match locale {
+                    "ru" => "Не удалось подключиться к радиостанции!",
+                    _ => "Failed to connect to the radio station!"
+                })
+            }
+        },
+        "add" => {
+            let name = match options.get("name") {
+                Some(ResolvedValue::String(name)) => name.trim(),
+                _ => return
+            };
+            let url = match options.get("url") {
+                Some(ResolvedValue::String(url)) => url.trim(),
+                _ => return
+            };
+            if !allowed {
+                EditInteractionResponse::new().content(match locale {
+                    "ru" => "Для изменения списка радиостанций нужно право «Управлять сервером»!",
+                    _ => "You need the Manage Server permission to change the radio stations!"
+                })
+            } else if resolve_radio(url, 0).await.is_none() {
+                EditInteractionResponse::new().content(match locale {
+                    "ru" => "Не удалось подключиться к радиостанции!",
+                    _ => "Failed to connect to the radio station!"
+                })
+            } else {
+                match add_station(&mut conn, guild_id.get(), name, url) {
+                    Ok(_) => EditInteractionResponse::new().content(match locale {
+                        "ru" => format!("Радиостанция **{}** сохранена.", name),
+                        _ => format!("Saved radio station **{}**.", name)
+                    }),
+                    Err(_) => EditInteractionResponse::new().content(match locale {
+                        "ru" => "Не удалось сохранить радиостанцию!",
+                        _ => "Failed to save the radio station!"
+                    })
+                }
+            }
+        },
+        "remove" => {
+            let name = match options.get("station") {
+                Some(ResolvedValue::String(name)) => name.trim(),
+                _ => return
+            };
+            if !allowed {
+                EditInteractionResponse::new().content(match locale {
+                    "ru" => "Для изменения списка радиостанций нужно право «Управлять сервером»!",
+                    _ => "You need the Manage Server permission to change the radio stations!"
+                })
+            } else {
+                match remove_station(&mut conn, guild_id.get(), name) {
+                    Ok(true) => EditInteractionResponse::new().content(match locale {
+                        "ru" => format!("Радиостанция **{}** удалена.", name),
+                        _ => format!("Removed radio station **{}**.", name)
+                    }),
+                    Ok(false) => EditInteractionResponse::new().content(match locale {
+                        "ru" => format!("Радиостанция **{}** не найдена!", name),
+                        _ => format!("Radio station **{}** was not found!", name)
+                    }),
+                    Err(_) => EditInteractionResponse::new().content(match locale {
+                        "ru" => "Не удалось удалить радиостанцию!",
+                        _ => "Failed to remove the radio station!"
+                    })
+                }
+            }
+        },
+        "list" => match list_stations(&mut conn, guild_id.get()) {
+            Ok(stations) if !stations.is_empty() => {
+                let description = stations.iter()
+                    .map(|station| format!("**{}** — {}", station.name, station.url))
+                    .collect::<Vec<String>>()
+                    .join("\n");
+                let embed = CreateEmbed::new()
+                    .color(14441063)
+                    .title(match locale {
+                        "ru" => "Радиостанции сервера:",
+                        _ => "Server radio stations:"
+                    })
+                    .description(description);
+                EditInteractionResponse::new().embed(embed)
+            },
+            Ok(_) => EditInteractionResponse::new().content(match locale {
+                "ru" => "На сервере нет сохранённых радиостанций.",
+                _ => "There are no saved radio stations on this server."
+            }),
+            Err(_) => EditInteractionResponse::new().content(match locale {
+                "ru" => "Не удалось получить радиостанции!",
+                _ => "Failed to get radio stations!"
+            })
+        },
+        _ => return
+    };
+    check_msg(command.edit_response(&ctx.http, builder).await);
+}
+
+pub fn register() -> CreateCommand {
+    CreateCommand::new("radio")
+        .description("Internet radio stations")
+        .description_localized("ru", "Интернет-радиостанции")
+        .add_option(
+            CreateCommandOption::new(CommandOptionType::SubCommand, "play", "Play a saved station or a stream URL")
+                .description_localized("ru", "Включить сохранённую станцию или ссылку на поток")
+                .add_sub_option(
+                    CreateCommandOption::new(CommandOptionType::String, "station", "Station name or URL")
+                        .description_localized("ru", "Название станции или ссылка")
+                        .set_autocomplete(true)
+                        .required(true)
+                )
+        )
+        .add_option(
+            CreateCommandOption::new(CommandOptionType::SubCommand, "add", "Save a station for this server")
+                .description_localized("ru", "Сохранить станцию для сервера")
+                .add_sub_option(
+                    CreateCommandOption::new(CommandOptionType::String, "name", "Station name")
+                        .description_localized("ru", "Название станции")
+                        .max_length(100)
+                        .required(true)
+                )
+                .add_sub_option(
+                    CreateCommandOption::new(CommandOptionType::String, "url", "Stream, PLS or M3U URL")
+                        .description_localized("ru", "Ссылка на поток, PLS или M3U")
+                        .required(true)
+                )
+        )
+        .add_option(
+            CreateCommandOption::new(CommandOptionType::SubCommand, "remove", "Remove a saved station")
+                .description_localized("ru", "Удалить сохранённую станцию")
+                .add_sub_option(
+                    CreateCommandOption::new(CommandOptionType::String, "station", "Station name")
+                        .description_localized("ru", "Название станции")
+                        .set_autocomplete(true)
+                        .required(true)
+                )
+        )
+        .add_option(
+            CreateCommandOption::new(CommandOptionType::SubCommand, "list", "List saved stations")
+                .description_localized("ru", "Список сохранённых станций")
+        )
+        .dm_permission(false)
+}
@@ -1,9 +1,9 @@
-use serenity::builder::{CreateCommand, CreateCommandOption, CreateInteractionResponseMessage, CreateInteractionResponse};
+use serenity::builder::{CreateCommand, CreateInteractionResponseMessage, CreateInteractionResponse};
 use serenity::client::Context;
-use serenity::model::application::{CommandOptionType, CommandInteraction};
+use serenity::model::application::CommandInteraction;
 
 use crate::bot::utils::check_msg;
-use crate::bot::utils::player::{PlayerData, PlayerState, initialize_guild_player};
+use crate::bot::utils::player::{PlayerData, ControlError, initialize_guild_player};
 
 pub async fn run(ctx: Context, command: CommandInteraction) {
     initialize_guild_player(&ctx, command.guild_id.unwrap()).await;
@@ -12,34 +12,18 @@ pub async fn run(ctx: Context, command: CommandInteraction) {
     let player = player.read().await.clone();
     let player = player.get(&command.guild_id.unwrap().get()).unwrap().clone();
 
-    let mut state = player.state.write().await;
-    let player_handler = player.player.write().await.clone();
-
-    let text = match *state {
-        PlayerState::Playing | PlayerState::Paused => match player_handler {
-            Some(handler) => {
-                match handler.play() {
-                    Ok(_) => {
-                        *state = PlayerState::Playing;
-                        match command.locale.as_str() {
-                            "ru" => "Возобновлено.",
-                            _ => "Resumed."
-                        }
-                    },
-                    Err(_) => match command.locale.as_str() {
-                        "ru" => "Не удалось возобновить.",
-                        _ => "Could not resume."
-                    }
-                }
-            },
-            None => match command.locale.as_str() {
-                "ru" => "Не удалось возобновить.",
-                _ => "Could not resume."
-            }
+    let text = match player.resume().await {
+        Ok(_) => match command.locale.as_str() {
+            "ru" => "Возобновлено.",
+            _ => "Resumed."
         },
-        _ => match command.locale.as_str() {
+        Err(ControlError::NotPlaying) => match command.locale.as_str() {
             "ru" => "Невозможно возобновить.",
             _ => "Cannot resume."
+        },
+        Err(_) => match command.locale.as_str() {
+            "ru" => "Не удалось возобновить.",
+            _ => "Could not resume."
         }
     };
 
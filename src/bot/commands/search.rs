@@ -0,0 +1,100 @@
+use std::collections::{HashMap, HashSet};
+
+use serenity::all::ResolvedValue;
+use serenity::builder::{CreateActionRow, CreateCommand, CreateCommandOption, CreateSelectMenu, CreateSelectMenuKind, CreateSelectMenuOption, EditInteractionResponse};
+use serenity::client::Context;
+use serenity::model::application::{CommandOptionType, CommandInteraction};
+
+use crate::bot::utils::parser::get_time_str;
+use crate::bot::utils::resolver::{source_registry, DEFAULT_SEARCH_SOURCE};
+use crate::bot::utils::{check_msg, join_user_channel};
+
+const SEARCH_RESULTS: usize = 10;
+const SELECT_TEXT_LIMIT: usize = 100;
+
+fn truncate(text: String) -> String {
+    match text.chars().count() > SELECT_TEXT_LIMIT {
+        true => text.chars().take(SELECT_TEXT_LIMIT - 1).chain(std::iter::once('…')).collect(),
+        false => text
+    }
+}
+
+pub async fn run(ctx: Context, command: CommandInteraction) {
+    let options: &HashMap<_, _> = &command.data.options().into_iter().map(|param| (param.name, param.value)).collect();
+    let query = match options.get("query") {
+        Some(ResolvedValue::String(query)) => *query,
+        _ => return
+    };
+    let source = match options.get("source") {
+        Some(ResolvedValue::String(source)) => Some(*source),
+        _ => None
+    };
+    let locale = command.locale.as_str();
+    check_msg(command.defer_ephemeral(&ctx.http).await);
+
+    if !join_user_channel(&ctx, &command).await {
+        return;
+    }
+
+    let tracks = source_registry().search_many(query, source, SEARCH_RESULTS).await;
+    let mut seen = HashSet::new();
+    let select_options: Vec<CreateSelectMenuOption> = tracks.iter()
+        .filter(|track| track.webpage_url.len() <= SELECT_TEXT_LIMIT && seen.insert(track.webpage_url.clone()))
+        .map(|track| {
+            let title = track.title.clone().unwrap_or(track.webpage_url.clone());
+            let description = match (&track.author.name, track.duration) {
+                (Some(author), Some(duration)) => Some(format!("{} • {}", author, get_time_str(duration))),
+                (Some(author), None) => Some(author.clone()),
+                (None, Some(duration)) => Some(get_time_str(duration)),
+                (None, None) => None
+            };
+            let option = CreateSelectMenuOption::new(truncate(title), track.webpage_url.clone());
+            match description {
+                Some(description) => option.description(truncate(description)),
+                None => option
+            }
+        })
+        .collect();
+
+    let builder = if select_options.is_empty() {
+        EditInteractionResponse::new().content(match locale {
+            "ru" => "Ничего не найдено!",
+            _ => "Nothing was found!"
+        })
+    } else {
+        let max_values = select_options.len() as u8;
+        let menu = CreateSelectMenu::new("search_select", CreateSelectMenuKind::String { options: select_options })
+            .placeholder(match locale {
+                "ru" => "Выберите треки",
+                _ => "Choose tracks"
+            })
+            .min_values(1)
+            .max_values(max_values);
+        EditInteractionResponse::new()
+            .content(match locale {
+                "ru" => format!("Результаты поиска «{}»:", query),
+                _ => format!("Search results for \"{}\":", query)
+            })
+            .components(vec![CreateActionRow::SelectMenu(menu)])
+    };
+    check_msg(command.edit_response(&ctx.http, builder).await);
+}
+
+pub fn register() -> CreateCommand {
+    let mut source = CreateCommandOption::new(CommandOptionType::String, "source", format!("Search source(default \"{}\")", source_registry().get(DEFAULT_SEARCH_SOURCE).map(|resolver| resolver.display_name()).unwrap_or(DEFAULT_SEARCH_SOURCE)))
+        .description_localized("ru", format!("Источник поиска(по умолчанию \"{}\")", source_registry().get(DEFAULT_SEARCH_SOURCE).map(|resolver| resolver.display_name()).unwrap_or(DEFAULT_SEARCH_SOURCE)))
+        .required(false);
+    for resolver in source_registry().searchable() {
+        source = source.add_string_choice(resolver.display_name(), resolver.name());
+    }
+    CreateCommand::new("search")
+        .description("Search for tracks and choose which to add")
+        .description_localized("ru", "Найти треки и выбрать, какие добавить")
+        .add_option(
+            CreateCommandOption::new(CommandOptionType::String, "query", "Search query")
+                .description_localized("ru", "Поисковый запрос")
+                .required(true)
+        )
+        .add_option(source)
+        .dm_permission(false)
+}
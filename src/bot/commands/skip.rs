@@ -6,7 +6,7 @@ use serenity::client::Context;
 use serenity::model::application::{CommandOptionType, CommandInteraction};
 
 use crate::bot::utils::{check_msg, get_title_author_str};
-use crate::bot::utils::player::{PlayerData, PlayerState, initialize_guild_player};
+use crate::bot::utils::player::{PlayerData, PlayerState, ControlError, initialize_guild_player};
 
 pub async fn run(ctx: Context, command: CommandInteraction) {
     initialize_guild_player(&ctx, command.guild_id.unwrap()).await;
@@ -35,39 +35,75 @@ pub async fn run(ctx: Context, command: CommandInteraction) {
     let player = player.read().await.clone();
     let player = player.get(&command.guild_id.unwrap().get()).unwrap().clone();
 
-    let mut player_playlist = player.playlist.write().await;
-    let mut state = player.state.write().await;
-    let player_handler = player.player.write().await.clone();
+    let text = match track_id {
+        None => match player.skip_current().await {
+            Ok(Some(track)) => match locale {
+                "ru" => format!("Пропущен: {}", get_title_author_str(&track, locale)),
+                _ => format!("Skipped: {}", get_title_author_str(&track, locale))
+            },
+            Ok(None) => match locale {
+                "ru" => format!("Пропущен текущий трек"),
+                _ => format!("Skipped current track")
+            },
+            Err(ControlError::Failed) => match locale {
+                "ru" => format!("Произошла ошибка при пропуске трека!"),
+                _ => format!("An error occurred while skipping the track!")
+            },
+            Err(_) => match locale {
+                "ru" => format!("Не удалось получить плеер!"),
+                _ => format!("Failed to get player!")
+            }
+        },
+        Some(track_id) => {
+            let mut player_playlist = player.playlist.write().await;
+            let mut state = player.state.write().await;
+            let player_handler = player.player.write().await.clone();
 
-    let text = match *state {
-        PlayerState::Playing | PlayerState::Paused | PlayerState::Seeking => match track_id {
-            Some(track_id) => match player_playlist.current.clone() {
-                Some(track) => match track.id == track_id{
-                    true => match player_handler {
-                        Some(handler) => match handler.stop() {
-                            Ok(_) => {
-                                *state = PlayerState::InSkip;
+            match *state {
+                PlayerState::Playing | PlayerState::Paused | PlayerState::Seeking => match player_playlist.current.clone() {
+                    Some(track) => match track.id == track_id{
+                        true => match player_handler {
+                            Some(handler) => match handler.stop() {
+                                Ok(_) => {
+                                    *state = PlayerState::InSkip;
+                                    match locale {
+                                        "ru" => format!("Пропущен: {}", get_title_author_str(&track, locale)),
+                                        _ => format!("Skipped: {}", get_title_author_str(&track, locale))
+                                    }
+                                },
+                                Err(_) => match locale {
+                                    "ru" => format!("Произошла ошибка при пропуске трека!"),
+                                    _ => format!("An error occurred while skipping the track!")
+                                }
+                            },
+                            None => match locale {
+                                "ru" => format!("Не удалось получить плеер!"),
+                                _ => format!("Failed to get player!")
+                            }
+                        },
+                        false => match player_playlist.tracks.iter().position(|track| track.id == track_id) {
+                            Some(index) => {
+                                player_playlist.tracks.remove(index);
                                 match locale {
                                     "ru" => format!("Пропущен: {}", get_title_author_str(&track, locale)),
                                     _ => format!("Skipped: {}", get_title_author_str(&track, locale))
                                 }
                             },
-                            Err(_) => match locale {
-                                "ru" => format!("Произошла ошибка при пропуске трека!"),
-                                _ => format!("An error occurred while skipping the track!")
+                            None => match locale {
+                                "ru" => format!("Не удалось найти трек!"),
+                                _ => format!("Failed to find track!")
                             }
-                        },
-                        None => match locale {
-                            "ru" => format!("Не удалось получить плеер!"),
-                            _ => format!("Failed to get player!")
                         }
                     },
-                    false => match player_playlist.tracks.iter().position(|track| track.id == track_id) {
-                        Some(index) => {
-                            player_playlist.tracks.remove(index);
-                            match locale {
+                    None => match player_playlist.tracks.iter().position(|track| track.id == track_id) {
+                        Some(index) => match player_playlist.tracks.remove(index) {
+                            Some(track) => match locale {
                                 "ru" => format!("Пропущен: {}", get_title_author_str(&track, locale)),
                                 _ => format!("Skipped: {}", get_title_author_str(&track, locale))
+                            },
+                            None => match locale {
+                                "ru" => format!("Не удалось найти трек!"),
+                                _ => format!("Failed to find track!")
                             }
                         },
                         None => match locale {
@@ -76,52 +112,11 @@ pub async fn run(ctx: Context, command: CommandInteraction) {
                         }
                     }
                 },
-                None => match player_playlist.tracks.iter().position(|track| track.id == track_id) {
-                    Some(index) => match player_playlist.tracks.remove(index) {
-                        Some(track) => match locale {
-                            "ru" => format!("Пропущен: {}", get_title_author_str(&track, locale)),
-                            _ => format!("Skipped: {}", get_title_author_str(&track, locale))
-                        },
-                        None => match locale {
-                            "ru" => format!("Не удалось найти трек!"),
-                            _ => format!("Failed to find track!")
-                        }
-                    },
-                    None => match locale {
-                        "ru" => format!("Не удалось найти трек!"),
-                        _ => format!("Failed to find track!")
-                    }
-                }
-            },
-            None => match player_handler {
-                Some(handler) => match handler.stop() {
-                    Ok(_) =>{
-                        *state = PlayerState::InSkip;
-                        match player_playlist.current.clone() {
-                            Some(track) => match locale {
-                                "ru" => format!("Пропущен: {}", get_title_author_str(&track, locale)),
-                                _ => format!("Skipped: {}", get_title_author_str(&track, locale))
-                            },
-                            None => match locale {
-                                "ru" => format!("Пропущен текущий трек"),
-                                _ => format!("Skipped current track")
-                            }
-                        }
-                    },
-                    Err(_) => match locale {
-                        "ru" => format!("Произошла ошибка при пропуске трека!"),
-                        _ => format!("An error occurred while skipping the track!")
-                    }
-                },
-                None => match locale {
+                _ => match locale {
                     "ru" => format!("Не удалось получить плеер!"),
                     _ => format!("Failed to get player!")
                 }
             }
-        } 
-        _ => match locale {
-            "ru" => format!("Не удалось получить плеер!"),
-            _ => format!("Failed to get player!")
         }
     };
 
@@ -0,0 +1,128 @@
+use std::collections::HashMap;
+use std::io::Write;
+use std::sync::Arc;
+use std::time::Duration;
+
+use chrono::Utc;
+use serenity::all::{GuildId, ResolvedValue};
+use serenity::builder::{CreateCommand, CreateCommandOption, CreateInteractionResponseMessage, CreateInteractionResponse};
+use serenity::client::Context;
+use serenity::model::application::{CommandOptionType, CommandInteraction};
+use tokio::time::sleep;
+
+use crate::bot::utils::check_msg;
+use crate::bot::utils::filters::Filter;
+use crate::bot::utils::parser::{get_time, get_time_str};
+use crate::bot::utils::player::{clear_guild_player, initialize_guild_player, Player, PlayerData, SleepTimer};
+
+const FADE_SECS: u64 = 30;
+
+pub async fn run(ctx: Context, command: CommandInteraction) {
+    let guild_id = command.guild_id.unwrap();
+    initialize_guild_player(&ctx, guild_id).await;
+
+    let player = ctx.data.read().await.get::<PlayerData>().unwrap().clone();
+    let player = player.read().await.clone();
+    let player = player.get(&guild_id.get()).unwrap().clone();
+
+    let locale = command.locale.as_str();
+    let text = match command.data.options().first() {
+        Some(subcommand) => match (subcommand.name, &subcommand.value) {
+            ("timer", ResolvedValue::SubCommand(options)) => {
+                let options: HashMap<_, _> = options.iter().map(|param| (param.name, &param.value)).collect();
+                let duration = match options.get("duration") {
+                    Some(ResolvedValue::String(duration)) => get_time(*duration),
+                    _ => 0.0
+                };
+                if duration < 1.0 {
+                    match locale {
+                        "ru" => "Неверная длительность!".to_string(),
+                        _ => "Invalid duration!".to_string()
+                    }
+                } else {
+                    player.cancel_sleep().await;
+                    let duration = Duration::from_secs_f64(duration);
+                    let deadline = Utc::now() + chrono::Duration::seconds(duration.as_secs() as i64);
+                    let task = tokio::spawn(sleep_timer(ctx.clone(), guild_id, player.clone(), duration));
+                    *player.sleep.write().await = SleepTimer::At(deadline, task);
+                    match locale {
+                        "ru" => format!("Плеер остановится через {}.", get_time_str(duration.as_secs_f64())),
+                        _ => format!("The player will stop in {}.", get_time_str(duration.as_secs_f64()))
+                    }
+                }
+            },
+            ("end-of-track", _) => {
+                player.cancel_sleep().await;
+                *player.sleep.write().await = SleepTimer::EndOfTrack;
+                match locale {
+                    "ru" => "Плеер остановится после текущего трека.",
+                    _ => "The player will stop after the current track."
+                }.to_string()
+            },
+            ("cancel", _) => match player.cancel_sleep().await {
+                true => match locale {
+                    "ru" => "Таймер сна отменён.",
+                    _ => "Sleep timer cancelled."
+                },
+                false => match locale {
+                    "ru" => "Таймер сна не установлен.",
+                    _ => "No sleep timer is set."
+                }
+            }.to_string(),
+            _ => return
+        },
+        None => return
+    };
+
+    let data = CreateInteractionResponseMessage::new().content(text).ephemeral(true);
+    let builder = CreateInteractionResponse::Message(data);
+    check_msg(command.create_response(&ctx.http, builder).await);
+}
+
+async fn sleep_timer(ctx: Context, guild_id: GuildId, player: Arc<Player>, duration: Duration) {
+    let fade = duration.min(Duration::from_secs(FADE_SECS));
+    sleep(duration - fade).await;
+
+    let volume = player.settings.read().await.volume;
+    let steps = fade.as_secs().max(1);
+    for step in (0..steps).rev() {
+        if let (Some(ffmpeg), Some(command)) = (player.ffmpeg.write().await.as_mut(), Filter::Volume(volume * step as f64 / steps as f64).command()) {
+            let _ = ffmpeg.write(command.as_bytes());
+        }
+        sleep(fade / steps as u32).await;
+    }
+
+    *player.sleep.write().await = SleepTimer::Off;
+    clear_guild_player(&ctx, guild_id).await;
+
+    let manager = songbird::get(&ctx).await.expect("Songbird Voice client placed in at initialisation.").clone();
+    if let Some(handler) = manager.get(guild_id) {
+        let mut handler = handler.lock().await;
+        handler.stop();
+        let _ = handler.leave().await;
+    }
+}
+
+pub fn register() -> CreateCommand {
+    CreateCommand::new("sleep")
+        .description("Stops the player after a while")
+        .description_localized("ru", "Останавливает плеер через некоторое время")
+        .add_option(
+            CreateCommandOption::new(CommandOptionType::SubCommand, "timer", "Stop and disconnect after the duration")
+                .description_localized("ru", "Остановить и отключиться через заданное время")
+                .add_sub_option(
+                    CreateCommandOption::new(CommandOptionType::String, "duration", "Duration( 30m | 1h30m | 1:30:00 )")
+                        .description_localized("ru", "Длительность( 30m | 1h30m | 1:30:00 )")
+                        .required(true)
+                )
+        )
+        .add_option(
+            CreateCommandOption::new(CommandOptionType::SubCommand, "end-of-track", "Stop after the current track")
+                .description_localized("ru", "Остановить после текущего трека")
+        )
+        .add_option(
+            CreateCommandOption::new(CommandOptionType::SubCommand, "cancel", "Cancel the sleep timer")
+                .description_localized("ru", "Отменить таймер сна")
+        )
+        .dm_permission(false)
+}
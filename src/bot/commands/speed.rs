@@ -26,7 +26,7 @@ pub async fn run(ctx: Context, command: CommandInteraction) {
     let mut position = player.position.write().await;
     let mut ffmpeg = player.ffmpeg.write().await;
 
-    let speed = settings.speed;
+    let rate = settings.get_rate();
     settings.set_speed(&ctx ,value, ffmpeg.as_mut()).await;
 
     match player_handler {
@@ -34,7 +34,7 @@ pub async fn run(ctx: Context, command: CommandInteraction) {
             match handler.get_info().await {
                 Ok(data) => {
                     let new_position = data.position;
-                    position.last_position = position.last_position + Duration::from_secs_f64((new_position - position.last_player_position).as_secs_f64() * speed + 0.2);
+                    position.last_position = position.last_position + Duration::from_secs_f64((new_position - position.last_player_position).as_secs_f64() * rate + 0.2);
                     position.last_player_position = new_position;
                 },
                 _ => {}
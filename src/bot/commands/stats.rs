@@ -0,0 +1,136 @@
+use std::collections::HashMap;
+
+use serenity::all::ResolvedValue;
+use serenity::builder::{CreateCommand, CreateCommandOption, CreateEmbed, EditInteractionResponse};
+use serenity::client::Context;
+use serenity::model::application::{CommandOptionType, CommandInteraction};
+
+use crate::bot::utils::check_msg;
+use crate::bot::utils::parser::get_time_str;
+use crate::bot::utils::player::PlayerDataBase;
+use crate::bot::utils::stats::{get_most_skipped, get_top_requesters, get_top_tracks, get_total, StatsPeriod, TrackStats};
+
+const STATS_LIMIT: i64 = 5;
+
+pub async fn run(ctx: Context, command: CommandInteraction) {
+    let options: &HashMap<_, _> = &command.data.options().into_iter().map(|param| (param.name, param.value)).collect();
+    let period = match options.get("period") {
+        Some(ResolvedValue::String(period)) => StatsPeriod::from_str(period),
+        _ => StatsPeriod::Week
+    };
+    check_msg(command.defer(&ctx.http).await);
+
+    let locale = command.locale.as_str();
+    let guild_id = command.guild_id.unwrap().get();
+    let pool = {
+        let data_read = ctx.data.read().await;
+        let conn = data_read.get::<PlayerDataBase>().expect("Expected PlayerDataBase in TypeMap.").clone();
+        conn.get()
+    };
+    let mut conn = match pool {
+        Ok(conn) => conn,
+        Err(_) => {
+            let builder = EditInteractionResponse::new().content(match locale {
+                "ru" => "Не удалось подключиться к базе данных!",
+                _ => "Failed to connect to the database!"
+            });
+            check_msg(command.edit_response(&ctx.http, builder).await);
+            return ;
+        }
+    };
+
+    let stats = (
+        get_total(&mut conn, guild_id, period),
+        get_top_tracks(&mut conn, guild_id, period, STATS_LIMIT),
+        get_top_requesters(&mut conn, guild_id, period, STATS_LIMIT),
+        get_most_skipped(&mut conn, guild_id, period, STATS_LIMIT)
+    );
+    let builder = match stats {
+        (Ok(total), Ok(top_tracks), Ok(top_requesters), Ok(most_skipped)) => {
+            let empty = match locale {
+                "ru" => "Нет данных",
+                _ => "No data"
+            };
+            let requesters = top_requesters.iter()
+                .enumerate()
+                .map(|(index, stats)| match locale {
+                    "ru" => format!("{}. <@{}> — {} ({} треков)", index + 1, stats.requester, get_time_str(stats.listened), stats.count),
+                    _ => format!("{}. <@{}> — {} ({} tracks)", index + 1, stats.requester, get_time_str(stats.listened), stats.count)
+                })
+                .collect::<Vec<String>>()
+                .join("\n");
+            let embed = CreateEmbed::new()
+                .color(14441063)
+                .title(match (locale, period) {
+                    ("ru", StatsPeriod::Day) => "Статистика за день",
+                    ("ru", StatsPeriod::Week) => "Статистика за неделю",
+                    ("ru", StatsPeriod::Month) => "Статистика за месяц",
+                    ("ru", StatsPeriod::All) => "Статистика за всё время",
+                    (_, StatsPeriod::Day) => "Stats for the last day",
+                    (_, StatsPeriod::Week) => "Stats for the last week",
+                    (_, StatsPeriod::Month) => "Stats for the last month",
+                    (_, StatsPeriod::All) => "Stats for all time"
+                })
+                .field(match locale {
+                    "ru" => "Всего прослушано",
+                    _ => "Total listening time"
+                }, match locale {
+                    "ru" => format!("{} ({} треков)", get_time_str(total.listened), total.plays),
+                    _ => format!("{} ({} tracks)", get_time_str(total.listened), total.plays)
+                }, false)
+                .field(match locale {
+                    "ru" => "Популярные треки",
+                    _ => "Top tracks"
+                }, get_tracks_str(&top_tracks, locale).unwrap_or(empty.to_string()), false)
+                .field(match locale {
+                    "ru" => "Активные слушатели",
+                    _ => "Top requesters"
+                }, if requesters.is_empty() { empty.to_string() } else { requesters }, false)
+                .field(match locale {
+                    "ru" => "Чаще всего пропускают",
+                    _ => "Most skipped"
+                }, get_tracks_str(&most_skipped, locale).unwrap_or(empty.to_string()), false);
+            EditInteractionResponse::new().embed(embed)
+        },
+        _ => EditInteractionResponse::new().content(match locale {
+            "ru" => "Не удалось получить статистику!",
+            _ => "Failed to get stats!"
+        })
+    };
+    check_msg(command.edit_response(&ctx.http, builder).await);
+}
+
+fn get_tracks_str(tracks: &[TrackStats], locale: &str) -> Option<String> {
+    if tracks.is_empty() {
+        return None;
+    }
+    Some(tracks.iter()
+        .enumerate()
+        .map(|(index, stats)| {
+            let title = match &stats.title {
+                Some(title) => title.clone(),
+                None => match locale {
+                    "ru" => "Название не известно",
+                    _ => "Unknown title"
+                }.to_string()
+            };
+            format!("{}. [{}]({}) — {}", index + 1, title, stats.webpage_url, stats.count)
+        })
+        .collect::<Vec<String>>()
+        .join("\n"))
+}
+
+pub fn register() -> CreateCommand {
+    CreateCommand::new("stats")
+        .description("Listening statistics of the server")
+        .description_localized("ru", "Статистика прослушивания на сервере")
+        .add_option(
+            CreateCommandOption::new(CommandOptionType::String, "period", "Period(default week)")
+                .description_localized("ru", "Период(по умолчанию неделя)")
+                .add_string_choice_localized("Day", "day", [("ru", "День")])
+                .add_string_choice_localized("Week", "week", [("ru", "Неделя")])
+                .add_string_choice_localized("Month", "month", [("ru", "Месяц")])
+                .add_string_choice_localized("All time", "all", [("ru", "Всё время")])
+                .required(false)
+        ).dm_permission(false)
+}
@@ -0,0 +1,59 @@
+use serenity::all::ComponentInteraction;
+use serenity::builder::{CreateInteractionResponseMessage, CreateInteractionResponse};
+use serenity::client::Context;
+
+use crate::bot::utils::check_msg;
+use crate::bot::utils::likes::{get_likes_message, get_likes_page, like_current_track};
+use crate::bot::utils::player::{initialize_guild_player, PlayerData, PlayerDataBase};
+
+pub async fn like(ctx: Context, component: ComponentInteraction) {
+    let guild_id = match component.guild_id {
+        Some(guild_id) => guild_id,
+        None => return
+    };
+    initialize_guild_player(&ctx, guild_id).await;
+
+    let player = ctx.data.read().await.get::<PlayerData>().unwrap().clone();
+    let player = player.read().await.clone();
+    let player = player.get(&guild_id.get()).unwrap().clone();
+
+    let text = like_current_track(&ctx, player.playlist.read().await.current.clone(), component.user.id.get(), component.locale.as_str()).await;
+
+    let data = CreateInteractionResponseMessage::new().content(text).ephemeral(true);
+    check_msg(component.create_response(&ctx.http, CreateInteractionResponse::Message(data)).await);
+}
+
+pub async fn run(ctx: Context, component: ComponentInteraction) {
+    let locale = component.locale.as_str();
+    let (user, page) = match component.data.custom_id.split(':').collect::<Vec<&str>>()[..] {
+        ["likes_page", user, page] => match (user.parse::<u64>(), page.parse::<i64>()) {
+            (Ok(user), Ok(page)) => (user, page),
+            _ => return
+        },
+        _ => return
+    };
+    if user != component.user.id.get() {
+        return ;
+    }
+
+    let pool = {
+        let data_read = ctx.data.read().await;
+        let conn = data_read.get::<PlayerDataBase>().expect("Expected PlayerDataBase in TypeMap.").clone();
+        conn.get()
+    };
+    let builder = match pool.map(|mut conn| get_likes_page(&mut conn, user, page)) {
+        Ok(Ok((liked, total))) => {
+            let (embed, components) = get_likes_message(&liked, total, page, user, locale);
+            CreateInteractionResponse::UpdateMessage(
+                CreateInteractionResponseMessage::new().embed(embed).components(components)
+            )
+        },
+        _ => CreateInteractionResponse::Message(
+            CreateInteractionResponseMessage::new().content(match locale {
+                "ru" => "Не удалось получить избранное!",
+                _ => "Failed to get your likes!"
+            }).ephemeral(true)
+        )
+    };
+    check_msg(component.create_response(&ctx.http, builder).await);
+}
@@ -0,0 +1,4 @@
+pub mod player;
+pub mod likes;
+pub mod search;
+pub mod podcast;
\ No newline at end of file
@@ -0,0 +1,106 @@
+use serenity::all::ComponentInteraction;
+use serenity::builder::{CreateInteractionResponseMessage, CreateInteractionResponse};
+use serenity::client::Context;
+
+use crate::bot::utils::{check_msg, in_bot_channel};
+use crate::bot::utils::controls::get_player_message;
+use crate::bot::utils::player::{PlayerData, ControlError, initialize_guild_player};
+
+const VOLUME_STEP: f64 = 0.1;
+
+pub async fn run(ctx: Context, component: ComponentInteraction) {
+    let guild_id = match component.guild_id {
+        Some(guild_id) => guild_id,
+        None => return
+    };
+    let locale = component.locale.as_str();
+
+    if !in_bot_channel(&ctx, guild_id, component.user.id).await {
+        let text = match locale {
+            "ru" => "Вы должны находиться в одном канале с ботом!",
+            _ => "You must be in the same voice channel as the bot!"
+        };
+        let data = CreateInteractionResponseMessage::new().content(text).ephemeral(true);
+        check_msg(component.create_response(&ctx.http, CreateInteractionResponse::Message(data)).await);
+        return ;
+    }
+
+    initialize_guild_player(&ctx, guild_id).await;
+
+    let player = ctx.data.read().await.get::<PlayerData>().unwrap().clone();
+    let player = player.read().await.clone();
+    let player = player.get(&guild_id.get()).unwrap().clone();
+
+    let result = match component.data.custom_id.as_str() {
+        "player_pause" => player.pause().await.map(|_| None),
+        "player_resume" => player.resume().await.map(|_| None),
+        "player_skip" => {
+            let next = player.playlist.read().await.tracks.front().cloned();
+            player.skip_current().await.map(|_| next)
+        },
+        "player_previous" => player.previous().await.map(Some),
+        "player_shuffle" => {
+            player.shuffle().await;
+            Ok(None)
+        },
+        "player_repeat" => {
+            let mut settings = player.settings.write().await;
+            let mode = settings.repeat.next();
+            settings.set_repeat(&ctx, mode).await;
+            Ok(None)
+        },
+        "player_volume_down" | "player_volume_up" => {
+            let mut settings = player.settings.write().await;
+            let mut ffmpeg = player.ffmpeg.write().await;
+            let value = match component.data.custom_id.as_str() {
+                "player_volume_up" => settings.volume + VOLUME_STEP,
+                _ => settings.volume - VOLUME_STEP
+            };
+            let value = ((value * 10.0).round() / 10.0).max(0.0);
+            settings.set_volume(&ctx, value, ffmpeg.as_mut()).await;
+            Ok(None)
+        },
+        _ => {
+            let text = match locale {
+                "ru" => "Неизвестная кнопка!",
+                _ => "Unknown button!"
+            };
+            let data = CreateInteractionResponseMessage::new().content(text).ephemeral(true);
+            check_msg(component.create_response(&ctx.http, CreateInteractionResponse::Message(data)).await);
+            return ;
+        }
+    };
+
+    let builder = match result {
+        Ok(track) => {
+            let (embed, components) = get_player_message(&player, locale, track).await;
+            CreateInteractionResponse::UpdateMessage(
+                CreateInteractionResponseMessage::new().embed(embed).components(components)
+            )
+        },
+        Err(error) => {
+            let text = match error {
+                ControlError::NotPlaying => match locale {
+                    "ru" => "Сейчас ничего не играет!",
+                    _ => "Nothing is playing!"
+                },
+                ControlError::NoHistory => match locale {
+                    "ru" => "Нет предыдущего трека!",
+                    _ => "There is no previous track!"
+                },
+                ControlError::NoHandler => match locale {
+                    "ru" => "Не удалось получить плеер!",
+                    _ => "Failed to get player!"
+                },
+                ControlError::Failed => match locale {
+                    "ru" => "Не удалось выполнить действие!",
+                    _ => "Could not perform the action!"
+                }
+            };
+            CreateInteractionResponse::Message(
+                CreateInteractionResponseMessage::new().content(text).ephemeral(true)
+            )
+        }
+    };
+    check_msg(component.create_response(&ctx.http, builder).await);
+}
@@ -0,0 +1,68 @@
+use serenity::all::{ComponentInteraction, ComponentInteractionDataKind};
+use serenity::builder::EditInteractionResponse;
+use serenity::client::Context;
+
+use crate::bot::utils::player::{enqueue_track, initialize_guild_player, PlayerData};
+use crate::bot::utils::podcast::{get_episode_track, take_picked_episodes};
+use crate::bot::utils::{check_msg, in_bot_channel};
+
+pub async fn run(ctx: Context, component: ComponentInteraction) {
+    let guild_id = match component.guild_id {
+        Some(guild_id) => guild_id,
+        None => return
+    };
+    let indices = match &component.data.kind {
+        ComponentInteractionDataKind::StringSelect { values } => values.clone(),
+        _ => return
+    };
+    let locale = component.locale.as_str();
+    check_msg(component.defer(&ctx.http).await);
+
+    if !in_bot_channel(&ctx, guild_id, component.user.id).await {
+        let builder = EditInteractionResponse::new()
+            .content(match locale {
+                "ru" => "Подключитесь к каналу бота!",
+                _ => "Join the bot's channel!"
+            });
+        check_msg(component.edit_response(&ctx.http, builder).await);
+        return;
+    }
+
+    let episodes = match take_picked_episodes(component.message.id.get(), &indices) {
+        Some(episodes) => episodes,
+        None => {
+            let builder = EditInteractionResponse::new()
+                .content(match locale {
+                    "ru" => "Список выпусков устарел, используйте /play ещё раз!",
+                    _ => "The episode list has expired, use /play again!"
+                })
+                .components(Vec::new());
+            check_msg(component.edit_response(&ctx.http, builder).await);
+            return;
+        }
+    };
+    let builder = EditInteractionResponse::new().components(Vec::new());
+    check_msg(component.edit_response(&ctx.http, builder).await);
+
+    initialize_guild_player(&ctx, guild_id).await;
+    let player = ctx.data.read().await.get::<PlayerData>().unwrap().clone();
+    let player = player.read().await.clone();
+    let player = player.get(&guild_id.get()).unwrap().clone();
+
+    for episode in episodes.iter() {
+        let mut track = get_episode_track(episode, 0).await;
+        track.id = {
+            let mut last_id = player.playlist_sync_and_last_id.lock().await;
+            *last_id+=1;
+            *last_id
+        };
+        track.requester = Some(component.user.id.get());
+        enqueue_track(&ctx, &player, track).await;
+    }
+
+    let builder = EditInteractionResponse::new().content(match locale {
+        "ru" => format!("Добавлено выпусков: {}", episodes.len()),
+        _ => format!("Added {} episodes", episodes.len())
+    });
+    check_msg(component.edit_response(&ctx.http, builder).await);
+}
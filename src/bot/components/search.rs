@@ -0,0 +1,67 @@
+use serenity::all::{ComponentInteraction, ComponentInteractionDataKind};
+use serenity::builder::EditInteractionResponse;
+use serenity::client::Context;
+use serenity::futures::StreamExt;
+
+use crate::bot::utils::player::{enqueue_track, initialize_guild_player, PlayerData};
+use crate::bot::utils::resolver::resolve_tracks_ordered;
+use crate::bot::utils::{check_msg, in_bot_channel};
+
+pub async fn run(ctx: Context, component: ComponentInteraction) {
+    let guild_id = match component.guild_id {
+        Some(guild_id) => guild_id,
+        None => return
+    };
+    let urls = match &component.data.kind {
+        ComponentInteractionDataKind::StringSelect { values } => values.clone(),
+        _ => return
+    };
+    let locale = component.locale.as_str();
+    check_msg(component.defer(&ctx.http).await);
+
+    if !in_bot_channel(&ctx, guild_id, component.user.id).await {
+        let builder = EditInteractionResponse::new()
+            .content(match locale {
+                "ru" => "Подключитесь к каналу бота!",
+                _ => "Join the bot's channel!"
+            })
+            .components(Vec::new());
+        check_msg(component.edit_response(&ctx.http, builder).await);
+        return;
+    }
+
+    let builder = EditInteractionResponse::new()
+        .content(match locale {
+            "ru" => format!("Добавление треков: {}...", urls.len()),
+            _ => format!("Adding {} tracks...", urls.len())
+        })
+        .components(Vec::new());
+    check_msg(component.edit_response(&ctx.http, builder).await);
+
+    initialize_guild_player(&ctx, guild_id).await;
+    let player = ctx.data.read().await.get::<PlayerData>().unwrap().clone();
+    let player = player.read().await.clone();
+    let player = player.get(&guild_id.get()).unwrap().clone();
+
+    let total = urls.len();
+    let mut added = 0;
+    let mut results = Box::pin(resolve_tracks_ordered(urls));
+    while let Some((_, track)) = results.next().await {
+        if let Some(mut track) = track {
+            track.id = {
+                let mut last_id = player.playlist_sync_and_last_id.lock().await;
+                *last_id+=1;
+                *last_id
+            };
+            track.requester = Some(component.user.id.get());
+            enqueue_track(&ctx, &player, track).await;
+            added += 1;
+        }
+    }
+
+    let builder = EditInteractionResponse::new().content(match locale {
+        "ru" => format!("Добавлено треков: {}/{}", added, total),
+        _ => format!("Added {}/{} tracks", added, total)
+    });
+    check_msg(component.edit_response(&ctx.http, builder).await);
+}
@@ -1,12 +1,77 @@
 use std::sync::Arc;
+use std::time::Duration;
 
-use serenity::{all::GuildId, async_trait, client::Context};
-use songbird::{events::{Event, EventContext, EventHandler as VoiceEventHandler}, input::Input, Songbird};
+use diesel::{r2d2::{ConnectionManager, Pool}, SqliteConnection};
+use serenity::{all::GuildId, async_trait, client::{Cache, Context}, http::Http};
+use songbird::{events::{Event, EventContext, EventHandler as VoiceEventHandler}, input::Input, tracks::TrackHandle, Songbird};
 
-use crate::bot::utils::player::{PlayerData, PlayerState, Position, RepeatMode};
+use crate::bot::utils::announce::spawn_announcement;
+use crate::bot::utils::presence::{spawn_presence_update, spawn_presence_update_ctx, Presence};
+use crate::bot::utils::player::{clear_guild_player, Player, PlayerData, PlayerDataBase, PlayerState, Position, RepeatMode};
+use crate::bot::utils::playlist::Playlist;
+use crate::bot::utils::stats::record_play;
+use crate::bot::utils::track::Track;
 
 use super::utils::player::PlayerDataType;
 
+const LIVE_RESTART_MIN_PLAY_SECS: u64 = 10;
+
+async fn prepare_live_restart(player: &Player, play_time: Duration) {
+    if play_time < Duration::from_secs(LIVE_RESTART_MIN_PLAY_SECS) {
+        return;
+    }
+    let track = {
+        let player_playlist = player.playlist.read().await;
+        let state = player.state.read().await;
+        match (&*state, &player_playlist.current) {
+            (PlayerState::Playing, Some(track)) if track.is_live => track.clone(),
+            _ => return
+        }
+    };
+    if let Some(track) = track.check_live().await {
+        let mut player_playlist = player.playlist.write().await;
+        let mut state = player.state.write().await;
+        if matches!(*state, PlayerState::Playing) && player_playlist.current.as_ref().map(|current| current.id) == Some(track.id) {
+            player_playlist.current = Some(track);
+            *player.position.write().await = Position::default();
+            *state = PlayerState::Seeking;
+        }
+    }
+}
+
+fn next_track<'a>(player_playlist: &'a Playlist, state: &PlayerState, repeat: &RepeatMode) -> Option<&'a Track> {
+    match state {
+        PlayerState::Seeking if player_playlist.current.is_some() => player_playlist.current.as_ref(),
+        PlayerState::Seeking | PlayerState::InSkip => player_playlist.tracks.front(),
+        _ if *repeat == RepeatMode::Track => player_playlist.current.as_ref(),
+        _ => player_playlist.tracks.front()
+    }
+}
+
+async fn prepare_next_track(player: &Player) {
+    loop {
+        let track = {
+            let player_playlist = player.playlist.read().await;
+            let state = player.state.read().await;
+            let settings = player.settings.read().await;
+            match next_track(&player_playlist, &state, &settings.repeat) {
+                Some(track) if !track.is_prepared() => track.clone(),
+                _ => return
+            }
+        };
+        let id = track.id;
+        let track = track.prepare().await;
+        player.playlist.write().await.replace(id, track);
+    }
+}
+
+async fn is_stale(player: &Player, handle: &TrackHandle) -> bool {
+    match player.player.read().await.as_ref() {
+        Some(current) => current.uuid() != handle.uuid(),
+        None => false
+    }
+}
+
 pub struct TrackEndNotifier {
     pub guild_id: GuildId,
     pub ctx_clone: Context,
@@ -23,16 +88,47 @@ impl VoiceEventHandler for TrackEndNotifier {
 
             let manager = songbird::get(&self.ctx_clone).await.expect("Songbird Voice client placed in at initialisation.").clone();
 
-            let mut player_playlist = player.playlist.write().await;
-            let mut state = player.state.write().await;
-            let settings = player.settings.read().await;
-            let mut player_handler = player.player.write().await;
+            if is_stale(&player, track_list[0].1).await {
+                return None;
+            }
+            prepare_live_restart(&player, track_list[0].0.play_time).await;
+            let (mut player_playlist, mut state, settings, mut player_handler) = loop {
+                prepare_next_track(&player).await;
+                let player_playlist = player.playlist.write().await;
+                let state = player.state.write().await;
+                let settings = player.settings.read().await;
+                let player_handler = player.player.write().await;
+                if next_track(&player_playlist, &state, &settings.repeat).map_or(true, Track::is_prepared) {
+                    break (player_playlist, state, settings, player_handler);
+                }
+            };
 
             if let Some(handler) = player_handler.clone() {
                 if handler.uuid() != track_list[0].1.uuid(){
                     return None;
                 }
             }
+            spawn_presence_update_ctx(&self.ctx_clone).await;
+
+            if let Some((play, skipped)) = player.finish_play(track_list[0].0.play_time, &*state).await {
+                let pool = {
+                    let data_read = self.ctx_clone.data.read().await;
+                    let conn = data_read.get::<PlayerDataBase>().expect("Expected PlayerDataBase in TypeMap.").clone();
+                    conn.get()
+                };
+                if let Ok(mut conn) = pool {
+                    let _ = record_play(&mut conn, self.guild_id.get(), &play, skipped);
+                }
+            }
+
+            if !matches!(*state, PlayerState::Seeking) && player.take_sleep_end_of_track().await {
+                let ctx = self.ctx_clone.clone();
+                let guild_id = self.guild_id;
+                tokio::spawn(async move {
+                    clear_guild_player(&ctx, guild_id).await;
+                });
+                return None;
+            }
             
             match *state {
                 PlayerState::Seeking => {
@@ -58,14 +154,14 @@ impl VoiceEventHandler for TrackEndNotifier {
                             }
                         },
                         None => {
-                            let track = player_playlist.tracks.pop_front();
+                            let track = player_playlist.pop_next();
                             if track.is_none() {
                                 return None;
                             }
                             let track = track.unwrap();
-                            player_playlist.current = Some(track.clone());
+                            player_playlist.set_current(track.clone());
                             *state = PlayerState::Playing;
-                            *position = Position::default();
+                            *position = Position::from_track(&track);
 
                             let mut child = track.get_child(&self.ctx_clone, &self.guild_id.get(), 0.0).await.unwrap();
                             let stdin = child.stdin.take().unwrap();
@@ -80,14 +176,16 @@ impl VoiceEventHandler for TrackEndNotifier {
                                 let handle = handler.play_only_input(data);
                                 let _ = player_handler.insert(handle);
                             }
+                            player.start_play(track.clone()).await;
+                            spawn_announcement(self.ctx_clone.cache.clone(), self.ctx_clone.http.clone(), player.clone(), track.clone());
                         }
                     }
                 },
                 PlayerState::InSkip => {
                     let mut last_updated_position = player.position.write().await;
-                        let track = player_playlist.tracks.pop_front();
+                        let track = player_playlist.pop_next();
                         if track.is_none() {
-                            player_playlist.current = None;
+                            player_playlist.take_current();
                             *state = PlayerState::Ended;
                             return None;
                         }
@@ -98,9 +196,9 @@ impl VoiceEventHandler for TrackEndNotifier {
                                 None => {}
                             }
                         }
-                        player_playlist.current = Some(track.clone());
+                        player_playlist.set_current(track.clone());
                         *state = PlayerState::Playing;
-                        *last_updated_position = Position::default();
+                        *last_updated_position = Position::from_track(&track);
 
                         let mut child = track.get_child(&self.ctx_clone, &self.guild_id.get(), 0.0).await.unwrap();
                         let stdin = child.stdin.take().unwrap();
@@ -115,19 +213,21 @@ impl VoiceEventHandler for TrackEndNotifier {
                             let handle = handler.play_only_input(data);
                             let _ = player_handler.insert(handle);
                         }
+                        player.start_play(track.clone()).await;
+                        spawn_announcement(self.ctx_clone.cache.clone(), self.ctx_clone.http.clone(), player.clone(), track.clone());
                 }
                 _ => match settings.repeat {
                     RepeatMode::Track => {
                         let mut last_updated_position = player.position.write().await;
                         let track = player_playlist.current.clone();
                         if track.is_none() {
-                            player_playlist.current = None;
+                            player_playlist.take_current();
                             *state = PlayerState::Ended;
                             return None;
                         }
                         let track = track.unwrap();
                         *state = PlayerState::Playing;
-                        *last_updated_position = Position::default();
+                        *last_updated_position = Position::from_track(&track);
 
                         let mut child = track.get_child(&self.ctx_clone, &self.guild_id.get(), 0.0).await.unwrap();
                         let stdin = child.stdin.take().unwrap();
@@ -142,12 +242,14 @@ impl VoiceEventHandler for TrackEndNotifier {
                             let handle = handler.play_only_input(data);
                             let _ = player_handler.insert(handle);
                         }
+                        player.start_play(track.clone()).await;
+                        spawn_announcement(self.ctx_clone.cache.clone(), self.ctx_clone.http.clone(), player.clone(), track.clone());
                     },
                     _ => {
                         let mut last_updated_position = player.position.write().await;
-                        let track = player_playlist.tracks.pop_front();
+                        let track = player_playlist.pop_next();
                         if track.is_none() {
-                            player_playlist.current = None;
+                            player_playlist.take_current();
                             *state = PlayerState::Ended;
                             return None;
                         }
@@ -158,9 +260,9 @@ impl VoiceEventHandler for TrackEndNotifier {
                                 None => {}
                             }
                         }
-                        player_playlist.current = Some(track.clone());
+                        player_playlist.set_current(track.clone());
                         *state = PlayerState::Playing;
-                        *last_updated_position = Position::default();
+                        *last_updated_position = Position::from_track(&track);
 
                         let mut child = track.get_child(&self.ctx_clone, &self.guild_id.get(), 0.0).await.unwrap();
                         let stdin = child.stdin.take().unwrap();
@@ -175,6 +277,8 @@ impl VoiceEventHandler for TrackEndNotifier {
                             let handle = handler.play_only_input(data);
                             let _ = player_handler.insert(handle);
                         }
+                        player.start_play(track.clone()).await;
+                        spawn_announcement(self.ctx_clone.cache.clone(), self.ctx_clone.http.clone(), player.clone(), track.clone());
                     }
                 }
             };
@@ -196,6 +300,10 @@ pub struct TrackEndNotifierWeb {
     pub guild_id: GuildId,
     pub songbird: Arc<Arc<Songbird>>,
     pub player: Arc<PlayerDataType>,
+    pub cache: Arc<Cache>,
+    pub http: Arc<Http>,
+    pub presence: Presence,
+    pub pool: Pool<ConnectionManager<SqliteConnection>>,
 }
 
 #[async_trait]
@@ -209,16 +317,41 @@ impl VoiceEventHandler for TrackEndNotifierWeb {
 
             let manager = self.songbird.clone();
 
-            let mut player_playlist = player.playlist.write().await;
-            let mut state = player.state.write().await;
-            let settings = player.settings.read().await;
-            let mut player_handler = player.player.write().await;
+            if is_stale(&player, track_list[0].1).await {
+                return None;
+            }
+            prepare_live_restart(&player, track_list[0].0.play_time).await;
+            let (mut player_playlist, mut state, settings, mut player_handler) = loop {
+                prepare_next_track(&player).await;
+                let player_playlist = player.playlist.write().await;
+                let state = player.state.write().await;
+                let settings = player.settings.read().await;
+                let player_handler = player.player.write().await;
+                if next_track(&player_playlist, &state, &settings.repeat).map_or(true, Track::is_prepared) {
+                    break (player_playlist, state, settings, player_handler);
+                }
+            };
 
             if let Some(handler) = player_handler.clone() {
                 if handler.uuid() != track_list[0].1.uuid(){
                     return None;
                 }
             }
+            spawn_presence_update(self.presence.clone(), self.player.as_ref().clone());
+
+            if let Some((play, skipped)) = player.finish_play(track_list[0].0.play_time, &*state).await {
+                if let Ok(mut conn) = self.pool.get() {
+                    let _ = record_play(&mut conn, self.guild_id.get(), &play, skipped);
+                }
+            }
+
+            if !matches!(*state, PlayerState::Seeking) && player.take_sleep_end_of_track().await {
+                let player = player.clone();
+                tokio::spawn(async move {
+                    player.clear().await;
+                });
+                return None;
+            }
             
             match *state {
                 PlayerState::Seeking => {
@@ -244,14 +377,14 @@ impl VoiceEventHandler for TrackEndNotifierWeb {
                             }
                         },
                         None => {
-                            let track = player_playlist.tracks.pop_front();
+                            let track = player_playlist.pop_next();
                             if track.is_none() {
                                 return None;
                             }
                             let track = track.unwrap();
-                            player_playlist.current = Some(track.clone());
+                            player_playlist.set_current(track.clone());
                             *state = PlayerState::Playing;
-                            *position = Position::default();
+                            *position = Position::from_track(&track);
 
                             let mut child = track.get_child_web(self.player.clone(), &self.guild_id.get(), 0.0).await.unwrap();
                             let stdin = child.stdin.take().unwrap();
@@ -266,14 +399,16 @@ impl VoiceEventHandler for TrackEndNotifierWeb {
                                 let handle = handler.play_only_input(data);
                                 let _ = player_handler.insert(handle);
                             }
+                            player.start_play(track.clone()).await;
+                            spawn_announcement(self.cache.clone(), self.http.clone(), player.clone(), track.clone());
                         }
                     }
                 },
                 PlayerState::InSkip => {
                     let mut last_updated_position = player.position.write().await;
-                        let track = player_playlist.tracks.pop_front();
+                        let track = player_playlist.pop_next();
                         if track.is_none() {
-                            player_playlist.current = None;
+                            player_playlist.take_current();
                             *state = PlayerState::Ended;
                             return None;
                         }
@@ -284,9 +419,9 @@ impl VoiceEventHandler for TrackEndNotifierWeb {
                                 None => {}
                             }
                         }
-                        player_playlist.current = Some(track.clone());
+                        player_playlist.set_current(track.clone());
                         *state = PlayerState::Playing;
-                        *last_updated_position = Position::default();
+                        *last_updated_position = Position::from_track(&track);
 
                         let mut child = track.get_child_web(self.player.clone(), &self.guild_id.get(), 0.0).await.unwrap();
                         let stdin = child.stdin.take().unwrap();
@@ -301,19 +436,21 @@ impl VoiceEventHandler for TrackEndNotifierWeb {
                             let handle = handler.play_only_input(data);
                             let _ = player_handler.insert(handle);
                         }
+                        player.start_play(track.clone()).await;
+                        spawn_announcement(self.cache.clone(), self.http.clone(), player.clone(), track.clone());
                 }
                 _ => match settings.repeat {
                     RepeatMode::Track => {
                         let mut last_updated_position = player.position.write().await;
                         let track = player_playlist.current.clone();
                         if track.is_none() {
-                            player_playlist.current = None;
+                            player_playlist.take_current();
                             *state = PlayerState::Ended;
                             return None;
                         }
                         let track = track.unwrap();
                         *state = PlayerState::Playing;
-                        *last_updated_position = Position::default();
+                        *last_updated_position = Position::from_track(&track);
 
                         let mut child = track.get_child_web(self.player.clone(), &self.guild_id.get(), 0.0).await.unwrap();
                         let stdin = child.stdin.take().unwrap();
@@ -328,12 +465,14 @@ impl VoiceEventHandler for TrackEndNotifierWeb {
                             let handle = handler.play_only_input(data);
                             let _ = player_handler.insert(handle);
                         }
+                        player.start_play(track.clone()).await;
+                        spawn_announcement(self.cache.clone(), self.http.clone(), player.clone(), track.clone());
                     },
                     _ => {
                         let mut last_updated_position = player.position.write().await;
-                        let track = player_playlist.tracks.pop_front();
+                        let track = player_playlist.pop_next();
                         if track.is_none() {
-                            player_playlist.current = None;
+                            player_playlist.take_current();
                             *state = PlayerState::Ended;
                             return None;
                         }
@@ -344,9 +483,9 @@ impl VoiceEventHandler for TrackEndNotifierWeb {
                                 None => {}
                             }
                         }
-                        player_playlist.current = Some(track.clone());
+                        player_playlist.set_current(track.clone());
                         *state = PlayerState::Playing;
-                        *last_updated_position = Position::default();
+                        *last_updated_position = Position::from_track(&track);
 
                         let mut child = track.get_child_web(self.player.clone(), &self.guild_id.get(), 0.0).await.unwrap();
                         let stdin = child.stdin.take().unwrap();
@@ -361,6 +500,8 @@ impl VoiceEventHandler for TrackEndNotifierWeb {
                             let handle = handler.play_only_input(data);
                             let _ = player_handler.insert(handle);
                         }
+                        player.start_play(track.clone()).await;
+                        spawn_announcement(self.cache.clone(), self.http.clone(), player.clone(), track.clone());
                     }
                 }
             };
@@ -1,4 +1,5 @@
 pub mod commands;
 pub mod utils;
 pub mod auto_complete;
+pub mod components;
 pub mod events;
\ No newline at end of file
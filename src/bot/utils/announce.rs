@@ -0,0 +1,94 @@
+use std::sync::Arc;
+
+use serenity::all::ChannelId;
+use serenity::builder::{CreateEmbed, CreateMessage, EditMessage};
+use serenity::client::Cache;
+use serenity::http::Http;
+
+use super::{check_msg, get_title_author_str};
+use super::player::Player;
+use super::radio::watch_stream_title;
+use super::track::{ParserType, Track};
+
+pub fn spawn_announcement(cache: Arc<Cache>, http: Arc<Http>, player: Arc<Player>, track: Track) {
+    tokio::spawn(async move {
+        announce_track(&cache, &http, &player, &track).await;
+        if track.parser_type == ParserType::Radio {
+            watch_stream_title(&cache, &http, &player, &track).await;
+        }
+    });
+}
+
+async fn get_announcement_embed(cache: &Cache, player: &Player, track: &Track) -> CreateEmbed {
+    let next = player.playlist.read().await.tracks.front().cloned();
+    let locale = match cache.guild(player.guild_id) {
+        Some(guild) => guild.preferred_locale.clone(),
+        None => String::new()
+    };
+    let locale = locale.as_str();
+
+    let mut embed = track.get_embed(locale)
+        .color(14441063)
+        .title(match locale {
+            "ru" => "Сейчас играет:",
+            _ => "Now playing:"
+        });
+    if let Some(requester) = track.requester {
+        embed = embed.field(match locale {
+            "ru" => "Заказал",
+            _ => "Requested by"
+        }, format!("<@{}>", requester), true);
+    }
+    embed = embed.field(match locale {
+        "ru" => "Далее",
+        _ => "Up next"
+    }, match &next {
+        Some(next) => get_title_author_str(next, locale),
+        None => match locale {
+            "ru" => "Очередь пуста",
+            _ => "Queue is empty"
+        }.to_string()
+    }, false);
+    embed
+}
+
+pub async fn announce_track(cache: &Cache, http: &Http, player: &Player, track: &Track) {
+    let (channel, delete_previous) = {
+        let settings = player.settings.read().await;
+        match settings.announce_channel {
+            Some(channel) => (ChannelId::new(channel), settings.announce_delete_previous),
+            None => return
+        }
+    };
+    let embed = get_announcement_embed(cache, player, track).await;
+
+    let mut announcement = player.announcement.write().await;
+    if let Some((last_channel, last_message)) = *announcement {
+        let is_last_message = last_channel == channel && match cache.channel(channel) {
+            Some(channel) => channel.last_message_id == Some(last_message),
+            None => false
+        };
+        if is_last_message {
+            let builder = EditMessage::new().embed(embed.clone());
+            if channel.edit_message(http, last_message, builder).await.is_ok() {
+                return;
+            }
+        }
+        if delete_previous {
+            check_msg(last_channel.delete_message(http, last_message).await);
+        }
+    }
+    match channel.send_message(http, CreateMessage::new().embed(embed)).await {
+        Ok(message) => *announcement = Some((channel, message.id)),
+        Err(why) => println!("Error sending message: {:?}", why)
+    }
+}
+
+pub async fn update_announcement(cache: &Cache, http: &Http, player: &Player, track: &Track) {
+    let (channel, message) = match *player.announcement.read().await {
+        Some(announcement) => announcement,
+        None => return
+    };
+    let embed = get_announcement_embed(cache, player, track).await;
+    check_msg(channel.edit_message(http, message, EditMessage::new().embed(embed)).await);
+}
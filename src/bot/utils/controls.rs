@@ -0,0 +1,93 @@
+use serenity::all::ButtonStyle;
+use serenity::builder::{CreateActionRow, CreateButton, CreateEmbed, CreateEmbedFooter};
+
+use super::player::{Player, PlayerState, RepeatMode, SleepTimer};
+use super::track::Track;
+
+pub async fn get_player_message(player: &Player, locale: &str, track: Option<Track>) -> (CreateEmbed, Vec<CreateActionRow>) {
+    let track = match track {
+        Some(track) => Some(track),
+        None => player.playlist.read().await.current.clone()
+    };
+    let queue_len = player.playlist.read().await.tracks.len();
+    let paused = matches!(*player.state.read().await, PlayerState::Paused);
+    let (volume, repeat) = {
+        let settings = player.settings.read().await;
+        (settings.volume, settings.repeat)
+    };
+    let sleep = match &*player.sleep.read().await {
+        SleepTimer::Off => None,
+        SleepTimer::At(deadline, _) => Some(format!("<t:{}:R>", deadline.timestamp())),
+        SleepTimer::EndOfTrack => Some(match locale {
+            "ru" => "после текущего трека",
+            _ => "after the current track"
+        }.to_string())
+    };
+
+    let mut embed = match &track {
+        Some(track) => track.get_embed(locale),
+        None => CreateEmbed::new().description(match locale {
+            "ru" => "Сейчас ничего не играет.",
+            _ => "Nothing is playing right now."
+        })
+    }.color(14441063)
+        .title(match locale {
+            "ru" => "Сейчас играет:",
+            _ => "Now playing:"
+        })
+        .footer(CreateEmbedFooter::new(match locale {
+            "ru" => format!("Громкость: {:.1} | Повтор: {} | В очереди: {}", volume, get_repeat_str(repeat, locale), queue_len),
+            _ => format!("Volume: {:.1} | Repeat: {} | In queue: {}", volume, get_repeat_str(repeat, locale), queue_len)
+        }));
+    if let Some(sleep) = sleep {
+        embed = embed.field(match locale {
+            "ru" => "Таймер сна",
+            _ => "Sleep timer"
+        }, sleep, false);
+    }
+
+    (embed, get_controls(paused, repeat))
+}
+
+pub fn get_controls(paused: bool, repeat: RepeatMode) -> Vec<CreateActionRow> {
+    vec![
+        CreateActionRow::Buttons(vec![
+            CreateButton::new("player_previous").emoji('⏮').style(ButtonStyle::Secondary),
+            match paused {
+                true => CreateButton::new("player_resume").emoji('▶').style(ButtonStyle::Success),
+                false => CreateButton::new("player_pause").emoji('⏸').style(ButtonStyle::Primary)
+            },
+            CreateButton::new("player_skip").emoji('⏭').style(ButtonStyle::Secondary),
+            CreateButton::new("player_like").emoji('❤').style(ButtonStyle::Secondary),
+        ]),
+        CreateActionRow::Buttons(vec![
+            CreateButton::new("player_shuffle").emoji('🔀').style(ButtonStyle::Secondary),
+            CreateButton::new("player_repeat").emoji(match repeat {
+                RepeatMode::Track => '🔂',
+                _ => '🔁'
+            }).style(match repeat {
+                RepeatMode::Off => ButtonStyle::Secondary,
+                _ => ButtonStyle::Success
+            }),
+            CreateButton::new("player_volume_down").emoji('🔉').style(ButtonStyle::Secondary),
+            CreateButton::new("player_volume_up").emoji('🔊').style(ButtonStyle::Secondary),
+        ])
+    ]
+}
+
+pub fn get_repeat_str(repeat: RepeatMode, locale: &str) -> &'static str {
+    match repeat {
+        RepeatMode::Off => match locale {
+            "ru" => "выкл",
+            _ => "off"
+        },
+        RepeatMode::Track => match locale {
+            "ru" => "трек",
+            _ => "track"
+        },
+        RepeatMode::Queue => match locale {
+            "ru" => "плейлист",
+            _ => "playlist"
+        }
+    }
+}
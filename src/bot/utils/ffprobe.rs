@@ -0,0 +1,264 @@
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{HashMap, VecDeque};
+use std::env;
+use std::hash::{Hash, Hasher};
+use std::path::PathBuf;
+use std::process::Stdio;
+use std::sync::{Mutex, OnceLock};
+use std::time::Duration;
+
+use chrono::Utc;
+use serde_json::Value;
+use tokio::process::Command;
+use tokio::time::timeout;
+
+use super::library::{get_library_paths, probe_file};
+use super::parser::get_time_str;
+use super::track::{Author, Chapter, ParserType, Track};
+
+const MEDIA_EXTENSIONS: [&str; 12] = ["mp3", "ogg", "oga", "opus", "flac", "wav", "m4a", "aac", "mka", "aif", "aiff", "m3u8"];
+const PROBE_TIMEOUT_SECS: u64 = 15;
+const ART_MAX_SIZE: usize = 4 * 1024 * 1024;
+const ART_CACHE_SIZE: usize = 128;
+
+struct ArtCache {
+    order: VecDeque<String>,
+    art: HashMap<String, (Vec<u8>, String)>,
+}
+
+fn art_cache() -> &'static Mutex<ArtCache> {
+    static ART_CACHE: OnceLock<Mutex<ArtCache>> = OnceLock::new();
+    ART_CACHE.get_or_init(|| Mutex::new(ArtCache { order: VecDeque::new(), art: HashMap::new() }))
+}
+
+fn cache_art(url: &str, data: Vec<u8>, media_type: String) -> Option<String> {
+    let public_url = env::var("PUBLIC_URL").ok()?;
+    let mut hasher = DefaultHasher::new();
+    url.hash(&mut hasher);
+    let key = format!("{:016x}", hasher.finish());
+
+    let mut cache = art_cache().lock().ok()?;
+    if cache.art.insert(key.clone(), (data, media_type)).is_none() {
+        cache.order.push_back(key.clone());
+    }
+    while cache.order.len() > ART_CACHE_SIZE {
+        if let Some(oldest) = cache.order.pop_front() {
+            cache.art.remove(&oldest);
+        }
+    }
+    Some(format!("{}/api/art/{}", public_url.trim_end_matches('/'), key))
+}
+
+pub fn get_cached_art(key: &str) -> Option<(Vec<u8>, String)> {
+    art_cache().lock().ok()?.art.get(key).cloned()
+}
+
+fn get_url_path(url: &str) -> &str {
+    let url = url.split(['?', '#']).next().unwrap_or(url);
+    match url.split_once("://") {
+        Some((_, rest)) => rest.find('/').map(|index| &rest[index..]).unwrap_or(""),
+        None => url
+    }
+}
+
+fn decode_percent(text: &str) -> String {
+    let bytes = text.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut index = 0;
+    while index < bytes.len() {
+        let hex = bytes.get(index + 1..index + 3)
+            .and_then(|hex| std::str::from_utf8(hex).ok())
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+        match (bytes[index], hex) {
+            (b'%', Some(byte)) => {
+                decoded.push(byte);
+                index += 3;
+            },
+            (byte, _) => {
+                decoded.push(byte);
+                index += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&decoded).to_string()
+}
+
+fn get_file_title(url: &str) -> Option<String> {
+    let name = decode_percent(get_url_path(url).rsplit('/').next()?);
+    let title = match name.rsplit_once('.') {
+        Some((stem, _)) if !stem.is_empty() => stem.to_string(),
+        _ => name
+    };
+    Some(title).filter(|title| !title.trim().is_empty())
+}
+
+pub fn is_direct_media_url(url: &str) -> bool {
+    if !(url.starts_with("http://") || url.starts_with("https://") || url.starts_with("ftp://")) {
+        return false;
+    }
+    match get_url_path(url).rsplit('/').next().and_then(|name| name.rsplit_once('.')) {
+        Some((_, extension)) => MEDIA_EXTENSIONS.contains(&extension.to_lowercase().as_str()),
+        None => false
+    }
+}
+
+pub fn get_local_path(url: &str) -> Option<PathBuf> {
+    let path = PathBuf::from(decode_percent(url.strip_prefix("file://")?)).canonicalize().ok()?;
+    let allowed = get_library_paths().iter()
+        .filter_map(|library_path| library_path.canonicalize().ok())
+        .any(|library_path| path.starts_with(library_path));
+    match allowed && path.is_file() {
+        true => Some(path),
+        false => None
+    }
+}
+
+pub async fn probe_media(url: &str, track_id: u64) -> Option<Track> {
+    if let Some(path) = get_local_path(url) {
+        return probe_local(path, url, track_id).await;
+    }
+    if is_direct_media_url(url) {
+        return probe_remote(url, track_id).await;
+    }
+    None
+}
+
+async fn probe_local(path: PathBuf, url: &str, track_id: u64) -> Option<Track> {
+    let probe_path = path.clone();
+    let probed = tokio::task::spawn_blocking(move || probe_file(&probe_path)).await.ok()??;
+    let path = path.to_string_lossy().to_string();
+    Some(Track {
+        id: track_id,
+        title: probed.title.or(get_file_title(&path)),
+        description: probed.album,
+        thumbnail: probed.art.and_then(|(media_type, data)| cache_art(&path, data, media_type)),
+        author: Author {
+            name: probed.artist,
+            url: None,
+            thumbnail: None,
+            verified: false,
+        },
+        url: path,
+        views: None,
+        likes: None,
+        chapters: probed.chapters,
+        webpage_url: url.to_string(),
+        duration: probed.duration,
+        is_live: false,
+        stream_title: None,
+        parse_time: Utc::now(),
+        parser_type: ParserType::Local,
+        edit_date: None,
+        start: None,
+        end: None,
+        requester: None,
+    })
+}
+
+async fn run_with_timeout(command: &mut Command) -> Option<Vec<u8>> {
+    let child = command
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .stdin(Stdio::null())
+        .kill_on_drop(true)
+        .spawn()
+        .ok()?;
+    match timeout(Duration::from_secs(PROBE_TIMEOUT_SECS), child.wait_with_output()).await {
+        Ok(Ok(output)) if output.status.success() => Some(output.stdout),
+        _ => None
+    }
+}
+
+fn get_tag(tags: Option<&Value>, key: &str) -> Option<String> {
+    tags?.as_object()?.iter()
+        .find(|(name, _)| name.eq_ignore_ascii_case(key))
+        .and_then(|(_, value)| value.as_str())
+        .map(|value| value.trim().to_string())
+        .filter(|value| !value.is_empty())
+}
+
+fn get_number(value: Option<&Value>) -> Option<f64> {
+    match value? {
+        Value::Number(number) => number.as_f64(),
+        Value::String(number) => number.parse::<f64>().ok(),
+        _ => None
+    }
+}
+
+async fn probe_remote(url: &str, track_id: u64) -> Option<Track> {
+    let output = run_with_timeout(Command::new("ffprobe")
+        .args(["-v", "quiet", "-print_format", "json", "-show_format", "-show_streams", "-show_chapters"])
+        .arg(url)).await?;
+    let data: Value = serde_json::from_slice(&output).ok()?;
+    let format = data.get("format")?;
+    let streams = data.get("streams").and_then(|streams| streams.as_array()).cloned().unwrap_or_default();
+    if !streams.iter().any(|stream| stream.get("codec_type").and_then(|codec_type| codec_type.as_str()) == Some("audio")) {
+        return None;
+    }
+
+    let tags = format.get("tags");
+    let duration = get_number(format.get("duration")).filter(|duration| *duration > 0.0);
+    let chapters = match data.get("chapters") {
+        Some(Value::Array(chapters)) => chapters.iter()
+            .enumerate()
+            .filter_map(|(index, chapter)| {
+                let start_time = get_number(chapter.get("start_time"))?;
+                Some(Chapter {
+                    title: get_tag(chapter.get("tags"), "title").unwrap_or(format!("#{}", index + 1)),
+                    start_time,
+                    start_time_str: get_time_str(start_time),
+                    end_time: get_number(chapter.get("end_time")).or(duration).unwrap_or(start_time),
+                })
+            })
+            .collect(),
+        _ => Vec::new()
+    };
+
+    let cover = streams.iter().find(|stream| {
+        stream.get("disposition").and_then(|disposition| disposition.get("attached_pic")).and_then(|attached_pic| attached_pic.as_i64()) == Some(1)
+    });
+    let thumbnail = match cover {
+        Some(cover) if env::var("PUBLIC_URL").is_ok() => {
+            let media_type = match cover.get("codec_name").and_then(|codec_name| codec_name.as_str()) {
+                Some("png") => "image/png",
+                Some("gif") => "image/gif",
+                Some("webp") => "image/webp",
+                _ => "image/jpeg"
+            };
+            let stream_index = cover.get("index").and_then(|index| index.as_i64()).unwrap_or(0);
+            match run_with_timeout(Command::new("ffmpeg")
+                .args(["-v", "quiet", "-i", url, "-map", &format!("0:{}", stream_index), "-c", "copy", "-frames:v", "1", "-f", "image2pipe", "-"])).await {
+                Some(data) if !data.is_empty() && data.len() <= ART_MAX_SIZE => cache_art(url, data, media_type.to_string()),
+                _ => None
+            }
+        },
+        _ => None
+    };
+
+    Some(Track {
+        id: track_id,
+        title: get_tag(tags, "title").or(get_tag(tags, "icy-name")).or(get_file_title(url)),
+        description: get_tag(tags, "album"),
+        thumbnail,
+        author: Author {
+            name: get_tag(tags, "artist").or(get_tag(tags, "album_artist")),
+            url: None,
+            thumbnail: None,
+            verified: false,
+        },
+        url: url.to_string(),
+        views: None,
+        likes: None,
+        chapters,
+        webpage_url: url.to_string(),
+        duration,
+        is_live: duration.is_none(),
+        stream_title: None,
+        parse_time: Utc::now(),
+        parser_type: ParserType::Ffprobe,
+        edit_date: None,
+        start: None,
+        end: None,
+        requester: None,
+    })
+}
@@ -0,0 +1,377 @@
+use std::io::Write;
+use std::process::ChildStdin;
+
+use super::player::PlayerSettings;
+
+const VOLUME_SCALE: f64 = 0.2;
+const BASS_FREQUENCY: f64 = 110.0;
+const BASS_WIDTH: f64 = 0.3;
+const EQUALIZER_BANDS: [(&str, &str, f64); 10] = [
+    ("h32", "32", 17.0),
+    ("h64", "64", 30.0),
+    ("h125", "125", 62.0),
+    ("h250", "250", 125.0),
+    ("h500", "500", 250.0),
+    ("h1k", "1k", 500.0),
+    ("h2k", "2k", 1000.0),
+    ("h4k", "4k", 2000.0),
+    ("h8k", "8k", 4000.0),
+    ("h16k", "16k", 8000.0),
+];
+const EFFECT_SAMPLE_RATE: f64 = 48000.0;
+const KARAOKE_MID_LEVEL: f64 = 0.015625;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Effect {
+    Nightcore,
+    Vaporwave,
+    EightD,
+    Karaoke,
+    Tremolo,
+    Vibrato,
+    Echo,
+    Reverb,
+}
+
+impl Effect {
+    pub const ALL: [Effect; 8] = [
+        Effect::Nightcore,
+        Effect::Vaporwave,
+        Effect::EightD,
+        Effect::Karaoke,
+        Effect::Tremolo,
+        Effect::Vibrato,
+        Effect::Echo,
+        Effect::Reverb,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Effect::Nightcore => "nightcore",
+            Effect::Vaporwave => "vaporwave",
+            Effect::EightD => "8d",
+            Effect::Karaoke => "karaoke",
+            Effect::Tremolo => "tremolo",
+            Effect::Vibrato => "vibrato",
+            Effect::Echo => "echo",
+            Effect::Reverb => "reverb",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|effect| effect.name() == name)
+    }
+
+    pub fn display_name(&self, locale: &str) -> &'static str {
+        match (self, locale) {
+            (Effect::Nightcore, _) => "Nightcore",
+            (Effect::Vaporwave, _) => "Vaporwave",
+            (Effect::EightD, _) => "8D",
+            (Effect::Karaoke, "ru") => "Караоке",
+            (Effect::Karaoke, _) => "Karaoke",
+            (Effect::Tremolo, "ru") => "Тремоло",
+            (Effect::Tremolo, _) => "Tremolo",
+            (Effect::Vibrato, "ru") => "Вибрато",
+            (Effect::Vibrato, _) => "Vibrato",
+            (Effect::Echo, "ru") => "Эхо",
+            (Effect::Echo, _) => "Echo",
+            (Effect::Reverb, "ru") => "Реверберация",
+            (Effect::Reverb, _) => "Reverb",
+        }
+    }
+
+    pub fn conflicts(&self) -> &'static [Effect] {
+        match self {
+            Effect::Nightcore => &[Effect::Vaporwave],
+            Effect::Vaporwave => &[Effect::Nightcore],
+            _ => &[]
+        }
+    }
+
+    pub fn rate(&self) -> f64 {
+        match self {
+            Effect::Nightcore => 1.25,
+            Effect::Vaporwave => 0.8,
+            _ => 1.0
+        }
+    }
+
+    pub fn is_live(&self) -> bool {
+        matches!(self, Effect::Karaoke)
+    }
+
+    fn render(&self, enabled: bool) -> Option<String> {
+        if !enabled && !self.is_live() {
+            return None;
+        }
+        Some(match self {
+            Effect::Nightcore | Effect::Vaporwave => format!("aresample={},asetrate={},aresample={}", EFFECT_SAMPLE_RATE, EFFECT_SAMPLE_RATE * self.rate(), EFFECT_SAMPLE_RATE),
+            Effect::EightD => "apulsator=hz=0.125".to_string(),
+            Effect::Karaoke => format!("stereotools=mlev={}", if enabled {KARAOKE_MID_LEVEL} else {1.0}),
+            Effect::Tremolo => "tremolo=f=6:d=0.6".to_string(),
+            Effect::Vibrato => "vibrato=f=6:d=0.5".to_string(),
+            Effect::Echo => "aecho=0.8:0.88:500:0.3".to_string(),
+            Effect::Reverb => "aecho=0.8:0.88:40|70|100:0.4|0.3|0.2".to_string(),
+        })
+    }
+
+    fn command(&self, enabled: bool) -> Option<String> {
+        match self {
+            Effect::Karaoke => Some(format!("^Cstereotools -1 mlev {}\n", if enabled {KARAOKE_MID_LEVEL} else {1.0})),
+            _ => None
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Effects(i32);
+
+impl Effects {
+    pub fn from_bits(bits: i32) -> Self {
+        Self(bits & Self::all_bits())
+    }
+
+    pub fn bits(&self) -> i32 {
+        self.0
+    }
+
+    fn all_bits() -> i32 {
+        Effect::ALL.into_iter().fold(0, |bits, effect| bits | Self::bit(effect))
+    }
+
+    fn bit(effect: Effect) -> i32 {
+        Effect::ALL.iter().position(|value| *value == effect).map_or(0, |index| 1 << index)
+    }
+
+    pub fn get(&self, effect: Effect) -> bool {
+        self.0 & Self::bit(effect) != 0
+    }
+
+    pub fn set(&mut self, effect: Effect, enabled: bool) {
+        match enabled {
+            true => {
+                effect.conflicts().iter().for_each(|conflict| self.set(*conflict, false));
+                self.0 |= Self::bit(effect);
+            },
+            false => self.0 &= !Self::bit(effect)
+        }
+    }
+
+    pub fn enabled(&self) -> impl Iterator<Item = Effect> + '_ {
+        Effect::ALL.into_iter().filter(move |effect| self.get(*effect))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Filter {
+    Volume(f64),
+    Tempo(f64),
+    Bass(f64),
+    Equalizer { band: &'static str, frequency: &'static str, width: f64, gain: f64 },
+    Effect(Effect, bool),
+}
+
+impl Filter {
+    fn target(&self) -> String {
+        match self {
+            Filter::Volume(_) => "volume".to_string(),
+            Filter::Tempo(_) => "atempo".to_string(),
+            Filter::Bass(_) => "bass".to_string(),
+            Filter::Equalizer { band, .. } => format!("equalizer@{}", band),
+            Filter::Effect(effect, _) => effect.name().to_string(),
+        }
+    }
+
+    pub fn is_live(&self) -> bool {
+        match self {
+            Filter::Effect(effect, _) => effect.is_live(),
+            _ => true
+        }
+    }
+
+    pub fn render(&self) -> Option<String> {
+        Some(match self {
+            Filter::Volume(volume) => format!("volume={}", volume * VOLUME_SCALE),
+            Filter::Tempo(tempo) => match *tempo != 1.0 {
+                true => format!("atempo={}", tempo),
+                false => "atempo".to_string()
+            },
+            Filter::Bass(gain) => format!("bass=g={}:f={}:w={}", gain, BASS_FREQUENCY, BASS_WIDTH),
+            Filter::Equalizer { band, frequency, width, gain } => match *gain != 0.0 {
+                true => format!("equalizer@{}=f={}:t=h:w={}:g={}", band, frequency, width, gain),
+                false => format!("equalizer@{}=f={}:t=h:w={}", band, frequency, width)
+            },
+            Filter::Effect(effect, enabled) => return effect.render(*enabled)
+        })
+    }
+
+    pub fn command(&self) -> Option<String> {
+        let (command, value) = match self {
+            Filter::Volume(volume) => ("volume", volume * VOLUME_SCALE),
+            Filter::Tempo(tempo) => ("tempo", *tempo),
+            Filter::Bass(gain) => ("g", *gain),
+            Filter::Equalizer { gain, .. } => ("g", *gain),
+            Filter::Effect(effect, enabled) => return effect.command(*enabled)
+        };
+        Some(format!("^C{} -1 {} {}\n", self.target(), command, value))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct FilterGraph {
+    filters: Vec<Filter>,
+}
+
+impl FilterGraph {
+    pub fn new(settings: &PlayerSettings) -> Self {
+        let equalizer = &settings.equalizer;
+        let gains = [
+            equalizer.f_32, equalizer.f_64, equalizer.f_125, equalizer.f_250, equalizer.f_500,
+            equalizer.f_1k, equalizer.f_2k, equalizer.f_4k, equalizer.f_8k, equalizer.f_16k
+        ];
+        let mut filters = vec![
+            Filter::Volume(settings.volume),
+            Filter::Tempo(settings.speed),
+            Filter::Bass(if settings.bass_enabled {settings.bass_gain} else {0.0}),
+        ];
+        filters.extend(EQUALIZER_BANDS.iter().zip(gains).map(|((band, frequency, width), gain)| Filter::Equalizer {
+            band,
+            frequency,
+            width: *width,
+            gain,
+        }));
+        filters.extend(Effect::ALL.into_iter().map(|effect| Filter::Effect(effect, settings.effects.get(effect))));
+        Self { filters }
+    }
+
+    pub fn render(&self) -> String {
+        self.filters.iter()
+            .filter_map(|filter| filter.render())
+            .collect::<Vec<String>>()
+            .join(",")
+    }
+
+    pub fn requires_restart(&self, previous: &FilterGraph) -> bool {
+        self.filters.iter().any(|filter| !filter.is_live() && !previous.filters.contains(filter))
+    }
+
+    pub fn commands(&self, previous: &FilterGraph) -> Vec<String> {
+        self.filters.iter()
+            .filter(|filter| !previous.filters.contains(filter))
+            .filter_map(|filter| filter.command())
+            .collect()
+    }
+
+    pub fn apply(&self, previous: &FilterGraph, ffmpeg: &mut ChildStdin) {
+        for command in self.commands(previous) {
+            let _ = ffmpeg.write(command.as_bytes());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn settings() -> PlayerSettings {
+        PlayerSettings::new_with_pool(Err::<_, ()>(()), 0).await
+    }
+
+    #[test]
+    fn stores_effects_as_bits() {
+        let mut effects = Effects::default();
+        effects.set(Effect::Nightcore, true);
+        effects.set(Effect::Reverb, true);
+        effects.set(Effect::Vaporwave, true);
+        assert_eq!(effects.enabled().collect::<Vec<Effect>>(), vec![Effect::Vaporwave, Effect::Reverb]);
+        assert_eq!(Effects::from_bits(effects.bits()), effects);
+        assert_eq!(Effects::from_bits(-1).enabled().count(), Effect::ALL.len());
+        effects.set(Effect::Reverb, false);
+        assert!(!effects.get(Effect::Reverb));
+    }
+
+    #[tokio::test]
+    async fn renders_default_graph() {
+        let graph = FilterGraph::new(&settings().await);
+        assert_eq!(graph.render(), [
+            "volume=0.2",
+            "atempo",
+            "bass=g=0:f=110:w=0.3",
+            "equalizer@h32=f=32:t=h:w=17",
+            "equalizer@h64=f=64:t=h:w=30",
+            "equalizer@h125=f=125:t=h:w=62",
+            "equalizer@h250=f=250:t=h:w=125",
+            "equalizer@h500=f=500:t=h:w=250",
+            "equalizer@h1k=f=1k:t=h:w=500",
+            "equalizer@h2k=f=2k:t=h:w=1000",
+            "equalizer@h4k=f=4k:t=h:w=2000",
+            "equalizer@h8k=f=8k:t=h:w=4000",
+            "equalizer@h16k=f=16k:t=h:w=8000",
+            "stereotools=mlev=1",
+        ].join(","));
+    }
+
+    #[tokio::test]
+    async fn renders_bass_equalizer_and_tempo() {
+        let mut settings = settings().await;
+        settings.speed = 1.5;
+        settings.bass_enabled = true;
+        settings.bass_gain = 12.0;
+        settings.equalizer.f_1k = -4.5;
+        let filters: Vec<String> = FilterGraph::new(&settings).render().split(',').map(|filter| filter.to_string()).collect();
+        assert!(filters.contains(&"atempo=1.5".to_string()));
+        assert!(filters.contains(&"bass=g=12:f=110:w=0.3".to_string()));
+        assert!(filters.contains(&"equalizer@h1k=f=1k:t=h:w=500:g=-4.5".to_string()));
+        assert!(filters.contains(&"equalizer@h2k=f=2k:t=h:w=1000".to_string()));
+    }
+
+    #[tokio::test]
+    async fn renders_effects() {
+        let mut settings = settings().await;
+        settings.effects.set(Effect::Nightcore, true);
+        settings.effects.set(Effect::Karaoke, true);
+        let rendered = FilterGraph::new(&settings).render();
+        assert!(rendered.contains("aresample=48000,asetrate=60000,aresample=48000"));
+        assert!(rendered.contains("stereotools=mlev=0.015625"));
+        assert!(!rendered.contains("apulsator"));
+    }
+
+    #[tokio::test]
+    async fn emits_only_changed_filters() {
+        let mut settings = settings().await;
+        let previous = FilterGraph::new(&settings);
+        assert!(FilterGraph::new(&settings).commands(&previous).is_empty());
+
+        settings.volume = 2.0;
+        settings.equalizer.f_64 = 3.0;
+        assert_eq!(FilterGraph::new(&settings).commands(&previous), vec![
+            "^Cvolume -1 volume 0.4\n".to_string(),
+            "^Cequalizer@h64 -1 g 3\n".to_string(),
+        ]);
+
+        let previous = FilterGraph::new(&settings);
+        settings.effects.set(Effect::Karaoke, true);
+        assert_eq!(FilterGraph::new(&settings).commands(&previous), vec![
+            "^Cstereotools -1 mlev 0.015625\n".to_string(),
+        ]);
+    }
+
+    #[tokio::test]
+    async fn restarts_only_for_non_live_filters() {
+        let mut settings = settings().await;
+        let previous = FilterGraph::new(&settings);
+
+        settings.volume = 0.5;
+        settings.equalizer.f_16k = 6.0;
+        settings.effects.set(Effect::Karaoke, true);
+        assert!(!FilterGraph::new(&settings).requires_restart(&previous));
+
+        settings.effects.set(Effect::Nightcore, true);
+        let nightcore = FilterGraph::new(&settings);
+        assert!(nightcore.requires_restart(&previous));
+        assert!(nightcore.commands(&previous).iter().all(|command| !command.contains("asetrate")));
+
+        settings.effects.set(Effect::Nightcore, false);
+        assert!(FilterGraph::new(&settings).requires_restart(&nightcore));
+    }
+}
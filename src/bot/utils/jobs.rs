@@ -0,0 +1,201 @@
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use serenity::all::CommandInteraction;
+use serenity::builder::EditInteractionResponse;
+use serenity::client::Context;
+use serenity::futures::future::{BoxFuture, FutureExt};
+
+use super::check_msg;
+use super::player::{enqueue_track, Player};
+use super::queue_file::{resolve_entry, QueueEntry};
+use super::track::Track;
+
+const PROGRESS_INTERVAL_SECS: u64 = 2;
+const FINISHED_JOBS_KEPT: usize = 10;
+
+static LAST_JOB_ID: AtomicU64 = AtomicU64::new(0);
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum JobState {
+    Running,
+    Finished,
+    Cancelled,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct JobStatus {
+    pub id: u64,
+    pub source: String,
+    pub requester: Option<u64>,
+    pub started_at: DateTime<Utc>,
+    pub state: JobState,
+    pub total: usize,
+    pub added: usize,
+    pub failed: usize,
+}
+
+impl JobStatus {
+    pub fn get_progress_str(&self, locale: &str) -> String {
+        let progress = match locale {
+            "ru" => format!("{}/{} добавлено, {} с ошибкой", self.added, self.total, self.failed),
+            _ => format!("{}/{} added, {} failed", self.added, self.total, self.failed)
+        };
+        match (self.state, locale) {
+            (JobState::Running, _) => progress,
+            (JobState::Finished, "ru") => format!("Импорт завершён: {}", progress),
+            (JobState::Finished, _) => format!("Import finished: {}", progress),
+            (JobState::Cancelled, "ru") => format!("Импорт отменён: {}", progress),
+            (JobState::Cancelled, _) => format!("Import cancelled: {}", progress)
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct ImportJob {
+    pub id: u64,
+    pub source: String,
+    pub requester: Option<u64>,
+    pub started_at: DateTime<Utc>,
+    pub total: usize,
+    added: AtomicUsize,
+    failed: AtomicUsize,
+    cancelled: AtomicBool,
+    finished: AtomicBool,
+}
+
+impl ImportJob {
+    fn new(source: String, requester: Option<u64>, total: usize) -> Self {
+        Self {
+            id: LAST_JOB_ID.fetch_add(1, Ordering::Relaxed) + 1,
+            source,
+            requester,
+            started_at: Utc::now(),
+            total,
+            added: AtomicUsize::new(0),
+            failed: AtomicUsize::new(0),
+            cancelled: AtomicBool::new(false),
+            finished: AtomicBool::new(false),
+        }
+    }
+
+    pub fn cancel(&self) -> bool {
+        !self.finished.load(Ordering::Relaxed) && !self.cancelled.swap(true, Ordering::Relaxed)
+    }
+
+    pub fn is_running(&self) -> bool {
+        !self.finished.load(Ordering::Relaxed)
+    }
+
+    pub fn status(&self) -> JobStatus {
+        JobStatus {
+            id: self.id,
+            source: self.source.clone(),
+            requester: self.requester,
+            started_at: self.started_at,
+            state: match (self.finished.load(Ordering::Relaxed), self.cancelled.load(Ordering::Relaxed)) {
+                (false, _) => JobState::Running,
+                (true, true) => JobState::Cancelled,
+                (true, false) => JobState::Finished
+            },
+            total: self.total,
+            added: self.added.load(Ordering::Relaxed),
+            failed: self.failed.load(Ordering::Relaxed),
+        }
+    }
+}
+
+pub async fn get_jobs(player: &Player) -> Vec<JobStatus> {
+    player.jobs.read().await.iter().map(|job| job.status()).collect()
+}
+
+pub async fn cancel_jobs(player: &Player, job_id: Option<u64>) -> usize {
+    player.jobs.read().await.iter()
+        .filter(|job| job_id.map(|job_id| job.id == job_id).unwrap_or(true))
+        .filter(|job| job.cancel())
+        .count()
+}
+
+pub async fn spawn_import_job<E, P>(player: Arc<Player>, source: String, requester: Option<u64>, entries: Vec<QueueEntry>, enqueue: E, progress: P) -> Arc<ImportJob>
+where
+    E: Fn(Track) -> BoxFuture<'static, ()> + Send + Sync + 'static,
+    P: Fn(JobStatus) -> BoxFuture<'static, ()> + Send + Sync + 'static
+{
+    let job = Arc::new(ImportJob::new(source, requester, entries.len()));
+    {
+        let mut jobs = player.jobs.write().await;
+        let finished = jobs.iter().filter(|job| !job.is_running()).count();
+        if finished >= FINISHED_JOBS_KEPT {
+            let mut excess = finished + 1 - FINISHED_JOBS_KEPT;
+            jobs.retain(|job| {
+                if excess > 0 && !job.is_running() {
+                    excess -= 1;
+                    return false;
+                }
+                true
+            });
+        }
+        jobs.push(job.clone());
+    }
+
+    let task_job = job.clone();
+    tokio::spawn(async move {
+        let job = task_job;
+        let mut last_progress = Instant::now();
+        for entry in entries {
+            if job.cancelled.load(Ordering::Relaxed) {
+                break;
+            }
+            let id = {
+                let mut last_id = player.playlist_sync_and_last_id.lock().await;
+                *last_id+=1;
+                *last_id
+            };
+            match resolve_entry(entry, id).await {
+                Some(mut track) => {
+                    track.requester = job.requester.or(track.requester);
+                    enqueue(track).await;
+                    job.added.fetch_add(1, Ordering::Relaxed);
+                },
+                None => {
+                    job.failed.fetch_add(1, Ordering::Relaxed);
+                }
+            }
+            if last_progress.elapsed() >= Duration::from_secs(PROGRESS_INTERVAL_SECS) {
+                last_progress = Instant::now();
+                progress(job.status()).await;
+            }
+        }
+        job.finished.store(true, Ordering::Relaxed);
+        progress(job.status()).await;
+    });
+    job
+}
+
+pub async fn spawn_import_job_ctx(ctx: &Context, command: &CommandInteraction, player: Arc<Player>, source: String, entries: Vec<QueueEntry>) -> Arc<ImportJob> {
+    let enqueue_ctx = ctx.clone();
+    let enqueue_player = player.clone();
+    let http = ctx.http.clone();
+    let command = command.clone();
+    spawn_import_job(player, source, Some(command.user.id.get()), entries,
+        move |track| {
+            let ctx = enqueue_ctx.clone();
+            let player = enqueue_player.clone();
+            async move {
+                enqueue_track(&ctx, &player, track).await;
+            }.boxed()
+        },
+        move |status| {
+            let http = http.clone();
+            let command = command.clone();
+            async move {
+                let builder = EditInteractionResponse::new().content(status.get_progress_str(command.locale.as_str()));
+                check_msg(command.edit_response(&http, builder).await);
+            }.boxed()
+        }
+    ).await
+}
@@ -0,0 +1,278 @@
+use std::collections::{HashMap, HashSet};
+use std::env;
+use std::fs::File;
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+
+use chrono::Utc;
+use diesel::{BoolExpressionMethods, EscapeExpressionMethods, ExpressionMethods, OptionalExtension, QueryDsl, QueryResult, RunQueryDsl, SelectableHelper, SqliteConnection, TextExpressionMethods};
+use symphonia::core::formats::FormatOptions;
+use symphonia::core::io::MediaSourceStream;
+use symphonia::core::meta::{MetadataOptions, MetadataRevision, StandardTagKey, StandardVisualKey, Tag};
+use symphonia::core::probe::Hint;
+use symphonia::core::units::TimeBase;
+use walkdir::WalkDir;
+
+use super::parser::get_time_str;
+use super::track::{Author, Chapter, ParserType, Track};
+use crate::models::{LibraryTrackDB, NewLibraryTrack};
+
+const AUDIO_EXTENSIONS: [&str; 14] = ["mp3", "flac", "ogg", "oga", "opus", "wav", "m4a", "aac", "alac", "mka", "webm", "aif", "aiff", "caf"];
+
+#[derive(Debug, Default)]
+pub struct LibraryScanResult {
+    pub added: usize,
+    pub updated: usize,
+    pub removed: usize,
+    pub unchanged: usize,
+    pub failed: usize,
+}
+
+#[derive(Debug, Default)]
+pub struct ProbedFile {
+    pub title: Option<String>,
+    pub artist: Option<String>,
+    pub album: Option<String>,
+    pub duration: Option<f64>,
+    pub chapters: Vec<Chapter>,
+    pub art: Option<(String, Vec<u8>)>,
+}
+
+pub fn get_library_paths() -> Vec<PathBuf> {
+    match env::var_os("LIBRARY_PATHS") {
+        Some(paths) => env::split_paths(&paths).filter(|path| path.is_dir()).collect(),
+        None => Vec::new()
+    }
+}
+
+fn read_revision(probed: &mut ProbedFile, revision: &MetadataRevision) {
+    let get_tag = |tags: &[Tag], key: StandardTagKey| tags.iter()
+        .find(|tag| tag.std_key == Some(key))
+        .map(|tag| tag.value.to_string())
+        .filter(|value| !value.trim().is_empty());
+    let tags = revision.tags();
+    probed.title = probed.title.take().or(get_tag(tags, StandardTagKey::TrackTitle));
+    probed.artist = probed.artist.take().or(get_tag(tags, StandardTagKey::Artist)).or(get_tag(tags, StandardTagKey::AlbumArtist));
+    probed.album = probed.album.take().or(get_tag(tags, StandardTagKey::Album));
+    if probed.art.is_none() {
+        let visual = revision.visuals().iter()
+            .find(|visual| visual.usage == Some(StandardVisualKey::FrontCover))
+            .or(revision.visuals().first());
+        if let Some(visual) = visual {
+            probed.art = Some((visual.media_type.clone(), visual.data.to_vec()));
+        }
+    }
+}
+
+pub fn probe_file(path: &Path) -> Option<ProbedFile> {
+    let file = File::open(path).ok()?;
+    let stream = MediaSourceStream::new(Box::new(file), Default::default());
+    let mut hint = Hint::new();
+    if let Some(extension) = path.extension().and_then(|extension| extension.to_str()) {
+        hint.with_extension(extension);
+    }
+    let mut result = symphonia::default::get_probe()
+        .format(&hint, stream, &FormatOptions::default(), &MetadataOptions::default())
+        .ok()?;
+
+    let mut probed = ProbedFile::default();
+    if let Some(metadata) = result.metadata.get() {
+        if let Some(revision) = metadata.current() {
+            read_revision(&mut probed, revision);
+        }
+    }
+    if let Some(revision) = result.format.metadata().current() {
+        read_revision(&mut probed, revision);
+    }
+
+    let params = result.format.default_track().map(|track| track.codec_params.clone());
+    let time_base = params.as_ref().and_then(|params| params.time_base.or(params.sample_rate.map(|rate| TimeBase::new(1, rate))));
+    if let (Some(params), Some(time_base)) = (&params, time_base) {
+        probed.duration = params.n_frames.map(|frames| {
+            let time = time_base.calc_time(frames);
+            time.seconds as f64 + time.frac
+        });
+        let starts: Vec<(f64, Option<String>)> = result.format.cues().iter()
+            .map(|cue| {
+                let time = time_base.calc_time(cue.start_ts);
+                let title = cue.tags.iter()
+                    .find(|tag| tag.std_key == Some(StandardTagKey::TrackTitle))
+                    .or(cue.tags.first())
+                    .map(|tag| tag.value.to_string());
+                (time.seconds as f64 + time.frac, title)
+            })
+            .collect();
+        probed.chapters = starts.iter()
+            .enumerate()
+            .map(|(index, (start, title))| Chapter {
+                title: title.clone().unwrap_or(format!("#{}", index + 1)),
+                start_time: *start,
+                start_time_str: get_time_str(*start),
+                end_time: starts.get(index + 1).map(|(end, _)| *end).or(probed.duration).unwrap_or(*start),
+            })
+            .collect();
+    }
+    Some(probed)
+}
+
+pub fn scan_library(conn: &mut SqliteConnection, paths: &[PathBuf]) -> QueryResult<LibraryScanResult> {
+    use crate::schema::library_tracks::dsl;
+    let mut result = LibraryScanResult::default();
+    let indexed: HashMap<String, (i32, i64, i64)> = dsl::library_tracks
+        .select((dsl::id, dsl::path, dsl::modified, dsl::size))
+        .load::<(i32, String, i64, i64)>(conn)?
+        .into_iter()
+        .map(|(id, path, modified, size)| (path, (id, modified, size)))
+        .collect();
+    let mut seen = HashSet::new();
+
+    for entry in paths.iter().flat_map(|path| WalkDir::new(path).follow_links(true)).filter_map(|entry| entry.ok()) {
+        let is_audio = entry.path().extension()
+            .and_then(|extension| extension.to_str())
+            .map(|extension| AUDIO_EXTENSIONS.contains(&extension.to_lowercase().as_str()))
+            .unwrap_or(false);
+        if !entry.file_type().is_file() || !is_audio {
+            continue;
+        }
+        let path = entry.path().to_string_lossy().to_string();
+        let (modified, size) = match entry.metadata() {
+            Ok(metadata) => (
+                metadata.modified().ok().and_then(|time| time.duration_since(UNIX_EPOCH).ok()).map(|time| time.as_secs() as i64).unwrap_or(0),
+                metadata.len() as i64
+            ),
+            Err(_) => {
+                result.failed += 1;
+                continue;
+            }
+        };
+        seen.insert(path.clone());
+
+        let existing = indexed.get(&path);
+        if let Some((_, indexed_modified, indexed_size)) = existing {
+            if *indexed_modified == modified && *indexed_size == size {
+                result.unchanged += 1;
+                continue;
+            }
+        }
+        let probed = match probe_file(entry.path()) {
+            Some(probed) => probed,
+            None => {
+                result.failed += 1;
+                continue;
+            }
+        };
+        let (art_type, art) = match probed.art {
+            Some((art_type, art)) => (Some(art_type), Some(art)),
+            None => (None, None)
+        };
+        let new_track = NewLibraryTrack {
+            path: path.clone(),
+            modified,
+            size,
+            title: probed.title.or(entry.path().file_stem().map(|stem| stem.to_string_lossy().to_string())),
+            artist: probed.artist,
+            album: probed.album,
+            duration: probed.duration,
+            chapters: serde_json::to_string(&probed.chapters).unwrap_or("[]".to_string()),
+            art,
+            art_type,
+        };
+        diesel::insert_into(dsl::library_tracks)
+            .values(&new_track)
+            .on_conflict(dsl::path)
+            .do_update()
+            .set(&new_track)
+            .execute(conn)?;
+        match existing {
+            Some(_) => result.updated += 1,
+            None => result.added += 1
+        }
+    }
+
+    let removed: Vec<i32> = indexed.iter()
+        .filter(|(path, _)| !seen.contains(*path))
+        .map(|(_, (id, _, _))| *id)
+        .collect();
+    if !removed.is_empty() {
+        result.removed = diesel::delete(dsl::library_tracks.filter(dsl::id.eq_any(removed))).execute(conn)?;
+    }
+    Ok(result)
+}
+
+pub fn search_library(conn: &mut SqliteConnection, query: &str, limit: i64) -> QueryResult<Vec<LibraryTrackDB>> {
+    use crate::schema::library_tracks::dsl::*;
+    let pattern = format!("%{}%", query.trim().replace('\\', "\\\\").replace('%', "\\%").replace('_', "\\_"));
+    library_tracks
+        .filter(title.like(&pattern).escape('\\')
+            .or(artist.like(&pattern).escape('\\'))
+            .or(album.like(&pattern).escape('\\'))
+            .or(path.like(&pattern).escape('\\')))
+        .order((artist.asc(), album.asc(), title.asc()))
+        .limit(limit)
+        .select(LibraryTrackDB::as_select())
+        .load(conn)
+}
+
+pub fn get_library_track(conn: &mut SqliteConnection, library_id: i32) -> QueryResult<Option<LibraryTrackDB>> {
+    use crate::schema::library_tracks::dsl::*;
+    library_tracks
+        .filter(id.eq(library_id))
+        .select(LibraryTrackDB::as_select())
+        .first(conn)
+        .optional()
+}
+
+pub fn get_library_art(conn: &mut SqliteConnection, library_id: i32) -> QueryResult<Option<(Vec<u8>, String)>> {
+    use crate::schema::library_tracks::dsl::*;
+    let result: Option<(Option<Vec<u8>>, Option<String>)> = library_tracks
+        .filter(id.eq(library_id).and(art.is_not_null()))
+        .select((art, art_type))
+        .first(conn)
+        .optional()?;
+    Ok(result.and_then(|(data, media_type)| Some((data?, media_type.unwrap_or("image/jpeg".to_string())))))
+}
+
+pub fn get_library_track_str(library_track: &LibraryTrackDB) -> String {
+    let title = library_track.title.clone().unwrap_or(library_track.path.clone());
+    let mut string = match &library_track.artist {
+        Some(artist) => format!("{} - {}", artist, title),
+        None => title
+    };
+    if string.chars().count() > 100 {
+        string = string.chars().take(100).collect();
+    }
+    string
+}
+
+pub fn get_track(library_track: &LibraryTrackDB, id: u64) -> Track {
+    let thumbnail = match (library_track.has_art, env::var("PUBLIC_URL")) {
+        (true, Ok(public_url)) => Some(format!("{}/api/library/{}/art", public_url.trim_end_matches('/'), library_track.id)),
+        _ => None
+    };
+    Track {
+        id,
+        title: library_track.title.clone(),
+        description: library_track.album.clone(),
+        thumbnail,
+        author: Author {
+            name: library_track.artist.clone(),
+            url: None,
+            thumbnail: None,
+            verified: false,
+        },
+        url: library_track.path.clone(),
+        views: None,
+        likes: None,
+        chapters: serde_json::from_str(&library_track.chapters).unwrap_or_default(),
+        webpage_url: format!("library://{}", library_track.id),
+        duration: library_track.duration,
+        is_live: false,
+        stream_title: None,
+        parse_time: Utc::now(),
+        parser_type: ParserType::Local,
+        edit_date: None,
+        start: None,
+        end: None,
+        requester: None,
+    }
+}
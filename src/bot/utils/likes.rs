@@ -0,0 +1,139 @@
+use diesel::{BoolExpressionMethods, ExpressionMethods, OptionalExtension, QueryDsl, QueryResult, RunQueryDsl, SelectableHelper, SqliteConnection};
+use serenity::all::ButtonStyle;
+use serenity::builder::{CreateActionRow, CreateButton, CreateEmbed, CreateEmbedFooter};
+use serenity::client::Context;
+
+use super::parser::get_time_str;
+use super::player::PlayerDataBase;
+use super::track::Track;
+use crate::models::{LikeDB, NewLike};
+
+pub const LIKES_PAGE_SIZE: i64 = 10;
+
+pub fn toggle_like(conn: &mut SqliteConnection, user: u64, liked_track: &Track) -> QueryResult<bool> {
+    use crate::schema::likes::dsl::*;
+    let existing = likes
+        .filter(user_id.eq(user as i64).and(webpage_url.eq(&liked_track.webpage_url)))
+        .select(id)
+        .first::<i32>(conn)
+        .optional()?;
+    match existing {
+        Some(like_id) => {
+            diesel::delete(likes.filter(id.eq(like_id))).execute(conn)?;
+            Ok(false)
+        },
+        None => {
+            let mut saved_track = liked_track.clone();
+            saved_track.requester = None;
+            diesel::insert_into(likes)
+                .values(NewLike {
+                    user_id: user as i64,
+                    webpage_url: saved_track.webpage_url.clone(),
+                    title: saved_track.title.clone(),
+                    duration: saved_track.duration,
+                    track: serde_json::to_string(&saved_track).unwrap_or_default(),
+                })
+                .execute(conn)?;
+            Ok(true)
+        }
+    }
+}
+
+pub async fn like_current_track(ctx: &Context, track: Option<Track>, user: u64, locale: &str) -> String {
+    let track = match track {
+        Some(track) => track,
+        None => return match locale {
+            "ru" => "Сейчас ничего не играет!",
+            _ => "Nothing is playing!"
+        }.to_string()
+    };
+    let pool = {
+        let data_read = ctx.data.read().await;
+        let conn = data_read.get::<PlayerDataBase>().expect("Expected PlayerDataBase in TypeMap.").clone();
+        conn.get()
+    };
+    let title = track.title.clone().unwrap_or(track.webpage_url.clone());
+    match pool.map(|mut conn| toggle_like(&mut conn, user, &track)) {
+        Ok(Ok(true)) => match locale {
+            "ru" => format!("Добавлено в избранное: {}", title),
+            _ => format!("Added to your likes: {}", title)
+        },
+        Ok(Ok(false)) => match locale {
+            "ru" => format!("Удалено из избранного: {}", title),
+            _ => format!("Removed from your likes: {}", title)
+        },
+        _ => match locale {
+            "ru" => "Не удалось сохранить трек!",
+            _ => "Failed to save the track!"
+        }.to_string()
+    }
+}
+
+pub fn get_likes_page(conn: &mut SqliteConnection, user: u64, page: i64) -> QueryResult<(Vec<LikeDB>, i64)> {
+    use crate::schema::likes::dsl::*;
+    let total: i64 = likes.filter(user_id.eq(user as i64)).count().get_result(conn)?;
+    let page = likes
+        .filter(user_id.eq(user as i64))
+        .order(id.desc())
+        .offset(page.max(0) * LIKES_PAGE_SIZE)
+        .limit(LIKES_PAGE_SIZE)
+        .select(LikeDB::as_select())
+        .load(conn)?;
+    Ok((page, total))
+}
+
+pub fn get_liked_tracks(conn: &mut SqliteConnection, user: u64) -> QueryResult<Vec<Track>> {
+    use crate::schema::likes::dsl::*;
+    let saved: Vec<String> = likes
+        .filter(user_id.eq(user as i64))
+        .order(id.desc())
+        .select(track)
+        .load(conn)?;
+    Ok(saved.iter().filter_map(|saved_track| serde_json::from_str::<Track>(saved_track).ok()).collect())
+}
+
+pub fn get_likes_message(liked: &[LikeDB], total: i64, page: i64, user: u64, locale: &str) -> (CreateEmbed, Vec<CreateActionRow>) {
+    let pages = ((total + LIKES_PAGE_SIZE - 1) / LIKES_PAGE_SIZE).max(1);
+    let description = match liked.is_empty() {
+        true => match locale {
+            "ru" => "Список избранного пуст.",
+            _ => "You have no liked tracks."
+        }.to_string(),
+        false => liked.iter()
+            .enumerate()
+            .map(|(index, like)| {
+                let title = match &like.title {
+                    Some(title) => title.clone(),
+                    None => match locale {
+                        "ru" => "Название не известно",
+                        _ => "Unknown title"
+                    }.to_string()
+                };
+                let duration = match like.duration {
+                    Some(duration) => format!(" `{}`", get_time_str(duration)),
+                    None => "".to_string()
+                };
+                format!("{}. [{}]({}){}", page * LIKES_PAGE_SIZE + index as i64 + 1, title, like.webpage_url, duration)
+            })
+            .collect::<Vec<String>>()
+            .join("\n")
+    };
+    let embed = CreateEmbed::new()
+        .color(14441063)
+        .title(match locale {
+            "ru" => "Избранное:",
+            _ => "Liked tracks:"
+        })
+        .description(description)
+        .footer(CreateEmbedFooter::new(match locale {
+            "ru" => format!("Страница {}/{} • Треков: {}", page + 1, pages, total),
+            _ => format!("Page {}/{} • {} tracks", page + 1, pages, total)
+        }));
+    let components = vec![
+        CreateActionRow::Buttons(vec![
+            CreateButton::new(format!("likes_page:{}:{}", user, page - 1)).emoji('◀').style(ButtonStyle::Secondary).disabled(page <= 0),
+            CreateButton::new(format!("likes_page:{}:{}", user, page + 1)).emoji('▶').style(ButtonStyle::Secondary).disabled(page + 1 >= pages),
+        ])
+    ];
+    (embed, components)
+}
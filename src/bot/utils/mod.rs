@@ -1,9 +1,33 @@
-use serenity::{all::{ChannelId, CommandInteraction}, client::Context, builder::{CreateInteractionResponseMessage, CreateInteractionResponse}};
+use std::sync::OnceLock;
+use std::time::Duration;
+
+use serenity::{all::{ChannelId, CommandInteraction, GuildId, UserId}, client::Context, builder::EditInteractionResponse};
+use songbird::{TrackEvent, Event, CoreEvent};
+use songbird::driver::Bitrate;
+
+use crate::bot::events::TrackEndNotifier;
 
 pub mod track;
 pub mod playlist;
 pub mod player;
 pub mod parser;
+pub mod controls;
+pub mod announce;
+pub mod presence;
+pub mod saved_playlist;
+pub mod likes;
+pub mod stats;
+pub mod queue_file;
+pub mod library;
+pub mod ffprobe;
+pub mod resolver;
+pub mod track_cache;
+pub mod jobs;
+pub mod radio;
+pub mod podcast;
+pub mod filters;
+
+const HTTP_CONNECT_TIMEOUT_SECS: u64 = 10;
 
 pub async fn get_voice_channel(ctx: &Context, command: &CommandInteraction) -> (Option<ChannelId>, Option<impl Into<String>>) {
     match ctx.cache.guild(command.guild_id.unwrap()) {
@@ -37,8 +61,118 @@ pub async fn get_voice_channel(ctx: &Context, command: &CommandInteraction) -> (
     }
 }
 
+pub async fn in_bot_channel(ctx: &Context, guild_id: GuildId, user_id: UserId) -> bool {
+    let user_channel = match ctx.cache.guild(guild_id) {
+        Some(guild) => match guild.voice_states.get(&user_id) {
+            Some(voice_state) => voice_state.channel_id,
+            None => None
+        },
+        None => None
+    };
+    let manager = songbird::get(ctx).await.expect("Songbird Voice client placed in at initialisation.").clone();
+    match (user_channel, manager.get(guild_id)) {
+        (Some(user_channel), Some(handler)) => match handler.lock().await.current_channel() {
+            Some(channel) => channel.0.get() == user_channel.get(),
+            None => false
+        },
+        _ => false
+    }
+}
+
+pub async fn join_user_channel(ctx: &Context, command: &CommandInteraction) -> bool {
+    let manager = songbird::get(ctx).await.expect("Songbird Voice client placed in at initialisation.").clone();
+
+    match manager.get(command.guild_id.unwrap()) {
+        Some(handler) => {
+            let mut handler = handler.lock().await;
+            match handler.current_channel() {
+                Some(_) => {},
+                None => {
+                    let (channel, message) = get_voice_channel(ctx, command).await;
+                    if channel.is_none() {
+                        let builder = EditInteractionResponse::new()
+                            .content(message.unwrap());
+                        check_msg(command.edit_response(&ctx.http, builder).await);
+                        return false;
+                    };
+                    let channel = channel.unwrap();
+                    if let Ok(_) = handler.join(channel).await{
+                        let text = match command.locale.as_str() {
+                            "ru" => format!("Подключился к \"<#{}>\"", channel.get()),
+                            _ => format!("Connected to \"<#{}>\"", channel.get())
+                        };
+                        let builder = EditInteractionResponse::new()
+                            .content(text);
+                        check_msg(command.edit_response(&ctx.http, builder).await);
+                    };
+                    handler.set_bitrate(Bitrate::BitsPerSecond(320000));
+                }
+            }
+        },
+        None => {
+            let (channel, message) = get_voice_channel(ctx, command).await;
+            if channel.is_none() {
+                let builder = EditInteractionResponse::new()
+                    .content(message.unwrap());
+                check_msg(command.edit_response(&ctx.http, builder).await);
+                return false;
+            };
+
+            let channel = channel.unwrap();
+            match manager.join(command.guild_id.unwrap(), channel).await {
+                Ok(handler) => {
+                    let text = match command.locale.as_str() {
+                        "ru" => format!("Подключился к \"<#{}>\"", channel.get()),
+                        _ => format!("Connected to \"<#{}>\"", channel.get())
+                    };
+                    let builder = EditInteractionResponse::new().content(text);
+                    check_msg(command.edit_response(&ctx.http, builder).await);
+                    let mut handler = handler.lock().await;
+                    let _ = handler.deafen(true).await;
+                    handler.set_bitrate(Bitrate::BitsPerSecond(256000));
+                    handler.remove_all_global_events();
+                    handler.add_global_event(
+                        Event::Track(TrackEvent::End),
+                        TrackEndNotifier {
+                            guild_id: command.guild_id.unwrap(),
+                            ctx_clone: ctx.clone()
+                        }
+                    );
+                    handler.add_global_event(
+                        Event::Core(CoreEvent::DriverConnect),
+                        TrackEndNotifier {
+                            guild_id: command.guild_id.unwrap(),
+                            ctx_clone: ctx.clone()
+                        }
+                    )
+                },
+                Err(e) => {
+                    println!("Error joining {:?}", e);
+                    let text = match command.locale.as_str() {
+                        "ru" => "Ошибка подключения!",
+                        _ => "Connection error!"
+                    };
+                    let builder = EditInteractionResponse::new().content(text);
+                    check_msg(command.edit_response(&ctx.http, builder).await);
+                    return false;
+                }
+            }
+        }
+    }
+    true
+}
+
 use serenity::Result as SerenityResult;
 
+pub fn http_client() -> &'static reqwest::Client {
+    static CLIENT: OnceLock<reqwest::Client> = OnceLock::new();
+    CLIENT.get_or_init(|| reqwest::Client::builder()
+        .user_agent("Mozilla/5.0 (Windows NT 10.0; Win64; x64; rv:106.0) Gecko/20100101 Firefox/106.0")
+        .connect_timeout(Duration::from_secs(HTTP_CONNECT_TIMEOUT_SECS))
+        .build()
+        .unwrap_or_default())
+}
+
 use self::track::Track;
 pub fn check_msg<T>(result: SerenityResult<T>) {
     if let Err(why) = result {
@@ -6,61 +6,72 @@ use youtube_dl::SearchType;
 
 use crate::bot::utils::track::VkTrack;
 
-use super::track::{Track, ParserType, Author, Chapter, YtDlTracksPlaylist, VkTracksPlaylist};
+use super::podcast::PodcastFeed;
+use super::resolver::source_registry;
+use super::track::{Track, ParserType, Author, Chapter, YtDlTracksPlaylist, VkTracksPlaylist, FlatEntry};
 
 pub async fn parse_url(url: impl Into<&str>, track_id: u64) -> ParsedDataType {
-    let url = url.into();
-    let vk_url = match Regex::new(r"^(http:\/\/|https:\/\/)?(www.)?(vk\.com|vkontakte\.ru)\/") {
-        Ok(re) => re.is_match(url),
-        _ => false
+    source_registry().resolve(url.into(), track_id).await
+}
+
+pub async fn parse_url_yt(url: &str, track_id: u64, find_audio: fn(Vec<Value>) -> Option<String>) -> ParsedDataType {
+    let raw_result = match youtube_dl::YoutubeDl::new(url).flat_playlist(true).socket_timeout("15").run_raw_async().await {
+        Ok(raw_result) => raw_result,
+        Err(_) => return ParsedDataType::None
+    };
+    let playlist_type = match raw_result.get("_type") {
+        Some(src) => src.clone() == json!("playlist"),
+        None => return ParsedDataType::None
     };
 
-    let raw_result = if vk_url {
-        youtube_dl::YoutubeDl::new(url).youtube_dl_path("python").extra_arg("./vk_parser.py").run_raw_async().await
+    if playlist_type {
+        let playlist = parse_playlist_yt(raw_result).await;
+        match playlist {
+            Some(playlist) => match playlist.tracks.first().map(|entry| entry.url.clone()) {
+                Some(url) => match youtube_dl::YoutubeDl::new(url).flat_playlist(true).socket_timeout("15").run_raw_async().await {
+                    Ok(src) => match parse_track_yt_with(src, track_id, find_audio).await {
+                        Some(track) => ParsedDataType::YtDlPlaylist((playlist, track)),
+                        None => ParsedDataType::None
+                    },
+                    Err(_) => ParsedDataType::None
+                },
+                None => ParsedDataType::None
+            },
+            None => ParsedDataType::None
+        }
     } else {
-        youtube_dl::YoutubeDl::new(url).flat_playlist(true).socket_timeout("15").run_raw_async().await
-    };
+        match parse_track_yt_with(raw_result, track_id, find_audio).await {
+            Some(track) => ParsedDataType::Track(track),
+            None => ParsedDataType::None
+        }
+    }
+}
 
-    let playlist_type = match &raw_result {
-        Ok(src) => match src.get("_type") {
-            Some(src) => src.clone() == json!("playlist"),
-            None => return ParsedDataType::None
-        },
+pub async fn parse_url_vk(url: &str, track_id: u64) -> ParsedDataType {
+    let raw_result = match youtube_dl::YoutubeDl::new(url).youtube_dl_path("python").extra_arg("./vk_parser.py").run_raw_async().await {
+        Ok(raw_result) => raw_result,
         Err(_) => return ParsedDataType::None
     };
-    let raw_result = raw_result.unwrap();
+    let playlist_type = match raw_result.get("_type") {
+        Some(src) => src.clone() == json!("playlist"),
+        None => return ParsedDataType::None
+    };
 
     if playlist_type {
-        if vk_url {
-            todo!()
-        } else {
-            let playlist = parse_playlist_yt(raw_result).await;
-            match playlist {
-                Some(playlist) => match playlist.tracks.first() {
-                    Some(url) => match youtube_dl::YoutubeDl::new(url).flat_playlist(true).socket_timeout("15").run_raw_async().await {
-                        Ok(src) => match parse_track_yt(src, track_id).await {
-                            Some(track) => 
-                                return ParsedDataType::YtDlPlaylist((playlist, track)),
-                            None => return ParsedDataType::None
-                        },
-                        Err(_) => return ParsedDataType::None
-                    },
-                    None => return ParsedDataType::None
+        match parse_playlist_vk(raw_result, url) {
+            Some(mut playlist) => match playlist.tracks.is_empty() {
+                false => {
+                    let track = Track::from_vk(playlist.tracks.remove(0), track_id);
+                    ParsedDataType::VkPlaylist((playlist, track))
                 },
-                None => return ParsedDataType::None
-            };
-        };
+                true => ParsedDataType::None
+            },
+            None => ParsedDataType::None
+        }
     } else {
-        if vk_url {
-            match serde_json::from_value::<VkTrack>(raw_result) {
-                Ok(track) => return ParsedDataType::Track(Track::from_vk(track, track_id)),
-                Err(_) => return ParsedDataType::None
-            }
-        } else {
-            match parse_track_yt(raw_result, track_id).await {
-                Some(track) => return ParsedDataType::Track(track),
-                None => return ParsedDataType::None
-            }
+        match serde_json::from_value::<VkTrack>(raw_result) {
+            Ok(track) => ParsedDataType::Track(Track::from_vk(track, track_id)),
+            Err(_) => ParsedDataType::None
         }
     }
 }
@@ -82,6 +93,36 @@ pub async fn search_track_yt(query: impl Into<&str>, track_id: u64, search_type:
     };
 }
 
+pub async fn search_tracks_yt(query: impl Into<&str>, count: usize, search_type: SearchType) -> Vec<Track> {
+    let options = match search_type {
+        SearchType::SoundCloud => youtube_dl::SearchOptions::soundcloud(query.into()).with_count(count),
+        _ => youtube_dl::SearchOptions::youtube(query.into()).with_count(count)
+    };
+    match youtube_dl::YoutubeDl::search_for(&options).flat_playlist(true).socket_timeout("15").run_raw_async().await {
+        Ok(data) => parse_flat_entries(data.get("entries")).into_iter()
+            .map(|entry| Track::from_flat(entry, 0))
+            .collect(),
+        Err(_) => Vec::new()
+    }
+}
+
+pub async fn search_tracks_vk(query: impl Into<&str>, count: usize) -> Vec<Track> {
+    match youtube_dl::YoutubeDl::new(format!("search{}:{}", count, query.into())).youtube_dl_path("python").extra_arg("./vk_parser.py").run_raw_async().await {
+        Ok(data) => match data.get("entries") {
+            Some(Value::Array(entries)) => entries.iter()
+                .filter_map(|entry| serde_json::from_value::<VkTrack>(entry.clone()).ok())
+                .take(count)
+                .map(|track| Track::from_vk(track, 0))
+                .collect(),
+            _ => match serde_json::from_value::<VkTrack>(data) {
+                Ok(track) => vec![Track::from_vk(track, 0)],
+                Err(_) => Vec::new()
+            }
+        },
+        Err(_) => Vec::new()
+    }
+}
+
 pub async fn search_track_vk(query: impl Into<&str>, track_id: u64) -> Option<Track> {
     match youtube_dl::YoutubeDl::new(format!("search:{}", query.into())).youtube_dl_path("python").extra_arg("./vk_parser.py").run_raw_async().await {
         Ok(data) => match serde_json::from_value::<VkTrack>(data) {
@@ -96,11 +137,16 @@ pub async fn search_track_vk(query: impl Into<&str>, track_id: u64) -> Option<Tr
 pub enum ParsedDataType {
     YtDlPlaylist((YtDlTracksPlaylist, Track)),
     VkPlaylist((VkTracksPlaylist, Track)),
+    Podcast((PodcastFeed, Track)),
     Track(Track),
     None
 }
 
 pub async fn parse_track_yt(data: Value, id: u64) -> Option<Track> {
+    parse_track_yt_with(data, id, find_best_audio).await
+}
+
+pub async fn parse_track_yt_with(data: Value, id: u64, find_audio: fn(Vec<Value>) -> Option<String>) -> Option<Track> {
     let data = match data.as_object() {
         Some(data) => data,
         None => return None
@@ -118,11 +164,7 @@ pub async fn parse_track_yt(data: Value, id: u64) -> Option<Track> {
     };
     let webpage_url = webpage_url.unwrap();
     let url = match data.get("formats") {
-        Some(Value::Array(formats)) => if webpage_url.starts_with("https://www.twitch.tv") {
-            find_best_audio_twitch(formats.clone())
-        } else {
-            find_best_audio(formats.clone())
-        },
+        Some(Value::Array(formats)) => find_audio(formats.clone()),
         _ => match data.get("url") {
             Some(Value::String(url)) => 
                 Some(url.clone()),
@@ -262,9 +304,17 @@ pub async fn parse_track_yt(data: Value, id: u64) -> Option<Track> {
             },
             webpage_url,
             duration,
+            is_live: match data.get("is_live") {
+                Some(Value::Bool(is_live)) => *is_live,
+                _ => matches!(data.get("live_status"), Some(Value::String(live_status)) if live_status == "is_live")
+            },
+            stream_title: None,
             parse_time: Utc::now(),
             parser_type: ParserType::YtDl,
             edit_date,
+            start: None,
+            end: None,
+            requester: None,
         }
     )
 }
@@ -347,24 +397,7 @@ pub async fn parse_playlist_yt(data: Value) -> Option<YtDlTracksPlaylist> {
         }
     };
 
-    let tracks = match data.get("entries") {
-        Some(Value::Array(tracks)) => {
-            let mut urls: Vec<String> = Vec::with_capacity(tracks.len());
-            for track in tracks {
-                match track {
-                    Value::Object(track) => {
-                        match track.get("url") {
-                            Some(Value::String(url)) => urls.push(url.clone()),
-                            _ => {}
-                        };
-                    },
-                    _ => {}
-                };
-            };
-            urls
-        },
-        _ => Vec::new()
-    };
+    let tracks = parse_flat_entries(data.get("entries"));
 
     Some(YtDlTracksPlaylist {
         title: match data.get("title") {
@@ -414,23 +447,94 @@ pub async fn parse_playlist_yt(data: Value) -> Option<YtDlTracksPlaylist> {
     })
 }
 
+pub fn parse_playlist_vk(data: Value, url: &str) -> Option<VkTracksPlaylist> {
+    let data = data.as_object()?;
+    let get_string = |key: &str| match data.get(key) {
+        Some(Value::String(value)) if !value.is_empty() => Some(value.clone()),
+        _ => None
+    };
+
+    let mut tracks = Vec::new();
+    let mut failed = 0;
+    if let Some(Value::Array(entries)) = data.get("entries") {
+        for entry in entries {
+            match serde_json::from_value::<VkTrack>(entry.clone()) {
+                Ok(track) if !track.url.is_empty() => tracks.push(track),
+                _ => failed += 1
+            }
+        }
+    }
+
+    Some(VkTracksPlaylist {
+        title: get_string("title"),
+        thumbnail: get_string("thumbnail"),
+        author: Author {
+            name: get_string("author").or(get_string("uploader")),
+            url: get_string("author_url").or(get_string("uploader_url")),
+            thumbnail: None,
+            verified: false
+        },
+        webpage_url: get_string("webpage_url").unwrap_or(url.to_string()),
+        tracks,
+        failed
+    })
+}
+
+fn parse_flat_entries(entries: Option<&Value>) -> Vec<FlatEntry> {
+    match entries {
+        Some(Value::Array(tracks)) => {
+            let mut entries: Vec<FlatEntry> = Vec::with_capacity(tracks.len());
+            for track in tracks {
+                match track {
+                    Value::Object(track) => {
+                        match track.get("url") {
+                            Some(Value::String(url)) => entries.push(FlatEntry {
+                                url: url.clone(),
+                                title: match track.get("title") {
+                                    Some(Value::String(title)) => Some(title.clone()),
+                                    _ => None
+                                },
+                                author: match track.get("channel") {
+                                    Some(Value::String(channel)) => Some(channel.clone()),
+                                    _ => match track.get("uploader") {
+                                        Some(Value::String(uploader)) => Some(uploader.clone()),
+                                        _ => None
+                                    }
+                                },
+                                duration: match track.get("duration") {
+                                    Some(Value::Number(duration)) => duration.as_f64(),
+                                    _ => None
+                                }
+                            }),
+                            _ => {}
+                        };
+                    },
+                    _ => {}
+                };
+            };
+            entries
+        },
+        _ => Vec::new()
+    }
+}
+
 pub fn get_time(time: impl Into<String>) -> f64 {
     let mut time = time.into();
     let mut total_time: u64 = 0;
 
     if time.contains(&['h', 'm', 's'][..]) {
         if let Some(p) = time.find('h') {
-            total_time = total_time * 60 + time[..p].parse::<u64>().unwrap_or(0);
+            total_time += time[..p].parse::<u64>().unwrap_or(0) * 3600;
             time.drain(..p + 1);
         }
 
         if let Some(p) = time.find('m') {
-            total_time = total_time * 60 + time[..p].parse::<u64>().unwrap_or(0);
+            total_time += time[..p].parse::<u64>().unwrap_or(0) * 60;
             time.drain(..p + 1);
         }
 
         if let Some(p) = time.find('s') {
-            total_time = total_time * 60 + time[..p].parse::<u64>().unwrap_or(0);
+            total_time += time[..p].parse::<u64>().unwrap_or(0);
             time.drain(..p + 1);
         }
 
@@ -450,6 +554,29 @@ pub fn get_time(time: impl Into<String>) -> f64 {
     }
 }
 
+pub fn parse_time(time: &str) -> Option<f64> {
+    let time = time.trim();
+    let re = Regex::new(r"^(?:\d+(?::\d+){0,2}|(?:\d+h)?(?:\d+m)?(?:\d+s)?)$").ok()?;
+    match !time.is_empty() && re.is_match(time) {
+        true => Some(get_time(time)),
+        false => None
+    }
+}
+
+pub fn get_url_timestamp(url: &str) -> Option<f64> {
+    let re = match Regex::new(r"[?&#](?:t|start)=([0-9hms:]+)") {
+        Ok(re) => re,
+        Err(_) => return None
+    };
+    match re.captures(url) {
+        Some(captures) => match get_time(&captures[1]) {
+            time if time > 0.0 => Some(time),
+            _ => None
+        },
+        None => None
+    }
+}
+
 pub fn get_time_str(time: f64) -> String {
     let time_seconds = time as i64;
 
@@ -511,7 +638,7 @@ fn get_chapters(data: Value, duration: f64) -> Vec<Chapter> {
     chapters
 }
 
-fn find_best_audio(formats: Vec<Value>) -> Option<String> {
+pub fn find_best_audio(formats: Vec<Value>) -> Option<String> {
     let mut best_abr: f64 = 0.0;
     let mut best_url: Option<String> = None;
     for format in &formats {
@@ -550,7 +677,7 @@ fn find_best_audio(formats: Vec<Value>) -> Option<String> {
     }
 }
 
-fn find_best_audio_twitch(formats: Vec<Value>) -> Option<String> {
+pub fn find_best_audio_twitch(formats: Vec<Value>) -> Option<String> {
     for format in &formats {
         match format.get("tbr") {
             Some(Value::Number(value)) => {
@@ -649,4 +776,40 @@ struct ChapterValue {
     pub title: String,
     pub start_time: f64,
     pub end_time: Option<f64>,
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_time() {
+        assert_eq!(get_time("90"), 90.0);
+        assert_eq!(get_time("1:30"), 90.0);
+        assert_eq!(get_time("0:01:30"), 90.0);
+        assert_eq!(get_time("1m30s"), 90.0);
+        assert_eq!(get_time("1h2m3s"), 3723.0);
+        assert_eq!(get_time("1h30s"), 3630.0);
+    }
+
+    #[test]
+    fn validates_time() {
+        assert_eq!(parse_time("1:30"), Some(90.0));
+        assert_eq!(parse_time(" 1h2m3s "), Some(3723.0));
+        assert_eq!(parse_time("abc"), None);
+        assert_eq!(parse_time("1:xx"), None);
+        assert_eq!(parse_time("5q"), None);
+        assert_eq!(parse_time(""), None);
+    }
+
+    #[test]
+    fn parses_url_timestamp() {
+        assert_eq!(get_url_timestamp("https://youtu.be/dQw4w9WgXcQ?t=90"), Some(90.0));
+        assert_eq!(get_url_timestamp("https://www.youtube.com/watch?v=dQw4w9WgXcQ&t=1m30s"), Some(90.0));
+        assert_eq!(get_url_timestamp("https://www.youtube.com/watch?v=dQw4w9WgXcQ&t=1h2m3s"), Some(3723.0));
+        assert_eq!(get_url_timestamp("https://www.youtube.com/embed/dQw4w9WgXcQ?rel=0&start=90"), Some(90.0));
+        assert_eq!(get_url_timestamp("https://example.com/video#t=1:30"), Some(90.0));
+        assert_eq!(get_url_timestamp("https://example.com/video#t=0:01:30"), Some(90.0));
+        assert_eq!(get_url_timestamp("https://www.youtube.com/watch?v=dQw4w9WgXcQ"), None);
+        assert_eq!(get_url_timestamp("https://www.youtube.com/watch?v=dQw4w9WgXcQ&t=0"), None);
+    }
+}
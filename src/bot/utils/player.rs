@@ -1,12 +1,20 @@
-use std::{sync::Arc, collections::HashMap, process::ChildStdin, io::Write, time::Duration};
+use std::{sync::Arc, collections::HashMap, io::Write, process::ChildStdin, time::Duration};
 
+use chrono::{DateTime, Utc};
 use diesel::{r2d2::{ConnectionManager, Pool, PooledConnection}, result::Error::NotFound, ExpressionMethods, Insertable, QueryDsl, RunQueryDsl, SelectableHelper, SqliteConnection};
-use serenity::{all::GuildId, client::Context, futures::lock::Mutex};
-use songbird::{typemap::TypeMapKey, tracks::TrackHandle};
-use tokio::sync::RwLock;
-
-use super::playlist::Playlist;
-use crate::models::{GuildSettingsDB, UpdateBass};
+use rand::seq::SliceRandom;
+use serenity::{all::{ChannelId, GuildId, MessageId}, client::{Cache, Context}, futures::lock::Mutex, http::Http};
+use songbird::{typemap::TypeMapKey, tracks::TrackHandle, Songbird};
+use tokio::{sync::RwLock, task::JoinHandle};
+
+use super::filters::{Effects, Filter, FilterGraph};
+use super::playlist::{spawn_prefetch, Playlist};
+use super::track::Track;
+use super::stats::PlayStats;
+use super::jobs::ImportJob;
+use super::announce::spawn_announcement;
+use super::presence::{spawn_presence_update, spawn_presence_update_ctx, Presence};
+use crate::models::{GuildSettingsDB, UpdateAnnounce, UpdateBass};
 
 pub struct PlayerData;
 
@@ -29,7 +37,11 @@ pub struct Player {
     pub playlist_sync_and_last_id: Arc<Mutex<u64>>,
     pub settings: Arc<RwLock<PlayerSettings>>,
     pub position: Arc<RwLock<Position>>,
-    pub state: Arc<RwLock<PlayerState>>
+    pub state: Arc<RwLock<PlayerState>>,
+    pub announcement: Arc<RwLock<Option<(ChannelId, MessageId)>>>,
+    pub sleep: Arc<RwLock<SleepTimer>>,
+    pub play_stats: Arc<RwLock<Option<PlayStats>>>,
+    pub jobs: Arc<RwLock<Vec<Arc<ImportJob>>>>
 
 }
 
@@ -47,6 +59,10 @@ impl Player {
                 )),
             position: Arc::new(RwLock::new(Position::default())),
             state: Arc::new(RwLock::new(PlayerState::Ended)),
+            announcement: Arc::new(RwLock::new(None)),
+            sleep: Arc::new(RwLock::new(SleepTimer::Off)),
+            play_stats: Arc::new(RwLock::new(None)),
+            jobs: Arc::new(RwLock::new(Vec::new())),
         }
     }
 
@@ -63,10 +79,18 @@ impl Player {
                 )),
             position: Arc::new(RwLock::new(Position::default())),
             state: Arc::new(RwLock::new(PlayerState::Ended)),
+            announcement: Arc::new(RwLock::new(None)),
+            sleep: Arc::new(RwLock::new(SleepTimer::Off)),
+            play_stats: Arc::new(RwLock::new(None)),
+            jobs: Arc::new(RwLock::new(Vec::new())),
         }
     }
 
     pub async fn clear(&self) {
+        self.cancel_sleep().await;
+        self.jobs.read().await.iter().for_each(|job| {
+            job.cancel();
+        });
         *self.playlist.write().await = Playlist::new();
         *self.state.write().await = PlayerState::Ended;
         *self.position.write().await = Position::default();
@@ -77,6 +101,197 @@ impl Player {
         }
         *player_handler = None;
     }
+
+    pub async fn start_play(&self, track: Track) {
+        spawn_prefetch(self.playlist.clone());
+        *self.play_stats.write().await = Some(PlayStats {
+            track,
+            started_at: Utc::now(),
+            listened: Duration::from_secs(0)
+        });
+    }
+
+    pub async fn finish_play(&self, play_time: Duration, state: &PlayerState) -> Option<(PlayStats, bool)> {
+        let mut play_stats = self.play_stats.write().await;
+        match state {
+            PlayerState::Seeking => {
+                if let Some(play) = play_stats.as_mut() {
+                    play.listened += play_time;
+                }
+                None
+            },
+            _ => play_stats.take().map(|mut play| {
+                play.listened += play_time;
+                (play, !matches!(state, PlayerState::Playing))
+            })
+        }
+    }
+
+    pub async fn cancel_sleep(&self) -> bool {
+        let mut sleep = self.sleep.write().await;
+        match std::mem::replace(&mut *sleep, SleepTimer::Off) {
+            SleepTimer::Off => false,
+            SleepTimer::At(_, task) => {
+                task.abort();
+                let volume = self.settings.read().await.volume;
+                if let (Some(ffmpeg), Some(command)) = (self.ffmpeg.write().await.as_mut(), Filter::Volume(volume).command()) {
+                    let _ = ffmpeg.write(command.as_bytes());
+                }
+                true
+            },
+            SleepTimer::EndOfTrack => true
+        }
+    }
+
+    pub async fn take_sleep_end_of_track(&self) -> bool {
+        let mut sleep = self.sleep.write().await;
+        match *sleep {
+            SleepTimer::EndOfTrack => {
+                *sleep = SleepTimer::Off;
+                true
+            },
+            _ => false
+        }
+    }
+
+    pub async fn pause(&self) -> Result<(), ControlError> {
+        let mut state = self.state.write().await;
+        let player_handler = self.player.write().await.clone();
+
+        match *state {
+            PlayerState::Playing | PlayerState::Paused => match player_handler {
+                Some(handler) => match handler.pause() {
+                    Ok(_) => {
+                        *state = PlayerState::Paused;
+                        Ok(())
+                    },
+                    Err(_) => Err(ControlError::Failed)
+                },
+                None => Err(ControlError::NoHandler)
+            },
+            _ => Err(ControlError::NotPlaying)
+        }
+    }
+
+    pub async fn resume(&self) -> Result<(), ControlError> {
+        let mut state = self.state.write().await;
+        let player_handler = self.player.write().await.clone();
+
+        match *state {
+            PlayerState::Playing | PlayerState::Paused => match player_handler {
+                Some(handler) => match handler.play() {
+                    Ok(_) => {
+                        *state = PlayerState::Playing;
+                        Ok(())
+                    },
+                    Err(_) => Err(ControlError::Failed)
+                },
+                None => Err(ControlError::NoHandler)
+            },
+            _ => Err(ControlError::NotPlaying)
+        }
+    }
+
+    pub async fn restart(&self, rate: f64) -> Result<(), ControlError> {
+        let mut state = self.state.write().await;
+        let player_handler = self.player.write().await.clone();
+
+        match *state {
+            PlayerState::Playing | PlayerState::Paused => match player_handler {
+                Some(handler) => {
+                    let mut position = self.position.write().await;
+                    let current = match handler.get_info().await {
+                        Ok(data) => position.last_position + Duration::from_secs_f64((data.position - position.last_player_position).as_secs_f64() * rate),
+                        Err(_) => return Err(ControlError::Failed)
+                    };
+                    match handler.stop() {
+                        Ok(_) => {
+                            *state = PlayerState::Seeking;
+                            *position = Position::from_secs_f64(current.as_secs_f64());
+                            Ok(())
+                        },
+                        Err(_) => Err(ControlError::Failed)
+                    }
+                },
+                None => Err(ControlError::NoHandler)
+            },
+            _ => Err(ControlError::NotPlaying)
+        }
+    }
+
+    pub async fn skip_current(&self) -> Result<Option<Track>, ControlError> {
+        let player_playlist = self.playlist.write().await;
+        let mut state = self.state.write().await;
+        let player_handler = self.player.write().await.clone();
+
+        match *state {
+            PlayerState::Playing | PlayerState::Paused | PlayerState::Seeking => match player_handler {
+                Some(handler) => match handler.stop() {
+                    Ok(_) => {
+                        *state = PlayerState::InSkip;
+                        Ok(player_playlist.current.clone())
+                    },
+                    Err(_) => Err(ControlError::Failed)
+                },
+                None => Err(ControlError::NoHandler)
+            },
+            _ => Err(ControlError::NotPlaying)
+        }
+    }
+
+    pub async fn previous(&self) -> Result<Track, ControlError> {
+        let mut player_playlist = self.playlist.write().await;
+        let mut state = self.state.write().await;
+        let player_handler = self.player.write().await.clone();
+
+        match *state {
+            PlayerState::Playing | PlayerState::Paused | PlayerState::Seeking => match player_handler {
+                Some(handler) => {
+                    let track = match player_playlist.history.pop_back() {
+                        Some(track) => track,
+                        None => return Err(ControlError::NoHistory)
+                    };
+                    match handler.stop() {
+                        Ok(_) => {
+                            if let Some(current) = player_playlist.current.take() {
+                                player_playlist.tracks.push_front(current);
+                            }
+                            player_playlist.tracks.push_front(track.clone());
+                            *state = PlayerState::InSkip;
+                            Ok(track)
+                        },
+                        Err(_) => {
+                            player_playlist.history.push_back(track);
+                            Err(ControlError::Failed)
+                        }
+                    }
+                },
+                None => Err(ControlError::NoHandler)
+            },
+            _ => Err(ControlError::NotPlaying)
+        }
+    }
+
+    pub async fn shuffle(&self) -> usize {
+        let mut player_playlist = self.playlist.write().await;
+        player_playlist.tracks.make_contiguous().shuffle(&mut rand::thread_rng());
+        player_playlist.tracks.len()
+    }
+}
+
+#[derive(Debug,PartialEq)]
+pub enum ControlError {
+    NotPlaying,
+    NoHandler,
+    NoHistory,
+    Failed
+}
+
+#[derive(Debug)]
+pub enum SleepTimer {
+    Off,
+    At(DateTime<Utc>, JoinHandle<()>),
+    EndOfTrack
 }
 
 #[derive(Debug)]
@@ -106,6 +321,10 @@ impl Position {
             last_player_position: Duration::from_secs(0)
         }
     }
+
+    pub fn from_track(track: &Track) -> Self {
+        Self::from_secs_f64(track.start.unwrap_or(0.0))
+    }
 }
 
 #[derive(Debug)]
@@ -116,7 +335,10 @@ pub struct PlayerSettings {
     pub bass_enabled: bool,
     pub bass_gain: f64,
     pub equalizer: Equalizer,
-    pub repeat: RepeatMode
+    pub effects: Effects,
+    pub repeat: RepeatMode,
+    pub announce_channel: Option<u64>,
+    pub announce_delete_previous: bool
 }
 
 impl PlayerSettings {
@@ -156,7 +378,10 @@ impl PlayerSettings {
                                 f_8k: settings.equalizer_8k,
                                 f_16k: settings.equalizer_16k
                             },
-                            repeat: RepeatMode::new(settings.loop_type)
+                            effects: Effects::from_bits(settings.effects),
+                            repeat: RepeatMode::new(settings.loop_type),
+                            announce_channel: settings.announce_channel.map(|channel| channel as u64),
+                            announce_delete_previous: settings.announce_delete_previous
                         };
                     },
                     Err(NotFound) => {
@@ -185,7 +410,10 @@ impl PlayerSettings {
                 f_8k: 0.0,
                 f_16k: 0.0
             },
-            repeat: RepeatMode::Off
+            effects: Effects::default(),
+            repeat: RepeatMode::Off,
+            announce_channel: None,
+            announce_delete_previous: false
         }
     }
 
@@ -205,6 +433,29 @@ impl PlayerSettings {
         self.repeat = repeat;
     }
 
+    pub async fn set_announce(&mut self, ctx: &Context, channel: Option<u64>, delete_previous: bool) {
+        let pool = {
+            let data_read = ctx.data.read().await;
+            let conn = data_read.get::<PlayerDataBase>().expect("Expected PlayerDataBase in TypeMap.").clone();
+            conn.get()
+        };
+        match pool {
+            Ok(mut pool) => {
+                use crate::schema::guild_settings::dsl::*;
+                let _ = diesel::update(guild_settings
+                    .filter(id.eq(self.guild_id as i64)))
+                    .set(UpdateAnnounce {
+                        announce_channel: channel.map(|channel| channel as i64),
+                        announce_delete_previous: delete_previous
+                    })
+                    .execute(&mut pool);
+            },
+            Err(_) => {}
+        };
+        self.announce_channel = channel;
+        self.announce_delete_previous = delete_previous;
+    }
+
     pub async fn set_volume(&mut self, ctx: &Context, volume_value: f64, ffmpeg: Option<&mut ChildStdin>) {
         let pool = {
             let data_read = ctx.data.read().await;
@@ -221,9 +472,10 @@ impl PlayerSettings {
             },
             Err(_) => {}
         };
+        let previous = FilterGraph::new(self);
         self.volume = volume_value;
         if let Some(ffmpeg) = ffmpeg {
-            let _ = ffmpeg.write(format!("^Cvolume -1 volume {}\n", self.volume * 0.2).as_bytes());
+            FilterGraph::new(self).apply(&previous, ffmpeg);
         }
     }
     
@@ -244,9 +496,10 @@ impl PlayerSettings {
             }
             Err(_) => {}
         };
+        let previous = FilterGraph::new(self);
         self.speed = speed_value;
         if let Some(ffmpeg) = ffmpeg {
-            let _ = ffmpeg.write(format!("^Catempo -1 tempo {}\n", self.speed).as_bytes());
+            FilterGraph::new(self).apply(&previous, ffmpeg);
         }
     }
 
@@ -270,6 +523,7 @@ impl PlayerSettings {
             }
             Err(_) => {}
         };
+        let previous = FilterGraph::new(self);
         if let Some(bass_on) = bass_on {
             self.bass_enabled = bass_on;
         }
@@ -277,12 +531,38 @@ impl PlayerSettings {
             self.bass_gain = bass_value;
         }
         if let Some(ffmpeg) = ffmpeg {
-            let _ = match self.bass_enabled {
-                true => ffmpeg.write(format!("^Cbass -1 g {}\n", self.bass_gain).as_bytes()),
-                false => ffmpeg.write("^Cbass -1 g 0\n".as_bytes()),
-            };
+            FilterGraph::new(self).apply(&previous, ffmpeg);
         }
     }
+
+    pub async fn set_effects(&mut self, ctx: &Context, effects_value: Effects, ffmpeg: Option<&mut ChildStdin>) -> bool {
+        let pool = {
+            let data_read = ctx.data.read().await;
+            let conn = data_read.get::<PlayerDataBase>().expect("Expected PlayerDataBase in TypeMap.").clone();
+            conn.get()
+        };
+        match pool {
+            Ok(mut pool) => {
+                use crate::schema::guild_settings::dsl::*;
+                let _ = diesel::update(guild_settings
+                    .filter(id.eq(self.guild_id as i64)))
+                    .set(effects.eq(effects_value.bits()))
+                    .execute(&mut pool);
+            },
+            Err(_) => {}
+        };
+        let previous = FilterGraph::new(self);
+        self.effects = effects_value;
+        let graph = FilterGraph::new(self);
+        if let Some(ffmpeg) = ffmpeg {
+            graph.apply(&previous, ffmpeg);
+        }
+        graph.requires_restart(&previous)
+    }
+
+    pub fn get_rate(&self) -> f64 {
+        self.effects.enabled().fold(self.speed, |rate, effect| rate * effect.rate())
+    }
 }
 
 #[derive(Debug,PartialEq)]
@@ -324,6 +604,14 @@ impl RepeatMode {
             _ => Self::Off
         }
     }
+
+    pub fn next(self) -> Self {
+        match self {
+            Self::Off => Self::Track,
+            Self::Track => Self::Queue,
+            Self::Queue => Self::Off
+        }
+    }
 }
 
 pub type PlayerDataType = Arc<RwLock<HashMap<u64, Arc<Player>>>>;
@@ -372,4 +660,92 @@ pub async fn clear_guild_player(ctx: &Context, guild_id: GuildId) {
             map.insert(guild_id.get(), player);
         }
     };
-}
\ No newline at end of file
+}
+pub async fn enqueue_track(ctx: &Context, player: &Arc<Player>, track: Track) {
+    {
+        let mut player_playlist = player.playlist.write().await;
+        let state = player.state.read().await;
+        if !matches!(*state, PlayerState::Ended) {
+            player_playlist.tracks.push_back(track);
+            return;
+        }
+    }
+    let track = match track.prepare().await {
+        Some(track) => track,
+        None => return
+    };
+    let mut player_playlist = player.playlist.write().await;
+    let mut state = player.state.write().await;
+    match *state {
+        PlayerState::Ended => {
+            let mut last_updated_position = player.position.write().await;
+            player_playlist.set_current(track.clone());
+            *state = PlayerState::Playing;
+            *last_updated_position = Position::from_track(&track);
+
+            let mut child = track.get_child(ctx, &player.guild_id.get(), 0.0).await.unwrap();
+            let stdin = child.stdin.take().unwrap();
+            let data = songbird::input::Input::from(songbird::input::ChildContainer::from(child));
+
+            let manager = songbird::get(ctx).await.expect("Songbird Voice client placed in at initialisation.").clone();
+            if let Some(handler_lock) = manager.get(player.guild_id) {
+                let mut handler = handler_lock.lock().await;
+                let mut ffmpeg = player.ffmpeg.write().await;
+                let mut player_handler = player.player.write().await;
+
+                let _ = ffmpeg.insert(stdin);
+
+                let handle = handler.play_only_input(data);
+                let _ = player_handler.insert(handle);
+            }
+            player.start_play(track.clone()).await;
+            spawn_announcement(ctx.cache.clone(), ctx.http.clone(), player.clone(), track.clone());
+            spawn_presence_update_ctx(ctx).await;
+        },
+        _ => player_playlist.tracks.push_back(track)
+    }
+}
+
+pub async fn enqueue_track_web(player_data: Arc<PlayerDataType>, songbird: &Songbird, cache: Arc<Cache>, http: Arc<Http>, presence: Presence, player: &Arc<Player>, track: Track) {
+    {
+        let mut player_playlist = player.playlist.write().await;
+        let state = player.state.read().await;
+        if !matches!(*state, PlayerState::Ended) {
+            player_playlist.tracks.push_back(track);
+            return;
+        }
+    }
+    let track = match track.prepare().await {
+        Some(track) => track,
+        None => return
+    };
+    let mut player_playlist = player.playlist.write().await;
+    let mut state = player.state.write().await;
+    match *state {
+        PlayerState::Ended => {
+            let mut last_updated_position = player.position.write().await;
+            player_playlist.set_current(track.clone());
+            *state = PlayerState::Playing;
+            *last_updated_position = Position::from_track(&track);
+
+            let mut child = track.get_child_web(player_data.clone(), &player.guild_id.get(), 0.0).await.unwrap();
+            let stdin = child.stdin.take().unwrap();
+            let data = songbird::input::Input::from(songbird::input::ChildContainer::from(child));
+
+            if let Some(handler_lock) = songbird.get(player.guild_id) {
+                let mut handler = handler_lock.lock().await;
+                let mut ffmpeg = player.ffmpeg.write().await;
+                let mut player_handler = player.player.write().await;
+
+                let _ = ffmpeg.insert(stdin);
+
+                let handle = handler.play_only_input(data);
+                let _ = player_handler.insert(handle);
+            }
+            player.start_play(track.clone()).await;
+            spawn_announcement(cache, http, player.clone(), track.clone());
+            spawn_presence_update(presence, player_data.as_ref().clone());
+        },
+        _ => player_playlist.tracks.push_back(track)
+    }
+}
@@ -1,18 +1,25 @@
 use std::collections:: VecDeque;
+use std::sync::Arc;
+
+use tokio::sync::RwLock;
 
 use super::track::Track;
 
+const HISTORY_LIMIT: usize = 50;
+
 #[derive(Debug)]
 pub struct Playlist {
     pub tracks: VecDeque<Track>,
-    pub current: Option<Track>
+    pub current: Option<Track>,
+    pub history: VecDeque<Track>
 }
 
 impl Playlist {
     pub fn new() -> Self {
         Self {
             tracks: VecDeque::new(),
-            current: None
+            current: None,
+            history: VecDeque::new()
         }
     }
 
@@ -23,4 +30,59 @@ impl Playlist {
     pub fn get(&self, index: usize) -> Option<&Track> {
         self.tracks.get(index)
     }
-}
\ No newline at end of file
+
+    pub fn pop_next(&mut self) -> Option<Track> {
+        self.tracks.pop_front()
+    }
+
+    pub fn replace(&mut self, id: u64, track: Option<Track>) {
+        match track {
+            Some(track) => {
+                if let Some(entry) = self.current.iter_mut().chain(self.tracks.iter_mut()).find(|entry| entry.id == id) {
+                    *entry = track;
+                }
+            },
+            None => {
+                self.tracks.retain(|entry| entry.id != id);
+                if self.current.as_ref().map(|current| current.id) == Some(id) {
+                    self.take_current();
+                }
+            }
+        }
+    }
+
+    pub fn set_current(&mut self, track: Track) {
+        if let Some(current) = self.current.replace(track) {
+            self.push_history(current);
+        }
+    }
+
+    pub fn take_current(&mut self) {
+        if let Some(current) = self.current.take() {
+            self.push_history(current);
+        }
+    }
+
+    fn push_history(&mut self, track: Track) {
+        self.history.push_back(track);
+        if self.history.len() > HISTORY_LIMIT {
+            self.history.pop_front();
+        }
+    }
+}
+
+pub fn spawn_prefetch(playlist: Arc<RwLock<Playlist>>) {
+    tokio::spawn(async move {
+        let next = match playlist.read().await.tracks.front() {
+            Some(track) if !track.is_prepared() => track.clone(),
+            _ => return
+        };
+        let id = next.id;
+        if let Some(track) = next.prepare().await {
+            let mut playlist = playlist.write().await;
+            if let Some(entry) = playlist.tracks.iter_mut().find(|entry| entry.id == id && !entry.is_prepared()) {
+                *entry = track;
+            }
+        }
+    });
+}
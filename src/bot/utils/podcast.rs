@@ -0,0 +1,617 @@
+use std::collections::VecDeque;
+use std::sync::{Mutex, OnceLock};
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use diesel::{BoolExpressionMethods, ExpressionMethods, OptionalExtension, QueryDsl, QueryResult, RunQueryDsl, SelectableHelper, SqliteConnection};
+use roxmltree::{Document, Node};
+use reqwest::Url;
+use serde::Serialize;
+use serde_json::Value;
+use serenity::builder::{CreateActionRow, CreateSelectMenu, CreateSelectMenuKind, CreateSelectMenuOption, EditInteractionResponse};
+use serenity::futures::StreamExt;
+use tokio::time::timeout;
+
+use super::http_client;
+use super::parser::{get_time, get_time_str};
+use super::track::{Author, Chapter, ParserType, Track};
+use crate::models::{NewPodcastSubscription, PodcastSubscriptionDB};
+
+const ITUNES_NAMESPACE: &str = "http://www.itunes.com/dtds/podcast-1.0.dtd";
+const PODCAST_NAMESPACE: &str = "https://podcastindex.org/namespace/1.0";
+const ATOM_NAMESPACE: &str = "http://www.w3.org/2005/Atom";
+const REQUEST_TIMEOUT_SECS: u64 = 15;
+const FEED_MAX_SIZE: usize = 10 * 1024 * 1024;
+const CHAPTERS_MAX_SIZE: usize = 1024 * 1024;
+const EPISODES_LIMIT: usize = 500;
+const PICKER_EPISODES: usize = 25;
+const PICKERS_KEPT: usize = 64;
+const SELECT_TEXT_LIMIT: usize = 100;
+const FEED_EXTENSIONS: [&str; 3] = ["rss", "xml", "atom"];
+const FEED_PATH_NAMES: [&str; 4] = ["rss", "feed", "feeds", "podcast"];
+const FEED_HOST_LABELS: [&str; 3] = ["rss", "feed", "feeds"];
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PodcastEpisode {
+    pub title: Option<String>,
+    pub description: Option<String>,
+    pub url: String,
+    pub duration: Option<f64>,
+    pub published: Option<DateTime<Utc>>,
+    pub thumbnail: Option<String>,
+    pub author: Option<String>,
+    pub chapters_url: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PodcastFeed {
+    pub title: Option<String>,
+    pub description: Option<String>,
+    pub thumbnail: Option<String>,
+    pub author: Option<String>,
+    pub link: Option<String>,
+    pub webpage_url: String,
+    pub episodes: Vec<PodcastEpisode>,
+}
+
+pub fn is_feed_url(url: &str) -> bool {
+    let url = match Url::parse(url) {
+        Ok(url) if matches!(url.scheme(), "http" | "https") => url,
+        _ => return false
+    };
+    let host = url.host_str().unwrap_or_default().to_lowercase();
+    let segments: Vec<String> = url.path_segments()
+        .map(|segments| segments.map(|segment| segment.to_lowercase()).collect())
+        .unwrap_or_default();
+    let is_feed_file = segments.last()
+        .and_then(|name| name.rsplit_once('.'))
+        .is_some_and(|(_, extension)| FEED_EXTENSIONS.contains(&extension));
+    is_feed_file
+        || host.split('.').next().is_some_and(|label| FEED_HOST_LABELS.contains(&label))
+        || segments.iter().any(|segment| FEED_PATH_NAMES.contains(&segment.as_str()))
+}
+
+fn find_child<'a, 'input>(node: Node<'a, 'input>, namespace: Option<&str>, name: &str) -> Option<Node<'a, 'input>> {
+    node.children().find(|child| child.is_element() && child.tag_name().name() == name && child.tag_name().namespace() == namespace)
+}
+
+fn get_child_text(node: Node, namespace: Option<&str>, name: &str) -> Option<String> {
+    find_child(node, namespace, name)
+        .and_then(|child| child.text())
+        .map(|text| text.trim().to_string())
+        .filter(|text| !text.is_empty())
+}
+
+fn get_child_attribute(node: Node, namespace: Option<&str>, name: &str, attribute: &str) -> Option<String> {
+    find_child(node, namespace, name)
+        .and_then(|child| child.attribute(attribute))
+        .map(|value| value.trim().to_string())
+        .filter(|value| !value.is_empty())
+}
+
+fn parse_duration(duration: &str) -> Option<f64> {
+    let duration = duration.trim();
+    match duration.parse::<f64>() {
+        Ok(duration) => Some(duration),
+        Err(_) if duration.contains(':') => Some(get_time(duration)),
+        Err(_) => None
+    }.filter(|duration| *duration > 0.0)
+}
+
+fn parse_date(date: &str) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc2822(date.trim())
+        .or(DateTime::parse_from_rfc3339(date.trim()))
+        .ok()
+        .map(|date| date.into())
+}
+
+fn parse_rss(channel: Node, feed_url: &str) -> PodcastFeed {
+    let thumbnail = get_child_attribute(channel, Some(ITUNES_NAMESPACE), "image", "href")
+        .or(find_child(channel, None, "image").and_then(|image| get_child_text(image, None, "url")));
+    let author = get_child_text(channel, Some(ITUNES_NAMESPACE), "author")
+        .or(get_child_text(channel, None, "managingEditor"));
+    let episodes = channel.children()
+        .filter(|item| item.is_element() && item.tag_name().name() == "item" && item.tag_name().namespace().is_none())
+        .filter_map(|item| {
+            let enclosure = find_child(item, None, "enclosure")?;
+            let url = enclosure.attribute("url")?.trim().to_string();
+            Some(PodcastEpisode {
+                title: get_child_text(item, None, "title").or(get_child_text(item, Some(ITUNES_NAMESPACE), "title")),
+                description: get_child_text(item, Some(ITUNES_NAMESPACE), "summary").or(get_child_text(item, None, "description")),
+                url,
+                duration: get_child_text(item, Some(ITUNES_NAMESPACE), "duration").and_then(|duration| parse_duration(&duration)),
+                published: get_child_text(item, None, "pubDate").and_then(|date| parse_date(&date)),
+                thumbnail: get_child_attribute(item, Some(ITUNES_NAMESPACE), "image", "href").or(thumbnail.clone()),
+                author: get_child_text(item, Some(ITUNES_NAMESPACE), "author").or(author.clone()),
+                chapters_url: get_child_attribute(item, Some(PODCAST_NAMESPACE), "chapters", "url"),
+            })
+        })
+        .collect();
+    PodcastFeed {
+        title: get_child_text(channel, None, "title"),
+        description: get_child_text(channel, Some(ITUNES_NAMESPACE), "summary").or(get_child_text(channel, None, "description")),
+        thumbnail,
+        author,
+        link: get_child_text(channel, None, "link"),
+        webpage_url: feed_url.to_string(),
+        episodes,
+    }
+}
+
+fn get_atom_link(node: Node, rel: &str) -> Option<String> {
+    node.children()
+        .filter(|link| link.is_element() && link.tag_name().name() == "link" && link.tag_name().namespace() == Some(ATOM_NAMESPACE))
+        .find(|link| link.attribute("rel").unwrap_or("alternate") == rel)
+        .and_then(|link| link.attribute("href"))
+        .map(|href| href.trim().to_string())
+}
+
+fn parse_atom(feed: Node, feed_url: &str) -> PodcastFeed {
+    let thumbnail = get_child_text(feed, Some(ATOM_NAMESPACE), "logo")
+        .or(get_child_text(feed, Some(ATOM_NAMESPACE), "icon"))
+        .or(get_child_attribute(feed, Some(ITUNES_NAMESPACE), "image", "href"));
+    let author = find_child(feed, Some(ATOM_NAMESPACE), "author").and_then(|author| get_child_text(author, Some(ATOM_NAMESPACE), "name"));
+    let episodes = feed.children()
+        .filter(|entry| entry.is_element() && entry.tag_name().name() == "entry" && entry.tag_name().namespace() == Some(ATOM_NAMESPACE))
+        .filter_map(|entry| Some(PodcastEpisode {
+            title: get_child_text(entry, Some(ATOM_NAMESPACE), "title"),
+            description: get_child_text(entry, Some(ATOM_NAMESPACE), "summary").or(get_child_text(entry, Some(ATOM_NAMESPACE), "content")),
+            url: get_atom_link(entry, "enclosure")?,
+            duration: get_child_text(entry, Some(ITUNES_NAMESPACE), "duration").and_then(|duration| parse_duration(&duration)),
+            published: get_child_text(entry, Some(ATOM_NAMESPACE), "published")
+                .or(get_child_text(entry, Some(ATOM_NAMESPACE), "updated"))
+                .and_then(|date| parse_date(&date)),
+            thumbnail: get_child_attribute(entry, Some(ITUNES_NAMESPACE), "image", "href").or(thumbnail.clone()),
+            author: find_child(entry, Some(ATOM_NAMESPACE), "author")
+                .and_then(|author| get_child_text(author, Some(ATOM_NAMESPACE), "name"))
+                .or(author.clone()),
+            chapters_url: get_child_attribute(entry, Some(PODCAST_NAMESPACE), "chapters", "url"),
+        }))
+        .collect();
+    PodcastFeed {
+        title: get_child_text(feed, Some(ATOM_NAMESPACE), "title"),
+        description: get_child_text(feed, Some(ATOM_NAMESPACE), "subtitle"),
+        thumbnail,
+        author,
+        link: get_atom_link(feed, "alternate"),
+        webpage_url: feed_url.to_string(),
+        episodes,
+    }
+}
+
+pub fn parse_feed(data: &str, feed_url: &str) -> Option<PodcastFeed> {
+    let document = Document::parse(data).ok()?;
+    let root = document.root_element();
+    let mut feed = match (root.tag_name().name(), root.tag_name().namespace()) {
+        ("rss", None) => parse_rss(find_child(root, None, "channel")?, feed_url),
+        ("feed", Some(ATOM_NAMESPACE)) => parse_atom(root, feed_url),
+        _ => return None
+    };
+    if feed.episodes.is_empty() {
+        return None;
+    }
+    feed.episodes.sort_by(|a, b| b.published.cmp(&a.published));
+    feed.episodes.truncate(EPISODES_LIMIT);
+    Some(feed)
+}
+
+async fn fetch(url: &str, max_size: usize, accept: fn(&str) -> bool) -> Option<String> {
+    let response = timeout(Duration::from_secs(REQUEST_TIMEOUT_SECS), http_client().get(url).send()).await.ok()?.ok()?;
+    if !response.status().is_success() || response.headers().keys().any(|header| header.as_str().starts_with("icy-")) {
+        return None;
+    }
+    let content_type = response.headers().get("content-type")
+        .and_then(|content_type| content_type.to_str().ok())
+        .unwrap_or_default()
+        .to_lowercase();
+    if !accept(&content_type) {
+        return None;
+    }
+    let mut data = Vec::new();
+    let mut stream = response.bytes_stream();
+    while let Some(chunk) = timeout(Duration::from_secs(REQUEST_TIMEOUT_SECS), stream.next()).await.ok()? {
+        data.extend_from_slice(&chunk.ok()?);
+        if data.len() > max_size {
+            return None;
+        }
+    }
+    Some(String::from_utf8_lossy(&data).to_string())
+}
+
+fn is_feed_content_type(content_type: &str) -> bool {
+    !["text/html", "audio/", "video/", "image/", "application/octet-stream"].iter().any(|prefix| content_type.starts_with(prefix))
+}
+
+fn is_json_content_type(content_type: &str) -> bool {
+    content_type.is_empty() || content_type.contains("json") || content_type.starts_with("text/")
+}
+
+pub async fn fetch_feed(url: &str) -> Option<PodcastFeed> {
+    let data = fetch(url, FEED_MAX_SIZE, is_feed_content_type).await?;
+    parse_feed(&data, url)
+}
+
+fn parse_chapters(data: &str, duration: Option<f64>) -> Vec<Chapter> {
+    let data: Value = match serde_json::from_str(data) {
+        Ok(data) => data,
+        Err(_) => return Vec::new()
+    };
+    let mut chapters: Vec<(f64, Option<f64>, String)> = match data.get("chapters") {
+        Some(Value::Array(chapters)) => chapters.iter()
+            .filter(|chapter| chapter.get("toc").and_then(|toc| toc.as_bool()).unwrap_or(true))
+            .filter_map(|chapter| Some((
+                chapter.get("startTime")?.as_f64()?,
+                chapter.get("endTime").and_then(|end_time| end_time.as_f64()),
+                chapter.get("title").and_then(|title| title.as_str()).unwrap_or_default().to_string()
+            )))
+            .collect(),
+        _ => return Vec::new()
+    };
+    chapters.sort_by(|a, b| a.0.total_cmp(&b.0));
+    let starts: Vec<f64> = chapters.iter().map(|(start_time, _, _)| *start_time).collect();
+    chapters.into_iter().enumerate()
+        .map(|(index, (start_time, end_time, title))| Chapter {
+            title,
+            start_time,
+            start_time_str: get_time_str(start_time),
+            end_time: end_time
+                .or(starts.get(index + 1).copied())
+                .or(duration)
+                .unwrap_or(start_time),
+        })
+        .collect()
+}
+
+pub async fn get_episode_track(episode: &PodcastEpisode, id: u64) -> Track {
+    let chapters = match &episode.chapters_url {
+        Some(chapters_url) => match fetch(chapters_url, CHAPTERS_MAX_SIZE, is_json_content_type).await {
+            Some(data) => parse_chapters(&data, episode.duration),
+            None => Vec::new()
+        },
+        None => Vec::new()
+    };
+    Track {
+        id,
+        title: episode.title.clone(),
+        description: episode.description.clone(),
+        thumbnail: episode.thumbnail.clone(),
+        author: Author {
+            name: episode.author.clone(),
+            url: None,
+            thumbnail: None,
+            verified: false,
+        },
+        url: episode.url.clone(),
+        views: None,
+        likes: None,
+        chapters,
+        webpage_url: episode.url.clone(),
+        duration: episode.duration,
+        is_live: false,
+        stream_title: None,
+        parse_time: Utc::now(),
+        parser_type: ParserType::Podcast,
+        edit_date: episode.published,
+        start: None,
+        end: None,
+        requester: None,
+    }
+}
+
+fn pickers() -> &'static Mutex<VecDeque<(u64, Vec<PodcastEpisode>)>> {
+    static PICKERS: OnceLock<Mutex<VecDeque<(u64, Vec<PodcastEpisode>)>>> = OnceLock::new();
+    PICKERS.get_or_init(|| Mutex::new(VecDeque::new()))
+}
+
+pub fn store_picker(message_id: u64, feed: &PodcastFeed) {
+    let mut pickers = pickers().lock().unwrap();
+    pickers.retain(|(id, _)| *id != message_id);
+    pickers.push_back((message_id, feed.episodes.iter().take(PICKER_EPISODES).cloned().collect()));
+    while pickers.len() > PICKERS_KEPT {
+        pickers.pop_front();
+    }
+}
+
+pub fn take_picked_episodes(message_id: u64, indices: &[String]) -> Option<Vec<PodcastEpisode>> {
+    let mut pickers = pickers().lock().unwrap();
+    let position = pickers.iter().position(|(id, _)| *id == message_id)?;
+    let (_, episodes) = pickers.remove(position)?;
+    Some(indices.iter()
+        .filter_map(|index| index.parse::<usize>().ok())
+        .filter_map(|index| episodes.get(index).cloned())
+        .collect())
+}
+
+fn truncate(text: String) -> String {
+    match text.chars().count() > SELECT_TEXT_LIMIT {
+        true => text.chars().take(SELECT_TEXT_LIMIT - 1).chain(std::iter::once('…')).collect(),
+        false => text
+    }
+}
+
+fn get_episode_picker(feed: &PodcastFeed, locale: &str) -> CreateActionRow {
+    let options: Vec<CreateSelectMenuOption> = feed.episodes.iter().take(PICKER_EPISODES).enumerate()
+        .map(|(index, episode)| {
+            let title = episode.title.clone().unwrap_or(episode.url.clone());
+            let description = [
+                episode.published.map(|published| published.format("%Y-%m-%d").to_string()),
+                episode.duration.map(get_time_str)
+            ].into_iter().flatten().collect::<Vec<String>>().join(" • ");
+            let option = CreateSelectMenuOption::new(truncate(title), index.to_string());
+            match description.is_empty() {
+                true => option,
+                false => option.description(truncate(description))
+            }
+        })
+        .collect();
+    let max_values = options.len() as u8;
+    CreateActionRow::SelectMenu(CreateSelectMenu::new("podcast_select", CreateSelectMenuKind::String { options })
+        .placeholder(match locale {
+            "ru" => "Добавить другие выпуски",
+            _ => "Add other episodes"
+        })
+        .min_values(1)
+        .max_values(max_values))
+}
+
+pub fn get_episode_response(feed: &PodcastFeed, track: &Track, locale: &str) -> EditInteractionResponse {
+    let mut embed = track.get_embed(locale)
+        .color(14441063)
+        .title(match locale {
+            "ru" => "Добавлен выпуск подкаста:",
+            _ => "Added podcast episode:"
+        });
+    if let Some(title) = &feed.title {
+        embed = embed.field(match locale {
+            "ru" => "Подкаст",
+            _ => "Podcast"
+        }, format!("[{}]({})", title, feed.webpage_url), false);
+    }
+    let builder = EditInteractionResponse::new().embed(embed);
+    match feed.episodes.len() > 1 {
+        true => builder.components(vec![get_episode_picker(feed, locale)]),
+        false => builder
+    }
+}
+
+pub fn add_subscription(conn: &mut SqliteConnection, guild: u64, podcast_title: &str, podcast_url: &str) -> QueryResult<usize> {
+    use crate::schema::podcast_subscriptions::dsl::*;
+    diesel::insert_into(podcast_subscriptions)
+        .values(NewPodcastSubscription {
+            guild_id: guild as i64,
+            title: podcast_title,
+            feed_url: podcast_url,
+        })
+        .on_conflict((guild_id, feed_url))
+        .do_update()
+        .set(title.eq(podcast_title))
+        .execute(conn)
+}
+
+pub fn remove_subscription(conn: &mut SqliteConnection, guild: u64, subscription_id: i32) -> QueryResult<bool> {
+    use crate::schema::podcast_subscriptions::dsl::*;
+    let removed = diesel::delete(podcast_subscriptions.filter(guild_id.eq(guild as i64).and(id.eq(subscription_id)))).execute(conn)?;
+    Ok(removed > 0)
+}
+
+pub fn get_subscription(conn: &mut SqliteConnection, guild: u64, subscription_id: i32) -> QueryResult<Option<PodcastSubscriptionDB>> {
+    use crate::schema::podcast_subscriptions::dsl::*;
+    podcast_subscriptions
+        .filter(guild_id.eq(guild as i64).and(id.eq(subscription_id)))
+        .select(PodcastSubscriptionDB::as_select())
+        .first(conn)
+        .optional()
+}
+
+pub fn list_subscriptions(conn: &mut SqliteConnection, guild: u64) -> QueryResult<Vec<PodcastSubscriptionDB>> {
+    use crate::schema::podcast_subscriptions::dsl::*;
+    podcast_subscriptions
+        .filter(guild_id.eq(guild as i64))
+        .order(title.asc())
+        .select(PodcastSubscriptionDB::as_select())
+        .load(conn)
+}
+
+#[cfg(test)]
+mod tests {
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
+    use super::*;
+
+    const RSS_FEED: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<rss version="2.0" xmlns:itunes="http://www.itunes.com/dtds/podcast-1.0.dtd" xmlns:podcast="https://podcastindex.org/namespace/1.0">
+  <channel>
+    <title>Test Podcast</title>
+    <link>https://example.com/podcast</link>
+    <description>Channel description</description>
+    <itunes:author>Test Author</itunes:author>
+    <itunes:image href="https://example.com/cover.jpg"/>
+    <item>
+      <title>Episode 1</title>
+      <description>First episode</description>
+      <pubDate>Mon, 01 Jan 2024 10:00:00 +0000</pubDate>
+      <itunes:duration>1:02:03</itunes:duration>
+      <enclosure url="https://example.com/episode1.mp3" type="audio/mpeg" length="1"/>
+    </item>
+    <item>
+      <title>Episode 2</title>
+      <itunes:summary>Second episode</itunes:summary>
+      <pubDate>Mon, 08 Jan 2024 10:00:00 +0000</pubDate>
+      <itunes:duration>1800</itunes:duration>
+      <itunes:image href="https://example.com/episode2.jpg"/>
+      <podcast:chapters url="https://example.com/episode2.json" type="application/json+chapters"/>
+      <enclosure url=" https://example.com/episode2.mp3 " type="audio/mpeg" length="1"/>
+    </item>
+    <item>
+      <title>Announcement without audio</title>
+      <pubDate>Mon, 15 Jan 2024 10:00:00 +0000</pubDate>
+    </item>
+  </channel>
+</rss>"#;
+
+    const ATOM_FEED: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<feed xmlns="http://www.w3.org/2005/Atom">
+  <title>Atom Podcast</title>
+  <subtitle>Feed subtitle</subtitle>
+  <logo>https://example.com/logo.png</logo>
+  <link rel="alternate" href="https://example.com/"/>
+  <author><name>Atom Author</name></author>
+  <entry>
+    <title>Atom Episode</title>
+    <summary>Atom summary</summary>
+    <updated>2024-02-01T12:00:00Z</updated>
+    <link rel="alternate" href="https://example.com/atom-episode"/>
+    <link rel="enclosure" href="https://example.com/atom-episode.ogg" type="audio/ogg"/>
+  </entry>
+  <entry>
+    <title>Text only</title>
+    <published>2024-03-01T12:00:00Z</published>
+  </entry>
+</feed>"#;
+
+    const CHAPTERS: &str = r#"{
+  "version": "1.2.0",
+  "chapters": [
+    {"startTime": 600, "title": "Outro"},
+    {"startTime": 0, "title": "Intro"},
+    {"startTime": 120, "title": "Hidden", "toc": false},
+    {"startTime": 300, "endTime": 400, "title": "Main"}
+  ]
+}"#;
+
+    async fn serve(headers: &'static str, body: String) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            while let Ok((mut socket, _)) = listener.accept().await {
+                let mut request = Vec::new();
+                let mut buffer = [0u8; 1024];
+                while !request.windows(4).any(|window| window == b"\r\n\r\n") {
+                    match socket.read(&mut buffer).await {
+                        Ok(0) | Err(_) => break,
+                        Ok(read) => request.extend_from_slice(&buffer[..read])
+                    }
+                }
+                let head = format!("HTTP/1.1 200 OK\r\n{}Content-Length: {}\r\nConnection: close\r\n\r\n", headers, body.len());
+                let _ = socket.write_all(head.as_bytes()).await;
+                let _ = socket.write_all(body.as_bytes()).await;
+                let _ = socket.shutdown().await;
+            }
+        });
+        format!("http://{}/feed.xml", address)
+    }
+
+    #[test]
+    fn parses_rss_feed() {
+        let feed = parse_feed(RSS_FEED, "https://example.com/feed.xml").unwrap();
+        assert_eq!(feed.title.as_deref(), Some("Test Podcast"));
+        assert_eq!(feed.description.as_deref(), Some("Channel description"));
+        assert_eq!(feed.author.as_deref(), Some("Test Author"));
+        assert_eq!(feed.thumbnail.as_deref(), Some("https://example.com/cover.jpg"));
+        assert_eq!(feed.link.as_deref(), Some("https://example.com/podcast"));
+        assert_eq!(feed.webpage_url, "https://example.com/feed.xml");
+        assert_eq!(feed.episodes.len(), 2);
+
+        let latest = &feed.episodes[0];
+        assert_eq!(latest.title.as_deref(), Some("Episode 2"));
+        assert_eq!(latest.description.as_deref(), Some("Second episode"));
+        assert_eq!(latest.url, "https://example.com/episode2.mp3");
+        assert_eq!(latest.duration, Some(1800.0));
+        assert_eq!(latest.thumbnail.as_deref(), Some("https://example.com/episode2.jpg"));
+        assert_eq!(latest.chapters_url.as_deref(), Some("https://example.com/episode2.json"));
+
+        let oldest = &feed.episodes[1];
+        assert_eq!(oldest.title.as_deref(), Some("Episode 1"));
+        assert_eq!(oldest.duration, Some(3723.0));
+        assert_eq!(oldest.thumbnail.as_deref(), Some("https://example.com/cover.jpg"));
+        assert_eq!(oldest.author.as_deref(), Some("Test Author"));
+        assert!(oldest.published < latest.published);
+    }
+
+    #[test]
+    fn parses_atom_feed() {
+        let feed = parse_feed(ATOM_FEED, "https://example.com/feed.atom").unwrap();
+        assert_eq!(feed.title.as_deref(), Some("Atom Podcast"));
+        assert_eq!(feed.description.as_deref(), Some("Feed subtitle"));
+        assert_eq!(feed.thumbnail.as_deref(), Some("https://example.com/logo.png"));
+        assert_eq!(feed.author.as_deref(), Some("Atom Author"));
+        assert_eq!(feed.link.as_deref(), Some("https://example.com/"));
+        assert_eq!(feed.episodes.len(), 1);
+
+        let episode = &feed.episodes[0];
+        assert_eq!(episode.title.as_deref(), Some("Atom Episode"));
+        assert_eq!(episode.description.as_deref(), Some("Atom summary"));
+        assert_eq!(episode.url, "https://example.com/atom-episode.ogg");
+        assert_eq!(episode.author.as_deref(), Some("Atom Author"));
+        assert!(episode.published.is_some());
+    }
+
+    #[test]
+    fn rejects_documents_without_episodes() {
+        assert!(parse_feed("<html><body>Not a feed</body></html>", "https://example.com/").is_none());
+        assert!(parse_feed("<rss><channel><title>Empty</title></channel></rss>", "https://example.com/").is_none());
+        assert!(parse_feed("not xml", "https://example.com/").is_none());
+    }
+
+    #[test]
+    fn parses_chapters() {
+        let chapters = parse_chapters(CHAPTERS, Some(900.0));
+        let chapters: Vec<(&str, f64, f64)> = chapters.iter()
+            .map(|chapter| (chapter.title.as_str(), chapter.start_time, chapter.end_time))
+            .collect();
+        assert_eq!(chapters, vec![("Intro", 0.0, 300.0), ("Main", 300.0, 400.0), ("Outro", 600.0, 900.0)]);
+        assert!(parse_chapters("{}", None).is_empty());
+        assert!(parse_chapters("not json", None).is_empty());
+    }
+
+    #[test]
+    fn detects_feed_urls() {
+        assert!(is_feed_url("https://example.com/podcast.rss"));
+        assert!(is_feed_url("https://feeds.example.com/show"));
+        assert!(is_feed_url("https://anchor.fm/s/1234/podcast/rss"));
+        assert!(is_feed_url("http://example.com/blog/feed/"));
+        assert!(!is_feed_url("https://www.youtube.com/watch?v=dQw4w9WgXcQ"));
+        assert!(!is_feed_url("https://example.com/stream"));
+        assert!(!is_feed_url("ftp://example.com/feed.xml"));
+    }
+
+    #[tokio::test]
+    async fn fetches_feed() {
+        let url = serve("Content-Type: application/rss+xml\r\n", RSS_FEED.to_string()).await;
+        let feed = fetch_feed(&url).await.unwrap();
+        assert_eq!(feed.webpage_url, url);
+        assert_eq!(feed.episodes.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn rejects_wrong_content_type() {
+        let url = serve("Content-Type: text/html; charset=utf-8\r\n", RSS_FEED.to_string()).await;
+        assert!(fetch_feed(&url).await.is_none());
+        let url = serve("Content-Type: audio/mpeg\r\n", RSS_FEED.to_string()).await;
+        assert!(fetch_feed(&url).await.is_none());
+        let url = serve("Content-Type: application/xml\r\nicy-name: Radio\r\n", RSS_FEED.to_string()).await;
+        assert!(fetch_feed(&url).await.is_none());
+    }
+
+    #[tokio::test]
+    async fn rejects_oversized_feed() {
+        let padding = " ".repeat(FEED_MAX_SIZE);
+        let url = serve("Content-Type: application/rss+xml\r\n", format!("{}{}", RSS_FEED, padding)).await;
+        assert!(fetch_feed(&url).await.is_none());
+    }
+
+    #[tokio::test]
+    async fn fetches_episode_chapters() {
+        let chapters_url = serve("Content-Type: application/json+chapters\r\n", CHAPTERS.to_string()).await;
+        let feed = parse_feed(RSS_FEED, "https://example.com/feed.xml").unwrap();
+        let mut episode = feed.episodes[0].clone();
+        episode.chapters_url = Some(chapters_url);
+        let track = get_episode_track(&episode, 7).await;
+        assert_eq!(track.id, 7);
+        assert_eq!(track.url, "https://example.com/episode2.mp3");
+        assert_eq!(track.parser_type, ParserType::Podcast);
+        assert_eq!(track.chapters.len(), 3);
+        assert_eq!(track.chapters[2].end_time, 1800.0);
+    }
+}
@@ -0,0 +1,58 @@
+use std::sync::Arc;
+
+use serenity::client::Context;
+use serenity::gateway::{ActivityData, ShardManager};
+use songbird::typemap::TypeMapKey;
+
+use super::player::{PlayerData, PlayerDataType, PlayerState};
+
+#[derive(Clone)]
+pub struct Presence {
+    pub shard_manager: Arc<ShardManager>,
+    pub enabled: bool
+}
+
+pub struct PresenceData;
+
+impl TypeMapKey for PresenceData {
+    type Value = Presence;
+}
+
+pub fn spawn_presence_update(presence: Presence, player_data: PlayerDataType) {
+    if !presence.enabled {
+        return;
+    }
+    tokio::spawn(async move {
+        update_presence(&presence, &player_data).await;
+    });
+}
+
+pub async fn spawn_presence_update_ctx(ctx: &Context) {
+    let data = ctx.data.read().await;
+    if let (Some(presence), Some(player_data)) = (data.get::<PresenceData>(), data.get::<PlayerData>()) {
+        spawn_presence_update(presence.clone(), player_data.clone());
+    }
+}
+
+pub async fn update_presence(presence: &Presence, player_data: &PlayerDataType) {
+    let players = player_data.read().await.clone();
+    let mut playing = Vec::new();
+    for player in players.values() {
+        if let PlayerState::Playing = *player.state.read().await {
+            playing.push(player.playlist.read().await.current.clone());
+        }
+    }
+
+    let activity = match playing.as_slice() {
+        [] => None,
+        [track] => match track.as_ref().and_then(|track| track.title.clone()) {
+            Some(title) => Some(ActivityData::listening(title)),
+            None => Some(ActivityData::playing("music"))
+        },
+        tracks => Some(ActivityData::playing(format!("in {} servers", tracks.len())))
+    };
+
+    for runner in presence.shard_manager.runners.lock().await.values() {
+        runner.runner_tx.set_activity(activity.clone());
+    }
+}
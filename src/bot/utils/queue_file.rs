@@ -0,0 +1,188 @@
+use super::resolver::source_registry;
+use super::track::Track;
+
+pub const IMPORT_LIMIT: usize = 100;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum QueueFormat {
+    M3u,
+    Xspf,
+    Json,
+}
+
+impl QueueFormat {
+    pub fn from_str(format: &str) -> Option<Self> {
+        match format.to_lowercase().as_str() {
+            "m3u" | "m3u8" => Some(Self::M3u),
+            "xspf" => Some(Self::Xspf),
+            "json" => Some(Self::Json),
+            _ => None
+        }
+    }
+
+    pub fn from_filename(filename: &str) -> Option<Self> {
+        Self::from_str(filename.rsplit('.').next()?)
+    }
+
+    pub fn detect(data: &str) -> Self {
+        let data = data.trim_start();
+        if data.starts_with('[') {
+            Self::Json
+        } else if data.starts_with("<?xml") || data.starts_with("<playlist") {
+            Self::Xspf
+        } else {
+            Self::M3u
+        }
+    }
+
+    pub fn extension(&self) -> &'static str {
+        match self {
+            Self::M3u => "m3u",
+            Self::Xspf => "xspf",
+            Self::Json => "json"
+        }
+    }
+
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            Self::M3u => "audio/x-mpegurl",
+            Self::Xspf => "application/xspf+xml",
+            Self::Json => "application/json"
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum QueueEntry {
+    Track(Track),
+    Url {
+        url: String,
+        title: Option<String>,
+        duration: Option<f64>
+    }
+}
+
+fn get_display_title(track: &Track) -> String {
+    let title = track.title.clone().unwrap_or(track.webpage_url.clone());
+    match &track.author.name {
+        Some(author) => format!("{} - {}", author, title),
+        None => title
+    }
+}
+
+fn escape_xml(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
+pub fn export_queue(tracks: &[Track], format: QueueFormat) -> String {
+    match format {
+        QueueFormat::M3u => {
+            let mut data = String::from("#EXTM3U\n");
+            for track in tracks {
+                let duration = match track.duration {
+                    Some(duration) => duration.round() as i64,
+                    None => -1
+                };
+                data.push_str(&format!("#EXTINF:{},{}\n{}\n", duration, get_display_title(track).replace('\n', " "), track.webpage_url));
+            }
+            data
+        },
+        QueueFormat::Xspf => {
+            let mut data = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<playlist version=\"1\" xmlns=\"http://xspf.org/ns/0/\">\n  <trackList>\n");
+            for track in tracks {
+                data.push_str("    <track>\n");
+                data.push_str(&format!("      <location>{}</location>\n", escape_xml(&track.webpage_url)));
+                if let Some(title) = &track.title {
+                    data.push_str(&format!("      <title>{}</title>\n", escape_xml(title)));
+                }
+                if let Some(author) = &track.author.name {
+                    data.push_str(&format!("      <creator>{}</creator>\n", escape_xml(author)));
+                }
+                if let Some(duration) = track.duration {
+                    data.push_str(&format!("      <duration>{}</duration>\n", (duration * 1000.0).round() as i64));
+                }
+                if let Some(thumbnail) = &track.thumbnail {
+                    data.push_str(&format!("      <image>{}</image>\n", escape_xml(thumbnail)));
+                }
+                data.push_str("    </track>\n");
+            }
+            data.push_str("  </trackList>\n</playlist>\n");
+            data
+        },
+        QueueFormat::Json => serde_json::to_string_pretty(tracks).unwrap_or("[]".to_string())
+    }
+}
+
+fn is_remote_url(url: &str) -> bool {
+    url.starts_with("http://") || url.starts_with("https://") || url.starts_with("ftp://")
+}
+
+pub fn import_queue(data: &str, format: QueueFormat) -> Result<Vec<QueueEntry>, String> {
+    match format {
+        QueueFormat::M3u => {
+            let mut entries = Vec::new();
+            let mut info: (Option<f64>, Option<String>) = (None, None);
+            for line in data.lines().map(|line| line.trim()) {
+                if let Some(extinf) = line.strip_prefix("#EXTINF:") {
+                    let (duration, title) = extinf.split_once(',').unwrap_or((extinf, ""));
+                    info = (
+                        duration.trim().parse::<f64>().ok().filter(|duration| *duration > 0.0),
+                        Some(title.trim().to_string()).filter(|title| !title.is_empty())
+                    );
+                } else if !line.is_empty() && !line.starts_with('#') {
+                    if is_remote_url(line) {
+                        entries.push(QueueEntry::Url { url: line.to_string(), title: info.1.take(), duration: info.0.take() });
+                    }
+                    info = (None, None);
+                }
+            }
+            Ok(entries)
+        },
+        QueueFormat::Xspf => {
+            let document = roxmltree::Document::parse(data).map_err(|error| error.to_string())?;
+            Ok(document.descendants()
+                .filter(|node| node.has_tag_name("track"))
+                .filter_map(|node| {
+                    let get_text = |name: &str| node.children()
+                        .find(|child| child.has_tag_name(name))
+                        .and_then(|child| child.text())
+                        .map(|text| text.trim().to_string());
+                    Some(QueueEntry::Url {
+                        url: get_text("location").filter(|url| is_remote_url(url))?,
+                        title: get_text("title"),
+                        duration: get_text("duration").and_then(|duration| duration.parse::<f64>().ok()).map(|duration| duration / 1000.0)
+                    })
+                })
+                .collect())
+        },
+        QueueFormat::Json => serde_json::from_str::<Vec<Track>>(data)
+            .map(|tracks| tracks.into_iter()
+                .filter(|track| is_remote_url(&track.webpage_url))
+                .map(|track| QueueEntry::Url { url: track.webpage_url, title: track.title, duration: track.duration })
+                .collect())
+            .map_err(|error| error.to_string())
+    }
+}
+
+pub async fn resolve_entry(entry: QueueEntry, id: u64) -> Option<Track> {
+    match entry {
+        QueueEntry::Track(mut track) => {
+            track.id = id;
+            Some(track)
+        },
+        QueueEntry::Url { url, title, duration } => {
+            let mut track = source_registry().resolve_track(url.as_str(), id).await?;
+            if track.title.is_none() {
+                track.title = title;
+            }
+            if track.duration.is_none() {
+                track.duration = duration;
+            }
+            Some(track)
+        }
+    }
+}
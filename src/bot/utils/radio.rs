@@ -0,0 +1,311 @@
+use std::time::Duration;
+
+use chrono::Utc;
+use diesel::{BoolExpressionMethods, ExpressionMethods, OptionalExtension, QueryDsl, QueryResult, RunQueryDsl, SelectableHelper, SqliteConnection};
+use reqwest::header::HeaderMap;
+use reqwest::Url;
+use serenity::client::Cache;
+use serenity::futures::StreamExt;
+use serenity::http::Http;
+use tokio::time::{sleep, timeout};
+
+use super::announce::update_announcement;
+use super::http_client;
+use super::player::{Player, PlayerState};
+use super::track::{Author, ParserType, Track};
+use crate::models::{NewRadioStation, RadioStationDB};
+
+const STATION_FILE_EXTENSIONS: [&str; 2] = ["pls", "m3u"];
+const STATION_FILE_MAX_SIZE: usize = 64 * 1024;
+const REQUEST_TIMEOUT_SECS: u64 = 10;
+const METADATA_RECONNECT_SECS: u64 = 5;
+const AUDIO_CONTENT_TYPES: [&str; 4] = ["audio/", "application/ogg", "application/aacp", "video/mp2t"];
+const STREAM_HOST_LABELS: [&str; 6] = ["stream", "streams", "streaming", "icecast", "shoutcast", "radio"];
+const STREAM_HOSTS: [&str; 5] = ["streamtheworld.com", "zeno.fm", "radio.co", "radiojar.com", "radioking.com"];
+const STREAM_PATH_NAMES: [&str; 5] = ["stream", "live", "listen", ";", ";stream.nsv"];
+
+pub fn is_station_file_url(url: &str) -> bool {
+    if !(url.starts_with("http://") || url.starts_with("https://")) {
+        return false;
+    }
+    let path = url.split(['?', '#']).next().unwrap_or(url);
+    match path.rsplit('/').next().and_then(|name| name.rsplit_once('.')) {
+        Some((_, extension)) => STATION_FILE_EXTENSIONS.contains(&extension.to_lowercase().as_str()),
+        None => false
+    }
+}
+
+pub fn is_station_url(url: &str) -> bool {
+    if is_station_file_url(url) {
+        return true;
+    }
+    let url = match Url::parse(url) {
+        Ok(url) if matches!(url.scheme(), "http" | "https") => url,
+        _ => return false
+    };
+    let host = url.host_str().unwrap_or_default().to_lowercase();
+    let name = url.path_segments().and_then(|segments| segments.last()).unwrap_or_default().to_lowercase();
+    url.port().is_some()
+        || host.split('.').any(|label| STREAM_HOST_LABELS.contains(&label))
+        || STREAM_HOSTS.iter().any(|stream_host| host == *stream_host || host.ends_with(&format!(".{}", stream_host)))
+        || STREAM_PATH_NAMES.contains(&name.as_str())
+}
+
+fn parse_station_file(data: &str) -> Vec<(String, Option<String>)> {
+    let mut streams: Vec<(String, Option<String>)> = Vec::new();
+    let mut titles: Vec<(String, String)> = Vec::new();
+    let mut extinf_title: Option<String> = None;
+    for line in data.lines().map(|line| line.trim()).filter(|line| !line.is_empty()) {
+        if let Some(title) = line.strip_prefix("#EXTINF:") {
+            extinf_title = title.split_once(',').map(|(_, title)| title.trim().to_string()).filter(|title| !title.is_empty());
+        } else if line.starts_with('#') || line.starts_with('[') {
+            continue;
+        } else if let Some((key, value)) = line.split_once('=') {
+            let key = key.trim().to_lowercase();
+            if let Some(index) = key.strip_prefix("file") {
+                streams.push((value.trim().to_string(), Some(index.to_string())));
+            } else if let Some(index) = key.strip_prefix("title") {
+                titles.push((index.to_string(), value.trim().to_string()));
+            }
+        } else if line.starts_with("http://") || line.starts_with("https://") {
+            streams.push((line.to_string(), extinf_title.take()));
+        }
+    }
+    streams.into_iter()
+        .map(|(url, title)| {
+            let title = match title {
+                Some(index) if index.chars().all(|char| char.is_ascii_digit()) => titles.iter().find(|(title_index, _)| *title_index == index).map(|(_, title)| title.clone()),
+                title => title
+            };
+            (url, title.filter(|title| !title.is_empty()))
+        })
+        .filter(|(url, _)| url.starts_with("http://") || url.starts_with("https://"))
+        .collect()
+}
+
+async fn fetch_station_file(url: &str) -> Option<Vec<(String, Option<String>)>> {
+    let response = timeout(Duration::from_secs(REQUEST_TIMEOUT_SECS), http_client().get(url).send()).await.ok()?.ok()?;
+    if !response.status().is_success() {
+        return None;
+    }
+    let mut data = Vec::new();
+    let mut stream = response.bytes_stream();
+    while let Some(chunk) = timeout(Duration::from_secs(REQUEST_TIMEOUT_SECS), stream.next()).await.ok()? {
+        data.extend_from_slice(&chunk.ok()?);
+        if data.len() > STATION_FILE_MAX_SIZE {
+            return None;
+        }
+    }
+    Some(parse_station_file(&String::from_utf8_lossy(&data)))
+}
+
+fn get_header(headers: &HeaderMap, name: &str) -> Option<String> {
+    headers.get(name)
+        .and_then(|value| value.to_str().ok())
+        .map(|value| value.trim().to_string())
+        .filter(|value| !value.is_empty())
+}
+
+async fn probe_stream(url: &str, track_id: u64) -> Option<Track> {
+    let response = timeout(Duration::from_secs(REQUEST_TIMEOUT_SECS), http_client().get(url).header("Icy-MetaData", "1").send()).await.ok()?.ok()?;
+    if !response.status().is_success() {
+        return None;
+    }
+    let headers = response.headers();
+    let content_type = get_header(headers, "content-type").unwrap_or_default().to_lowercase();
+    let is_icy = headers.keys().any(|name| name.as_str().starts_with("icy-"));
+    if !is_icy && !AUDIO_CONTENT_TYPES.iter().any(|audio_type| content_type.starts_with(audio_type)) {
+        return None;
+    }
+    if !is_icy && headers.get("content-length").is_some() {
+        return None;
+    }
+    Some(Track {
+        id: track_id,
+        title: get_header(headers, "icy-name"),
+        description: get_header(headers, "icy-description"),
+        thumbnail: None,
+        author: Author {
+            name: get_header(headers, "icy-genre"),
+            url: get_header(headers, "icy-url"),
+            thumbnail: None,
+            verified: false,
+        },
+        url: response.url().to_string(),
+        views: None,
+        likes: None,
+        chapters: Vec::new(),
+        webpage_url: url.to_string(),
+        duration: None,
+        is_live: true,
+        stream_title: None,
+        parse_time: Utc::now(),
+        parser_type: ParserType::Radio,
+        edit_date: None,
+        start: None,
+        end: None,
+        requester: None,
+    })
+}
+
+pub async fn resolve_radio(url: &str, track_id: u64) -> Option<Track> {
+    if !is_station_file_url(url) {
+        return probe_stream(url, track_id).await;
+    }
+    for (stream_url, title) in fetch_station_file(url).await? {
+        if let Some(mut track) = probe_stream(&stream_url, track_id).await {
+            track.title = title.or(track.title);
+            track.webpage_url = url.to_string();
+            return Some(track);
+        }
+    }
+    None
+}
+
+struct IcyReader {
+    metaint: usize,
+    audio_left: usize,
+    metadata_left: Option<usize>,
+    metadata: Vec<u8>,
+}
+
+impl IcyReader {
+    fn new(metaint: usize) -> Self {
+        Self {
+            metaint,
+            audio_left: metaint,
+            metadata_left: None,
+            metadata: Vec::new(),
+        }
+    }
+
+    fn push(&mut self, mut chunk: &[u8]) -> Option<String> {
+        let mut title = None;
+        while !chunk.is_empty() {
+            match self.metadata_left {
+                None if self.audio_left > 0 => {
+                    let skipped = self.audio_left.min(chunk.len());
+                    self.audio_left -= skipped;
+                    chunk = &chunk[skipped..];
+                },
+                None => {
+                    self.metadata_left = Some(chunk[0] as usize * 16);
+                    self.metadata.clear();
+                    chunk = &chunk[1..];
+                },
+                Some(left) => {
+                    let taken = left.min(chunk.len());
+                    self.metadata.extend_from_slice(&chunk[..taken]);
+                    chunk = &chunk[taken..];
+                    if left == taken {
+                        self.metadata_left = None;
+                        self.audio_left = self.metaint;
+                        if !self.metadata.is_empty() {
+                            title = get_stream_title(&String::from_utf8_lossy(&self.metadata)).or(title);
+                        }
+                    } else {
+                        self.metadata_left = Some(left - taken);
+                    }
+                }
+            }
+        }
+        title
+    }
+}
+
+fn get_stream_title(metadata: &str) -> Option<String> {
+    let start = metadata.find("StreamTitle='")? + "StreamTitle='".len();
+    let rest = &metadata[start..];
+    let end = rest.find("';").unwrap_or(rest.len());
+    Some(rest[..end].trim_end_matches(['\0', '\'']).trim().to_string())
+}
+
+async fn is_current(player: &Player, track_id: u64) -> bool {
+    let is_current_track = player.playlist.read().await.current.as_ref().map(|track| track.id == track_id).unwrap_or(false);
+    is_current_track && !matches!(*player.state.read().await, PlayerState::Ended)
+}
+
+async fn set_stream_title(cache: &Cache, http: &Http, player: &Player, track_id: u64, stream_title: String) {
+    let track = {
+        let mut player_playlist = player.playlist.write().await;
+        match player_playlist.current.as_mut() {
+            Some(track) if track.id == track_id => {
+                track.stream_title = Some(stream_title).filter(|title| !title.is_empty());
+                track.clone()
+            },
+            _ => return
+        }
+    };
+    update_announcement(cache, http, player, &track).await;
+}
+
+pub async fn watch_stream_title(cache: &Cache, http: &Http, player: &Player, track: &Track) {
+    let mut last_title: Option<String> = None;
+    while is_current(player, track.id).await {
+        let url = player.playlist.read().await.current.as_ref().map(|track| track.url.clone()).unwrap_or(track.url.clone());
+        let response = timeout(Duration::from_secs(REQUEST_TIMEOUT_SECS), http_client().get(&url).header("Icy-MetaData", "1").send()).await;
+        let response = match response {
+            Ok(Ok(response)) if response.status().is_success() => response,
+            _ => {
+                sleep(Duration::from_secs(METADATA_RECONNECT_SECS)).await;
+                continue;
+            }
+        };
+        let metaint = match get_header(response.headers(), "icy-metaint").and_then(|metaint| metaint.parse::<usize>().ok()) {
+            Some(metaint) if metaint > 0 => metaint,
+            _ => return
+        };
+        let mut reader = IcyReader::new(metaint);
+        let mut stream = response.bytes_stream();
+        while let Ok(Some(Ok(chunk))) = timeout(Duration::from_secs(REQUEST_TIMEOUT_SECS), stream.next()).await {
+            if let Some(title) = reader.push(&chunk) {
+                if last_title.as_ref() != Some(&title) {
+                    last_title = Some(title.clone());
+                    set_stream_title(cache, http, player, track.id, title).await;
+                }
+            }
+            if !is_current(player, track.id).await {
+                return;
+            }
+        }
+        sleep(Duration::from_secs(METADATA_RECONNECT_SECS)).await;
+    }
+}
+
+pub fn add_station(conn: &mut SqliteConnection, guild: u64, station_name: &str, station_url: &str) -> QueryResult<usize> {
+    use crate::schema::radio_stations::dsl::*;
+    diesel::insert_into(radio_stations)
+        .values(NewRadioStation {
+            guild_id: guild as i64,
+            name: station_name,
+            url: station_url,
+        })
+        .on_conflict((guild_id, name))
+        .do_update()
+        .set(url.eq(station_url))
+        .execute(conn)
+}
+
+pub fn remove_station(conn: &mut SqliteConnection, guild: u64, station_name: &str) -> QueryResult<bool> {
+    use crate::schema::radio_stations::dsl::*;
+    let removed = diesel::delete(radio_stations.filter(guild_id.eq(guild as i64).and(name.eq(station_name)))).execute(conn)?;
+    Ok(removed > 0)
+}
+
+pub fn get_station(conn: &mut SqliteConnection, guild: u64, station_name: &str) -> QueryResult<Option<RadioStationDB>> {
+    use crate::schema::radio_stations::dsl::*;
+    radio_stations
+        .filter(guild_id.eq(guild as i64).and(name.eq(station_name)))
+        .select(RadioStationDB::as_select())
+        .first(conn)
+        .optional()
+}
+
+pub fn list_stations(conn: &mut SqliteConnection, guild: u64) -> QueryResult<Vec<RadioStationDB>> {
+    use crate::schema::radio_stations::dsl::*;
+    radio_stations
+        .filter(guild_id.eq(guild as i64))
+        .order(name.asc())
+        .select(RadioStationDB::as_select())
+        .load(conn)
+}
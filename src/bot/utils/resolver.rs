@@ -0,0 +1,487 @@
+use std::env;
+use std::sync::OnceLock;
+use std::time::Duration;
+
+use diesel::{r2d2::{ConnectionManager, Pool}, SqliteConnection};
+use regex::Regex;
+use serenity::async_trait;
+use serenity::futures::{stream, Stream, StreamExt};
+use tokio::time::timeout;
+use youtube_dl::SearchType;
+
+use super::ffprobe::{is_direct_media_url, probe_media};
+use super::library::{get_library_track, get_track, search_library};
+use super::parser::{find_best_audio, find_best_audio_twitch, get_url_timestamp, parse_url_vk, parse_url_yt, search_track_vk, search_track_yt, search_tracks_vk, search_tracks_yt, ParsedDataType};
+use super::podcast::{fetch_feed, get_episode_track, is_feed_url};
+use super::radio::{is_station_url, resolve_radio};
+use super::track::{ParserType, Track};
+use super::track_cache::{cache_track, get_cached_track, get_search_key, get_url_key, is_cacheable};
+
+pub const DEFAULT_SEARCH_SOURCE: &str = "youtube";
+const DEFAULT_RESOLVE_WORKERS: usize = 4;
+const DEFAULT_RESOLVE_TIMEOUT_SECS: u64 = 30;
+
+#[async_trait]
+pub trait SourceResolver: Send + Sync {
+    fn name(&self) -> &'static str;
+
+    fn display_name(&self) -> &'static str;
+
+    fn matches(&self, url: &str) -> bool;
+
+    fn handles(&self, track: &Track) -> bool {
+        self.matches(&track.webpage_url)
+    }
+
+    async fn resolve(&self, url: &str, track_id: u64) -> ParsedDataType;
+
+    fn searchable(&self) -> bool {
+        false
+    }
+
+    async fn search(&self, _query: &str, _track_id: u64) -> Option<Track> {
+        None
+    }
+
+    async fn search_many(&self, query: &str, _count: usize) -> Vec<Track> {
+        self.search(query, 0).await.into_iter().collect()
+    }
+
+    async fn refresh(&self, track: &Track) -> Option<String> {
+        match self.resolve(&track.webpage_url, track.id).await {
+            ParsedDataType::Track(track) | ParsedDataType::YtDlPlaylist((_, track)) | ParsedDataType::VkPlaylist((_, track)) | ParsedDataType::Podcast((_, track)) => Some(track.url),
+            ParsedDataType::None => None
+        }
+    }
+}
+
+fn is_match(pattern: &str, url: &str) -> bool {
+    match Regex::new(pattern) {
+        Ok(re) => re.is_match(url),
+        Err(_) => false
+    }
+}
+
+struct DirectMediaResolver;
+
+#[async_trait]
+impl SourceResolver for DirectMediaResolver {
+    fn name(&self) -> &'static str { "direct" }
+
+    fn display_name(&self) -> &'static str { "Direct link" }
+
+    fn matches(&self, url: &str) -> bool {
+        url.starts_with("file://") || is_direct_media_url(url)
+    }
+
+    async fn resolve(&self, url: &str, track_id: u64) -> ParsedDataType {
+        match probe_media(url, track_id).await {
+            Some(track) => ParsedDataType::Track(track),
+            None => ParsedDataType::None
+        }
+    }
+
+    async fn refresh(&self, track: &Track) -> Option<String> {
+        Some(track.url.clone())
+    }
+}
+
+struct LibraryResolver {
+    pool: Pool<ConnectionManager<SqliteConnection>>,
+}
+
+#[async_trait]
+impl SourceResolver for LibraryResolver {
+    fn name(&self) -> &'static str { "library" }
+
+    fn display_name(&self) -> &'static str { "Library" }
+
+    fn matches(&self, url: &str) -> bool {
+        url.starts_with("library://")
+    }
+
+    async fn resolve(&self, url: &str, track_id: u64) -> ParsedDataType {
+        let library_track = url.trim_start_matches("library://").parse::<i32>().ok()
+            .and_then(|library_id| self.pool.get().ok().and_then(|mut conn| get_library_track(&mut conn, library_id).ok().flatten()));
+        match library_track {
+            Some(library_track) => ParsedDataType::Track(get_track(&library_track, track_id)),
+            None => ParsedDataType::None
+        }
+    }
+
+    fn searchable(&self) -> bool {
+        true
+    }
+
+    async fn search(&self, query: &str, track_id: u64) -> Option<Track> {
+        let mut conn = self.pool.get().ok()?;
+        let mut found = search_library(&mut conn, query, 1).ok()?;
+        found.pop().map(|library_track| get_track(&library_track, track_id))
+    }
+
+    async fn search_many(&self, query: &str, count: usize) -> Vec<Track> {
+        let found = match self.pool.get() {
+            Ok(mut conn) => search_library(&mut conn, query, count as i64).unwrap_or_default(),
+            Err(_) => Vec::new()
+        };
+        found.iter().map(|library_track| get_track(library_track, 0)).collect()
+    }
+
+    async fn refresh(&self, track: &Track) -> Option<String> {
+        Some(track.url.clone())
+    }
+}
+
+struct VkResolver;
+
+#[async_trait]
+impl SourceResolver for VkResolver {
+    fn name(&self) -> &'static str { "vk" }
+
+    fn display_name(&self) -> &'static str { "VK" }
+
+    fn matches(&self, url: &str) -> bool {
+        is_match(r"^(http:\/\/|https:\/\/)?(www.)?(vk\.com|vkontakte\.ru)\/", url)
+    }
+
+    async fn resolve(&self, url: &str, track_id: u64) -> ParsedDataType {
+        parse_url_vk(url, track_id).await
+    }
+
+    fn searchable(&self) -> bool {
+        true
+    }
+
+    async fn search(&self, query: &str, track_id: u64) -> Option<Track> {
+        search_track_vk(query, track_id).await
+    }
+
+    async fn search_many(&self, query: &str, count: usize) -> Vec<Track> {
+        search_tracks_vk(query, count).await
+    }
+}
+
+struct TwitchResolver;
+
+#[async_trait]
+impl SourceResolver for TwitchResolver {
+    fn name(&self) -> &'static str { "twitch" }
+
+    fn display_name(&self) -> &'static str { "Twitch" }
+
+    fn matches(&self, url: &str) -> bool {
+        is_match(r"^(http:\/\/|https:\/\/)?(www\.|m\.)?twitch\.tv\/", url)
+    }
+
+    async fn resolve(&self, url: &str, track_id: u64) -> ParsedDataType {
+        parse_url_yt(url, track_id, find_best_audio_twitch).await
+    }
+}
+
+struct YouTubeResolver;
+
+#[async_trait]
+impl SourceResolver for YouTubeResolver {
+    fn name(&self) -> &'static str { "youtube" }
+
+    fn display_name(&self) -> &'static str { "YouTube" }
+
+    fn matches(&self, url: &str) -> bool {
+        is_match(r"^(http:\/\/|https:\/\/)?(www\.|m\.|music\.)?(youtube\.com|youtu\.be)\/", url)
+    }
+
+    async fn resolve(&self, url: &str, track_id: u64) -> ParsedDataType {
+        parse_url_yt(url, track_id, find_best_audio).await
+    }
+
+    fn searchable(&self) -> bool {
+        true
+    }
+
+    async fn search(&self, query: &str, track_id: u64) -> Option<Track> {
+        search_track_yt(query, track_id, SearchType::Youtube).await
+    }
+
+    async fn search_many(&self, query: &str, count: usize) -> Vec<Track> {
+        search_tracks_yt(query, count, SearchType::Youtube).await
+    }
+}
+
+struct SoundCloudResolver;
+
+#[async_trait]
+impl SourceResolver for SoundCloudResolver {
+    fn name(&self) -> &'static str { "soundcloud" }
+
+    fn display_name(&self) -> &'static str { "SoundCloud" }
+
+    fn matches(&self, url: &str) -> bool {
+        is_match(r"^(http:\/\/|https:\/\/)?(www\.|m\.)?(soundcloud\.com|on\.soundcloud\.com)\/", url)
+    }
+
+    async fn resolve(&self, url: &str, track_id: u64) -> ParsedDataType {
+        parse_url_yt(url, track_id, find_best_audio).await
+    }
+
+    fn searchable(&self) -> bool {
+        true
+    }
+
+    async fn search(&self, query: &str, track_id: u64) -> Option<Track> {
+        search_track_yt(query, track_id, SearchType::SoundCloud).await
+    }
+
+    async fn search_many(&self, query: &str, count: usize) -> Vec<Track> {
+        search_tracks_yt(query, count, SearchType::SoundCloud).await
+    }
+}
+
+struct PodcastResolver;
+
+#[async_trait]
+impl SourceResolver for PodcastResolver {
+    fn name(&self) -> &'static str { "podcast" }
+
+    fn display_name(&self) -> &'static str { "Podcast" }
+
+    fn matches(&self, url: &str) -> bool {
+        is_feed_url(url)
+    }
+
+    fn handles(&self, track: &Track) -> bool {
+        track.parser_type == ParserType::Podcast || self.matches(&track.webpage_url)
+    }
+
+    async fn resolve(&self, url: &str, track_id: u64) -> ParsedDataType {
+        let feed = match fetch_feed(url).await {
+            Some(feed) => feed,
+            None => return ParsedDataType::None
+        };
+        match feed.episodes.first() {
+            Some(episode) => {
+                let track = get_episode_track(episode, track_id).await;
+                ParsedDataType::Podcast((feed, track))
+            },
+            None => ParsedDataType::None
+        }
+    }
+
+    async fn refresh(&self, track: &Track) -> Option<String> {
+        match track.parser_type {
+            ParserType::Podcast => Some(track.url.clone()),
+            _ => None
+        }
+    }
+}
+
+struct RadioResolver;
+
+#[async_trait]
+impl SourceResolver for RadioResolver {
+    fn name(&self) -> &'static str { "radio" }
+
+    fn display_name(&self) -> &'static str { "Radio" }
+
+    fn matches(&self, url: &str) -> bool {
+        is_station_url(url)
+    }
+
+    fn handles(&self, track: &Track) -> bool {
+        track.parser_type == ParserType::Radio || self.matches(&track.webpage_url)
+    }
+
+    async fn resolve(&self, url: &str, track_id: u64) -> ParsedDataType {
+        match resolve_radio(url, track_id).await {
+            Some(track) => ParsedDataType::Track(track),
+            None => ParsedDataType::None
+        }
+    }
+
+    async fn refresh(&self, track: &Track) -> Option<String> {
+        if track.parser_type != ParserType::Radio {
+            return None;
+        }
+        resolve_radio(&track.webpage_url, track.id).await.map(|track| track.url)
+    }
+}
+
+struct YtDlResolver;
+
+#[async_trait]
+impl SourceResolver for YtDlResolver {
+    fn name(&self) -> &'static str { "ytdl" }
+
+    fn display_name(&self) -> &'static str { "yt-dlp" }
+
+    fn matches(&self, url: &str) -> bool {
+        url.starts_with("http://") || url.starts_with("https://") || url.starts_with("ftp://")
+    }
+
+    async fn resolve(&self, url: &str, track_id: u64) -> ParsedDataType {
+        parse_url_yt(url, track_id, find_best_audio).await
+    }
+}
+
+pub struct SourceRegistry {
+    resolvers: Vec<Box<dyn SourceResolver>>,
+    cache: Option<Pool<ConnectionManager<SqliteConnection>>>,
+}
+
+impl SourceRegistry {
+    pub fn new() -> Self {
+        Self {
+            resolvers: vec![
+                Box::new(DirectMediaResolver),
+                Box::new(YouTubeResolver),
+                Box::new(SoundCloudResolver),
+                Box::new(VkResolver),
+                Box::new(TwitchResolver),
+                Box::new(PodcastResolver),
+                Box::new(RadioResolver),
+                Box::new(YtDlResolver),
+            ],
+            cache: None
+        }
+    }
+
+    pub fn set_cache(&mut self, pool: Pool<ConnectionManager<SqliteConnection>>) {
+        self.cache = Some(pool);
+    }
+
+    fn get_cached(&self, key: &str, track_id: u64) -> Option<Track> {
+        let mut conn = self.cache.as_ref()?.get().ok()?;
+        let mut track = get_cached_track(&mut conn, key).ok()??;
+        track.id = track_id;
+        Some(track)
+    }
+
+    fn store_cached(&self, keys: &[String], track: &Track) {
+        if !is_cacheable(track) {
+            return;
+        }
+        if let Some(Ok(mut conn)) = self.cache.as_ref().map(|pool| pool.get()) {
+            if let Err(why) = cache_track(&mut conn, keys, track) {
+                log::warn!("Failed to cache track: {:?}", why);
+            }
+        }
+    }
+
+    pub fn register(&mut self, resolver: Box<dyn SourceResolver>) {
+        self.resolvers.insert(0, resolver);
+    }
+
+    pub fn get(&self, name: &str) -> Option<&dyn SourceResolver> {
+        self.resolvers.iter().find(|resolver| resolver.name() == name).map(|resolver| resolver.as_ref())
+    }
+
+    pub fn searchable(&self) -> impl Iterator<Item = &dyn SourceResolver> {
+        self.resolvers.iter().filter(|resolver| resolver.searchable()).map(|resolver| resolver.as_ref())
+    }
+
+    pub fn is_url(&self, url: &str) -> bool {
+        self.resolvers.iter().any(|resolver| resolver.matches(url))
+    }
+
+    pub async fn resolve(&self, url: &str, track_id: u64) -> ParsedDataType {
+        let url_start = get_url_timestamp(url);
+        let key = get_url_key(url);
+        if let Some(mut track) = self.get_cached(&key, track_id) {
+            track.start = url_start;
+            return ParsedDataType::Track(track);
+        }
+        for resolver in self.resolvers.iter().filter(|resolver| resolver.matches(url)) {
+            let mut result = resolver.resolve(url, track_id).await;
+            match &mut result {
+                ParsedDataType::Track(track) => {
+                    self.store_cached(&[key, get_url_key(&track.webpage_url)], track);
+                    track.start = url_start;
+                    return result
+                },
+                ParsedDataType::YtDlPlaylist((_, track)) | ParsedDataType::VkPlaylist((_, track)) | ParsedDataType::Podcast((_, track)) => {
+                    track.start = url_start;
+                    return result
+                },
+                ParsedDataType::None => {}
+            }
+        }
+        ParsedDataType::None
+    }
+
+    pub async fn resolve_track(&self, url: &str, track_id: u64) -> Option<Track> {
+        match self.resolve(url, track_id).await {
+            ParsedDataType::Track(track) | ParsedDataType::YtDlPlaylist((_, track)) | ParsedDataType::VkPlaylist((_, track)) | ParsedDataType::Podcast((_, track)) => Some(track),
+            ParsedDataType::None => None
+        }
+    }
+
+    pub async fn search(&self, query: &str, source: Option<&str>, track_id: u64) -> Option<Track> {
+        let resolver = source.and_then(|source| self.get(source))
+            .filter(|resolver| resolver.searchable())
+            .or(self.get(DEFAULT_SEARCH_SOURCE))?;
+        let key = get_search_key(resolver.name(), query);
+        if let Some(track) = self.get_cached(&key, track_id) {
+            return Some(track);
+        }
+        let track = resolver.search(query, track_id).await?;
+        self.store_cached(&[key, get_url_key(&track.webpage_url)], &track);
+        Some(track)
+    }
+
+    pub async fn search_many(&self, query: &str, source: Option<&str>, count: usize) -> Vec<Track> {
+        let resolver = source.and_then(|source| self.get(source))
+            .filter(|resolver| resolver.searchable())
+            .or(self.get(DEFAULT_SEARCH_SOURCE));
+        match resolver {
+            Some(resolver) => resolver.search_many(query, count).await,
+            None => Vec::new()
+        }
+    }
+
+    pub async fn reload(&self, track: &Track) -> Option<Track> {
+        for resolver in self.resolvers.iter().filter(|resolver| resolver.handles(track)) {
+            match resolver.resolve(&track.webpage_url, track.id).await {
+                ParsedDataType::Track(track) | ParsedDataType::YtDlPlaylist((_, track)) | ParsedDataType::VkPlaylist((_, track)) | ParsedDataType::Podcast((_, track)) => return Some(track),
+                ParsedDataType::None => {}
+            }
+        }
+        None
+    }
+
+    pub async fn refresh(&self, track: &Track) -> Option<String> {
+        for resolver in self.resolvers.iter().filter(|resolver| resolver.handles(track)) {
+            if let Some(url) = resolver.refresh(track).await {
+                return Some(url);
+            }
+        }
+        None
+    }
+}
+
+static SOURCE_REGISTRY: OnceLock<SourceRegistry> = OnceLock::new();
+
+pub fn init_source_registry(pool: Pool<ConnectionManager<SqliteConnection>>) {
+    let mut registry = SourceRegistry::new();
+    registry.set_cache(pool.clone());
+    registry.register(Box::new(LibraryResolver { pool }));
+    let _ = SOURCE_REGISTRY.set(registry);
+}
+
+pub fn source_registry() -> &'static SourceRegistry {
+    SOURCE_REGISTRY.get_or_init(SourceRegistry::new)
+}
+
+pub fn resolve_tracks_ordered(urls: Vec<String>) -> impl Stream<Item = (String, Option<Track>)> {
+    let workers = env::var("RESOLVE_WORKERS").ok()
+        .and_then(|workers| workers.parse::<usize>().ok())
+        .unwrap_or(DEFAULT_RESOLVE_WORKERS)
+        .max(1);
+    let item_timeout = Duration::from_secs(env::var("RESOLVE_TIMEOUT_SECS").ok()
+        .and_then(|secs| secs.parse::<u64>().ok())
+        .unwrap_or(DEFAULT_RESOLVE_TIMEOUT_SECS));
+    stream::iter(urls)
+        .map(move |url| async move {
+            let track = timeout(item_timeout, source_registry().resolve_track(&url, 0)).await.ok().flatten();
+            (url, track)
+        })
+        .buffered(workers)
+}
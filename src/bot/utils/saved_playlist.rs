@@ -0,0 +1,136 @@
+use diesel::{BoolExpressionMethods, Connection, ExpressionMethods, OptionalExtension, QueryDsl, QueryResult, RunQueryDsl, EscapeExpressionMethods, TextExpressionMethods, SelectableHelper, SqliteConnection};
+
+use super::track::Track;
+use crate::models::{NewPlaylist, NewPlaylistTrack, PlaylistDB};
+
+pub const PLAYLIST_TRACKS_LIMIT: usize = 500;
+
+fn get_playlist(conn: &mut SqliteConnection, owner: u64, playlist_name: &str) -> QueryResult<Option<PlaylistDB>> {
+    use crate::schema::playlists::dsl::*;
+    playlists
+        .filter(owner_id.eq(owner as i64).and(name.eq(playlist_name)))
+        .select(PlaylistDB::as_select())
+        .first(conn)
+        .optional()
+}
+
+fn get_or_create_playlist(conn: &mut SqliteConnection, owner: u64, playlist_name: &str) -> QueryResult<PlaylistDB> {
+    match get_playlist(conn, owner, playlist_name)? {
+        Some(playlist) => Ok(playlist),
+        None => {
+            use crate::schema::playlists::dsl::*;
+            diesel::insert_into(playlists)
+                .values(NewPlaylist { owner_id: owner as i64, name: playlist_name })
+                .returning(PlaylistDB::as_returning())
+                .get_result(conn)
+        }
+    }
+}
+
+fn insert_tracks(conn: &mut SqliteConnection, playlist: i32, first_position: i32, tracks: &[Track]) -> QueryResult<usize> {
+    use crate::schema::playlist_tracks::dsl::*;
+    let new_tracks: Vec<NewPlaylistTrack> = tracks.iter()
+        .enumerate()
+        .filter_map(|(index, saved_track)| {
+            let mut saved_track = saved_track.clone();
+            saved_track.requester = None;
+            Some(NewPlaylistTrack {
+                playlist_id: playlist,
+                position: first_position + index as i32,
+                webpage_url: saved_track.webpage_url.clone(),
+                title: saved_track.title.clone(),
+                duration: saved_track.duration,
+                track: serde_json::to_string(&saved_track).ok()?,
+            })
+        })
+        .collect();
+    diesel::insert_into(playlist_tracks)
+        .values(&new_tracks)
+        .execute(conn)
+}
+
+pub fn save_playlist(conn: &mut SqliteConnection, owner: u64, playlist_name: &str, tracks: &[Track]) -> QueryResult<usize> {
+    conn.transaction(|conn| {
+        let playlist = get_or_create_playlist(conn, owner, playlist_name)?;
+        {
+            use crate::schema::playlist_tracks::dsl::*;
+            diesel::delete(playlist_tracks.filter(playlist_id.eq(playlist.id))).execute(conn)?;
+        }
+        insert_tracks(conn, playlist.id, 0, &tracks[..tracks.len().min(PLAYLIST_TRACKS_LIMIT)])
+    })
+}
+
+pub fn add_to_playlist(conn: &mut SqliteConnection, owner: u64, playlist_name: &str, tracks: &[Track]) -> QueryResult<usize> {
+    conn.transaction(|conn| {
+        let playlist = get_or_create_playlist(conn, owner, playlist_name)?;
+        use crate::schema::playlist_tracks::dsl::*;
+        let count: i64 = playlist_tracks.filter(playlist_id.eq(playlist.id)).count().get_result(conn)?;
+        let free = PLAYLIST_TRACKS_LIMIT.saturating_sub(count as usize);
+        let last_position: Option<i32> = playlist_tracks
+            .filter(playlist_id.eq(playlist.id))
+            .select(diesel::dsl::max(position))
+            .first(conn)?;
+        insert_tracks(conn, playlist.id, last_position.map(|last| last + 1).unwrap_or(0), &tracks[..tracks.len().min(free)])
+    })
+}
+
+pub fn load_playlist(conn: &mut SqliteConnection, owner: u64, playlist_name: &str) -> QueryResult<Option<Vec<Track>>> {
+    let playlist = match get_playlist(conn, owner, playlist_name)? {
+        Some(playlist) => playlist,
+        None => return Ok(None)
+    };
+    use crate::schema::playlist_tracks::dsl::*;
+    let saved: Vec<String> = playlist_tracks
+        .filter(playlist_id.eq(playlist.id))
+        .order(position.asc())
+        .select(track)
+        .load(conn)?;
+    Ok(Some(saved.iter().filter_map(|saved_track| serde_json::from_str::<Track>(saved_track).ok()).collect()))
+}
+
+pub fn list_playlists(conn: &mut SqliteConnection, owner: u64) -> QueryResult<Vec<(PlaylistDB, i64)>> {
+    let saved = {
+        use crate::schema::playlists::dsl::*;
+        playlists
+            .filter(owner_id.eq(owner as i64))
+            .order(name.asc())
+            .select(PlaylistDB::as_select())
+            .load(conn)?
+    };
+    use crate::schema::playlist_tracks::dsl::*;
+    saved.into_iter()
+        .map(|playlist| {
+            let count: i64 = playlist_tracks.filter(playlist_id.eq(playlist.id)).count().get_result(conn)?;
+            Ok((playlist, count))
+        })
+        .collect()
+}
+
+pub fn search_playlist_tracks(conn: &mut SqliteConnection, owner: u64, query: &str, limit: i64) -> QueryResult<Vec<(String, Option<String>)>> {
+    use crate::schema::{playlists, playlist_tracks};
+    let pattern = format!("%{}%", query.replace('\\', "\\\\").replace('%', "\\%").replace('_', "\\_"));
+    playlist_tracks::table
+        .inner_join(playlists::table)
+        .filter(playlists::owner_id.eq(owner as i64))
+        .filter(playlist_tracks::title.like(&pattern).escape('\\').or(playlist_tracks::webpage_url.like(&pattern).escape('\\')))
+        .select((playlist_tracks::webpage_url, playlist_tracks::title))
+        .distinct()
+        .limit(limit)
+        .load(conn)
+}
+
+pub fn delete_playlist(conn: &mut SqliteConnection, owner: u64, playlist_name: &str) -> QueryResult<bool> {
+    conn.transaction(|conn| {
+        let playlist = match get_playlist(conn, owner, playlist_name)? {
+            Some(playlist) => playlist,
+            None => return Ok(false)
+        };
+        {
+            use crate::schema::playlist_tracks::dsl::*;
+            diesel::delete(playlist_tracks.filter(playlist_id.eq(playlist.id))).execute(conn)?;
+        }
+        use crate::schema::playlists::dsl::*;
+        diesel::delete(playlists.filter(id.eq(playlist.id))).execute(conn)?;
+        Ok(true)
+    })
+}
@@ -0,0 +1,138 @@
+use std::time::Duration;
+
+use chrono::{DateTime, NaiveDateTime, Utc};
+use diesel::{dsl::count_star, BoolExpressionMethods, ExpressionMethods, QueryDsl, QueryResult, RunQueryDsl, SqliteConnection};
+use serde::Serialize;
+
+use super::track::Track;
+use crate::models::NewPlay;
+
+#[derive(Debug, Clone)]
+pub struct PlayStats {
+    pub track: Track,
+    pub started_at: DateTime<Utc>,
+    pub listened: Duration,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub enum StatsPeriod {
+    Day,
+    Week,
+    Month,
+    All,
+}
+
+impl StatsPeriod {
+    pub fn from_str(period: &str) -> Self {
+        match period {
+            "day" => Self::Day,
+            "month" => Self::Month,
+            "all" => Self::All,
+            _ => Self::Week
+        }
+    }
+
+    pub fn since(&self) -> NaiveDateTime {
+        let since = match self {
+            Self::Day => Utc::now() - chrono::Duration::days(1),
+            Self::Week => Utc::now() - chrono::Duration::weeks(1),
+            Self::Month => Utc::now() - chrono::Duration::days(30),
+            Self::All => DateTime::<Utc>::UNIX_EPOCH
+        };
+        since.naive_utc()
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct TrackStats {
+    pub webpage_url: String,
+    pub title: Option<String>,
+    pub count: i64,
+    pub listened: f64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct RequesterStats {
+    pub requester: u64,
+    pub count: i64,
+    pub listened: f64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct TotalStats {
+    pub plays: i64,
+    pub listened: f64,
+}
+
+pub fn record_play(conn: &mut SqliteConnection, guild: u64, play: &PlayStats, was_skipped: bool) -> QueryResult<usize> {
+    use crate::schema::plays::dsl::*;
+    diesel::insert_into(plays)
+        .values(NewPlay {
+            guild_id: guild as i64,
+            webpage_url: play.track.webpage_url.clone(),
+            title: play.track.title.clone(),
+            requester: play.track.requester.map(|user| user as i64),
+            started_at: play.started_at.naive_utc(),
+            listened: play.listened.as_secs_f64(),
+            skipped: was_skipped,
+        })
+        .execute(conn)
+}
+
+pub fn get_top_tracks(conn: &mut SqliteConnection, guild: u64, period: StatsPeriod, limit: i64) -> QueryResult<Vec<TrackStats>> {
+    use crate::schema::plays::dsl::*;
+    let rows: Vec<(String, Option<String>, i64, Option<f64>)> = plays
+        .filter(guild_id.eq(guild as i64).and(started_at.ge(period.since())))
+        .group_by(webpage_url)
+        .select((webpage_url, diesel::dsl::max(title), count_star(), diesel::dsl::sum(listened)))
+        .order(count_star().desc())
+        .limit(limit)
+        .load(conn)?;
+    Ok(rows.into_iter().map(|(url, track_title, count, total)| TrackStats { webpage_url: url, title: track_title, count, listened: total.unwrap_or(0.0) }).collect())
+}
+
+pub fn get_recent_tracks(conn: &mut SqliteConnection, guild: u64, limit: i64) -> QueryResult<Vec<(String, Option<String>)>> {
+    use crate::schema::plays::dsl::*;
+    plays
+        .filter(guild_id.eq(guild as i64))
+        .group_by(webpage_url)
+        .select((webpage_url, diesel::dsl::max(title)))
+        .order(diesel::dsl::max(started_at).desc())
+        .limit(limit)
+        .load(conn)
+}
+
+pub fn get_most_skipped(conn: &mut SqliteConnection, guild: u64, period: StatsPeriod, limit: i64) -> QueryResult<Vec<TrackStats>> {
+    use crate::schema::plays::dsl::*;
+    let rows: Vec<(String, Option<String>, i64, Option<f64>)> = plays
+        .filter(guild_id.eq(guild as i64).and(started_at.ge(period.since())).and(skipped.eq(true)))
+        .group_by(webpage_url)
+        .select((webpage_url, diesel::dsl::max(title), count_star(), diesel::dsl::sum(listened)))
+        .order(count_star().desc())
+        .limit(limit)
+        .load(conn)?;
+    Ok(rows.into_iter().map(|(url, track_title, count, total)| TrackStats { webpage_url: url, title: track_title, count, listened: total.unwrap_or(0.0) }).collect())
+}
+
+pub fn get_top_requesters(conn: &mut SqliteConnection, guild: u64, period: StatsPeriod, limit: i64) -> QueryResult<Vec<RequesterStats>> {
+    use crate::schema::plays::dsl::*;
+    let rows: Vec<(Option<i64>, i64, Option<f64>)> = plays
+        .filter(guild_id.eq(guild as i64).and(started_at.ge(period.since())).and(requester.is_not_null()))
+        .group_by(requester)
+        .select((requester, count_star(), diesel::dsl::sum(listened)))
+        .order(diesel::dsl::sum(listened).desc())
+        .limit(limit)
+        .load(conn)?;
+    Ok(rows.into_iter()
+        .filter_map(|(user, count, total)| Some(RequesterStats { requester: user? as u64, count, listened: total.unwrap_or(0.0) }))
+        .collect())
+}
+
+pub fn get_total(conn: &mut SqliteConnection, guild: u64, period: StatsPeriod) -> QueryResult<TotalStats> {
+    use crate::schema::plays::dsl::*;
+    let (count, total): (i64, Option<f64>) = plays
+        .filter(guild_id.eq(guild as i64).and(started_at.ge(period.since())))
+        .select((count_star(), diesel::dsl::sum(listened)))
+        .first(conn)?;
+    Ok(TotalStats { plays: count, listened: total.unwrap_or(0.0) })
+}
@@ -1,17 +1,22 @@
 use std::process::{Command, Stdio, Child};
 use std::io::Error;
 use std::sync::Arc;
+use std::time::Duration;
 
 use chrono::{DateTime, Utc};
 use serde::{Serialize, Deserialize};
 use serenity::builder::CreateEmbed;
 use serenity::client::Context;
+use tokio::time::timeout;
 
+use crate::bot::utils::filters::FilterGraph;
 use crate::bot::utils::player::PlayerData;
+use crate::bot::utils::parser::get_time_str;
+use crate::bot::utils::resolver::source_registry;
 
-use super::player::PlayerDataType;
+use super::player::{PlayerDataType, PlayerSettings};
 
-#[derive(Debug,Clone,Serialize)]
+#[derive(Debug,Clone,Serialize,Deserialize)]
 pub struct Track {
     pub id: u64,
     pub title: Option<String>,
@@ -24,9 +29,16 @@ pub struct Track {
     pub chapters: Vec<Chapter>,
     pub webpage_url: String,
     pub duration: Option<f64>,
+    #[serde(default)]
+    pub is_live: bool,
+    #[serde(default)]
+    pub stream_title: Option<String>,
     pub parse_time: DateTime<Utc>,
     pub parser_type: ParserType,
     pub edit_date: Option<DateTime<Utc>>,
+    pub start: Option<f64>,
+    pub end: Option<f64>,
+    pub requester: Option<u64>,
 }
 
 impl Track {
@@ -48,9 +60,14 @@ impl Track {
             chapters: Vec::new(),
             webpage_url: track.webpage_url,
             duration: Some(track.duration),
+            is_live: false,
+            stream_title: None,
             parse_time: Utc::now(),
             parser_type: ParserType::Vk,
             edit_date: None,
+            start: None,
+            end: None,
+            requester: None,
         }
     }
 
@@ -67,9 +84,43 @@ impl Track {
             chapters: track.chapters,
             webpage_url: track.webpage_url,
             duration: track.duration,
+            is_live: track.is_live,
+            stream_title: None,
             parse_time: track.parse_time,
             parser_type: track.parser_type,
-            edit_date: track.edit_date
+            edit_date: track.edit_date,
+            start: track.start,
+            end: track.end,
+            requester: None
+        }
+    }
+
+    pub fn from_flat(entry: FlatEntry, id: u64) -> Self {
+        Track {
+            id,
+            title: entry.title,
+            description: None,
+            thumbnail: None,
+            author: Author {
+                name: entry.author,
+                url: None,
+                thumbnail: None,
+                verified: false,
+            },
+            url: String::new(),
+            views: None,
+            likes: None,
+            chapters: Vec::new(),
+            webpage_url: entry.url,
+            duration: entry.duration,
+            is_live: false,
+            stream_title: None,
+            parse_time: Utc::now(),
+            parser_type: ParserType::Unresolved,
+            edit_date: None,
+            start: None,
+            end: None,
+            requester: None,
         }
     }
 }
@@ -86,9 +137,15 @@ pub struct WebTrack {
     pub chapters: Vec<Chapter>,
     pub webpage_url: String,
     pub duration: Option<f64>,
+    #[serde(default)]
+    pub is_live: bool,
     pub parse_time: DateTime<Utc>,
     pub parser_type: ParserType,
     pub edit_date: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub start: Option<f64>,
+    #[serde(default)]
+    pub end: Option<f64>,
 }
 
 #[derive(Debug,Clone,Serialize,Deserialize)]
@@ -99,7 +156,67 @@ pub struct Author {
     pub verified: bool,
 }
 
+const STREAM_URL_LIFETIME_HOURS: i64 = 3;
+const RESOLVE_TIMEOUT_SECS: u64 = 30;
+
 impl Track {
+    pub fn is_resolved(&self) -> bool {
+        self.parser_type != ParserType::Unresolved
+    }
+
+    pub async fn resolve(self) -> Option<Track> {
+        if self.is_resolved() {
+            return Some(self);
+        }
+        let resolved = timeout(Duration::from_secs(RESOLVE_TIMEOUT_SECS), source_registry().resolve_track(&self.webpage_url, self.id)).await.ok().flatten();
+        resolved.map(|mut track| {
+            track.id = self.id;
+            track.start = self.start.or(track.start);
+            track.end = self.end;
+            track.requester = self.requester;
+            track
+        })
+    }
+
+    pub async fn prepare(self) -> Option<Track> {
+        let mut track = self.resolve().await?;
+        track.refresh_stream_url().await;
+        Some(track).filter(|track| !track.url.is_empty())
+    }
+
+    pub async fn check_live(&self) -> Option<Track> {
+        if !self.is_live {
+            return None;
+        }
+        let resolved = timeout(Duration::from_secs(RESOLVE_TIMEOUT_SECS), source_registry().reload(self)).await.ok().flatten()?;
+        match resolved.is_live {
+            true => Some(Track {
+                requester: self.requester,
+                stream_title: self.stream_title.clone(),
+                ..resolved
+            }),
+            false => None
+        }
+    }
+
+    fn is_stream_url_expired(&self) -> bool {
+        Utc::now() - self.parse_time >= chrono::Duration::hours(STREAM_URL_LIFETIME_HOURS)
+    }
+
+    pub fn is_prepared(&self) -> bool {
+        self.is_resolved() && !self.url.is_empty() && !self.is_stream_url_expired()
+    }
+
+    pub async fn refresh_stream_url(&mut self) {
+        if !self.url.is_empty() && !self.is_stream_url_expired() {
+            return;
+        }
+        if let Some(url) = source_registry().refresh(self).await {
+            self.url = url;
+        }
+        self.parse_time = Utc::now();
+    }
+
     pub async fn get_child(&self, ctx: &Context, guild_id: &u64, start: f64) -> Result<Child, Error> {
         let map = {
             let data_read = ctx.data.read().await;
@@ -108,35 +225,33 @@ impl Track {
         let map = map.read().await;
         let player = map.get(guild_id).unwrap();
         let settings = player.settings.read().await;
+        self.spawn_ffmpeg(&settings, &self.url, start)
+    }
+
+    fn spawn_ffmpeg(&self, settings: &PlayerSettings, url: &str, start: f64) -> Result<Child, Error> {
         let command = &mut Command::new("ffmpeg");
-        command.args([
-            "-reconnect", "1", "-reconnect_streamed", "1",
-            "-reconnect_delay_max", "5", "-err_detect", "ignore_err",
-            "-vn", "-sn", "-user_agent", "Mozilla/5.0 (Windows NT 10.0; Win64; x64; rv:106.0) Gecko/20100101 Firefox/106.0"
-            ]);
-        if self.url.ends_with(".m3u8") {
+        if !matches!(self.parser_type, ParserType::Local) {
+            command.args([
+                "-reconnect", "1", "-reconnect_streamed", "1",
+                "-reconnect_delay_max", "5",
+                "-user_agent", "Mozilla/5.0 (Windows NT 10.0; Win64; x64; rv:106.0) Gecko/20100101 Firefox/106.0"
+                ]);
+        }
+        command.args(["-err_detect", "ignore_err", "-vn", "-sn"]);
+        if url.ends_with(".m3u8") {
             command.args(["-http_persistent", "false"]);
         };
-        if start != 0.0 {
-            command.args(["-ss", &format!("{}", start)]);
-        };
-        command.args(["-i", self.url.as_str()])
-            .args(["-af", format!(
-                "volume={},atempo{},bass=g={}:f=110:w=0.3,equalizer@h32=f=32:t=h:w=17{},equalizer@h64=f=64:t=h:w=30{},equalizer@h125=f=125:t=h:w=62{},equalizer@h250=f=250:t=h:w=125{},equalizer@h500=f=500:t=h:w=250{},equalizer@h1k=f=1k:t=h:w=500{},equalizer@h2k=f=2k:t=h:w=1000{},equalizer@h4k=f=4k:t=h:w=2000{},equalizer@h8k=f=8k:t=h:w=4000{},equalizer@h16k=f=16k:t=h:w=8000{}",
-                settings.volume*0.2,
-                if settings.speed != 1.0 {format!("={}", settings.speed)} else {"".to_string()},
-                if settings.bass_enabled {format!("{}", settings.bass_gain)} else {"0".to_string()},
-                if settings.equalizer.f_32 != 0.0 {format!(":g={}", settings.equalizer.f_32)} else {"".to_string()},
-                if settings.equalizer.f_64 != 0.0 {format!(":g={}", settings.equalizer.f_64)} else {"".to_string()},
-                if settings.equalizer.f_125 != 0.0 {format!(":g={}", settings.equalizer.f_125)} else {"".to_string()},
-                if settings.equalizer.f_250 != 0.0 {format!(":g={}", settings.equalizer.f_250)} else {"".to_string()},
-                if settings.equalizer.f_500 != 0.0 {format!(":g={}", settings.equalizer.f_500)} else {"".to_string()},
-                if settings.equalizer.f_1k != 0.0 {format!(":g={}", settings.equalizer.f_1k)} else {"".to_string()},
-                if settings.equalizer.f_2k != 0.0 {format!(":g={}", settings.equalizer.f_2k)} else {"".to_string()},
-                if settings.equalizer.f_4k != 0.0 {format!(":g={}", settings.equalizer.f_4k)} else {"".to_string()},
-                if settings.equalizer.f_8k != 0.0 {format!(":g={}", settings.equalizer.f_8k)} else {"".to_string()},
-                if settings.equalizer.f_16k != 0.0 {format!(":g={}", settings.equalizer.f_16k)} else {"".to_string()},
-            ).as_str()])
+        if !self.is_live {
+            let start = start.max(self.start.unwrap_or(0.0));
+            if start != 0.0 {
+                command.args(["-ss", &format!("{}", start)]);
+            };
+            if let Some(end) = self.end {
+                command.args(["-to", &format!("{}", end)]);
+            };
+        }
+        command.args(["-i", url])
+            .args(["-af", FilterGraph::new(settings).render().as_str()])
             .args(["-f", "wav", "-loglevel","info", "pipe:1"]);
         command.stdin(Stdio::piped())
             .stdout(Stdio::piped())
@@ -147,39 +262,7 @@ impl Track {
         let map = player_data.read().await;
         let player = map.get(guild_id).unwrap();
         let settings = player.settings.read().await;
-        let command = &mut Command::new("ffmpeg");
-        command.args([
-            "-reconnect", "1", "-reconnect_streamed", "1",
-            "-reconnect_delay_max", "5", "-err_detect", "ignore_err",
-            "-vn", "-sn", "-user_agent", "Mozilla/5.0 (Windows NT 10.0; Win64; x64; rv:106.0) Gecko/20100101 Firefox/106.0"
-            ]);
-        if self.url.ends_with(".m3u8") {
-            command.args(["-http_persistent", "false"]);
-        };
-        if start != 0.0 {
-            command.args(["-ss", &format!("{}", start)]);
-        };
-        command.args(["-i", self.url.as_str()])
-            .args(["-af", format!(
-                "volume={},atempo{},bass=g={}:f=110:w=0.3,equalizer@h32=f=32:t=h:w=17{},equalizer@h64=f=64:t=h:w=30{},equalizer@h125=f=125:t=h:w=62{},equalizer@h250=f=250:t=h:w=125{},equalizer@h500=f=500:t=h:w=250{},equalizer@h1k=f=1k:t=h:w=500{},equalizer@h2k=f=2k:t=h:w=1000{},equalizer@h4k=f=4k:t=h:w=2000{},equalizer@h8k=f=8k:t=h:w=4000{},equalizer@h16k=f=16k:t=h:w=8000{}",
-                settings.volume*0.2,
-                if settings.speed != 1.0 {format!("={}", settings.speed)} else {"".to_string()},
-                if settings.bass_enabled {format!("{}", settings.bass_gain)} else {"0".to_string()},
-                if settings.equalizer.f_32 != 0.0 {format!(":g={}", settings.equalizer.f_32)} else {"".to_string()},
-                if settings.equalizer.f_64 != 0.0 {format!(":g={}", settings.equalizer.f_64)} else {"".to_string()},
-                if settings.equalizer.f_125 != 0.0 {format!(":g={}", settings.equalizer.f_125)} else {"".to_string()},
-                if settings.equalizer.f_250 != 0.0 {format!(":g={}", settings.equalizer.f_250)} else {"".to_string()},
-                if settings.equalizer.f_500 != 0.0 {format!(":g={}", settings.equalizer.f_500)} else {"".to_string()},
-                if settings.equalizer.f_1k != 0.0 {format!(":g={}", settings.equalizer.f_1k)} else {"".to_string()},
-                if settings.equalizer.f_2k != 0.0 {format!(":g={}", settings.equalizer.f_2k)} else {"".to_string()},
-                if settings.equalizer.f_4k != 0.0 {format!(":g={}", settings.equalizer.f_4k)} else {"".to_string()},
-                if settings.equalizer.f_8k != 0.0 {format!(":g={}", settings.equalizer.f_8k)} else {"".to_string()},
-                if settings.equalizer.f_16k != 0.0 {format!(":g={}", settings.equalizer.f_16k)} else {"".to_string()},
-            ).as_str()])
-            .args(["-f", "wav", "-loglevel","info", "pipe:1"]);
-        command.stdin(Stdio::piped())
-            .stdout(Stdio::piped())
-            .spawn()
+        self.spawn_ffmpeg(&settings, &self.url, start)
     }
 
     pub fn get_embed(&self, local: &str) -> CreateEmbed {
@@ -201,7 +284,7 @@ impl Track {
             Some(url) => format!("[{}]({})", author, url),
             None => author
         };
-        let title = if self.webpage_url.is_empty() {
+        let title = if !self.webpage_url.starts_with("http") {
             title
         } else {
             format!("[{}]({})", title, self.webpage_url)
@@ -225,6 +308,29 @@ impl Track {
         if let Some(date) = self.edit_date {
             embed = embed.timestamp(date);
         }
+        if self.is_live {
+            embed = embed.field(match local {
+                "ru" => "Трансляция",
+                _ => "Stream",
+            }, "🔴 LIVE", true)
+        } else if self.start.is_some() || self.end.is_some() {
+            embed = embed.field(match local {
+                "ru" => "Фрагмент",
+                _ => "Fragment",
+            }, format!("{} - {}",
+                get_time_str(self.start.unwrap_or(0.0)),
+                match self.end.or(self.duration) {
+                    Some(end) => get_time_str(end),
+                    None => "∞".to_string()
+                }
+            ), true)
+        }
+        if let Some(stream_title) = &self.stream_title {
+            embed = embed.field(match local {
+                "ru" => "В эфире",
+                _ => "On air",
+            }, stream_title, false)
+        }
         if let Some(likes) = self.likes {
             embed = embed.field(match local {
                 "ru" => "Лайков",
@@ -253,7 +359,19 @@ pub struct Chapter {
 pub enum ParserType {
     YtDl,
     Ffprobe,
-    Vk
+    Vk,
+    Local,
+    Radio,
+    Podcast,
+    Unresolved
+}
+
+#[derive(Debug,Clone,Serialize,Deserialize)]
+pub struct FlatEntry {
+    pub url: String,
+    pub title: Option<String>,
+    pub author: Option<String>,
+    pub duration: Option<f64>,
 }
 
 #[derive(Debug,Clone)]
@@ -265,12 +383,17 @@ pub struct YtDlTracksPlaylist {
     pub views: Option<i64>,
     pub webpage_url: String,
     pub edit_date: Option<DateTime<Utc>>,
-    pub tracks: Vec<String>
+    pub tracks: Vec<FlatEntry>
 }
 
 #[derive(Debug,Clone)]
 pub struct VkTracksPlaylist {
-    pub tracks: Vec<VkTrack>
+    pub title: Option<String>,
+    pub thumbnail: Option<String>,
+    pub author: Author,
+    pub webpage_url: String,
+    pub tracks: Vec<VkTrack>,
+    pub failed: usize
 }
 
 #[derive(Debug,Clone,Serialize,Deserialize)]
@@ -0,0 +1,98 @@
+use chrono::{DateTime, Duration, Utc};
+use diesel::{Connection, ExpressionMethods, OptionalExtension, QueryDsl, QueryResult, RunQueryDsl, SqliteConnection};
+
+use super::track::{ParserType, Track};
+use crate::models::NewTrackCache;
+
+pub const TRACK_CACHE_TTL_HOURS: i64 = 24;
+
+const TRACKING_PARAMS: [&str; 8] = ["si", "feature", "pp", "t", "start", "ref", "fbclid", "gclid"];
+
+pub fn normalize_url(url: &str) -> String {
+    let url = url.trim();
+    let url = url.split('#').next().unwrap_or(url);
+    let (scheme, rest) = match url.split_once("://") {
+        Some((scheme, rest)) => (scheme.to_lowercase(), rest),
+        None => return url.to_string()
+    };
+    let (location, query) = rest.split_once('?').unwrap_or((rest, ""));
+    let (host, path) = match location.find('/') {
+        Some(index) => (&location[..index], &location[index..]),
+        None => (location, "")
+    };
+    let host = host.to_lowercase();
+    let host = host.strip_prefix("www.").or(host.strip_prefix("m.")).unwrap_or(&host);
+    let path = path.trim_end_matches('/');
+    let mut params: Vec<&str> = query.split('&')
+        .filter(|param| !param.is_empty())
+        .filter(|param| {
+            let name = param.split('=').next().unwrap_or(param);
+            !name.starts_with("utm_") && !TRACKING_PARAMS.contains(&name)
+        })
+        .collect();
+    params.sort_unstable();
+    match params.is_empty() {
+        true => format!("{}://{}{}", scheme, host, path),
+        false => format!("{}://{}{}?{}", scheme, host, path, params.join("&"))
+    }
+}
+
+pub fn get_url_key(url: &str) -> String {
+    format!("url:{}", normalize_url(url))
+}
+
+pub fn get_search_key(source: &str, query: &str) -> String {
+    format!("search:{}:{}", source, query.trim().to_lowercase())
+}
+
+pub fn is_cacheable(track: &Track) -> bool {
+    matches!(track.parser_type, ParserType::YtDl | ParserType::Vk) && !track.is_live
+}
+
+pub fn get_cached_track(conn: &mut SqliteConnection, key: &str) -> QueryResult<Option<Track>> {
+    use crate::schema::track_cache::dsl;
+    let since = (Utc::now() - Duration::hours(TRACK_CACHE_TTL_HOURS)).naive_utc();
+    let data: Option<String> = dsl::track_cache
+        .filter(dsl::cache_key.eq(key))
+        .filter(dsl::cached_at.gt(since))
+        .select(dsl::track)
+        .first(conn)
+        .optional()?;
+    Ok(data.and_then(|data| serde_json::from_str::<Track>(&data).ok()).map(|mut track| {
+        track.url = String::new();
+        track.parse_time = DateTime::<Utc>::MIN_UTC;
+        track
+    }))
+}
+
+pub fn cache_track(conn: &mut SqliteConnection, keys: &[String], track: &Track) -> QueryResult<()> {
+    use crate::schema::track_cache::dsl;
+    let mut track = track.clone();
+    track.url = String::new();
+    track.parse_time = DateTime::<Utc>::MIN_UTC;
+    track.start = None;
+    track.end = None;
+    track.requester = None;
+    let data = match serde_json::to_string(&track) {
+        Ok(data) => data,
+        Err(_) => return Ok(())
+    };
+    let now = Utc::now().naive_utc();
+    conn.transaction(|conn| {
+        diesel::delete(dsl::track_cache.filter(dsl::cached_at.le(now - Duration::hours(TRACK_CACHE_TTL_HOURS)))).execute(conn)?;
+        for key in keys {
+            let new_cache = NewTrackCache {
+                cache_key: key,
+                track: &data,
+                cached_at: now,
+            };
+            diesel::insert_into(dsl::track_cache)
+                .values(&new_cache)
+                .on_conflict(dsl::cache_key)
+                .do_update()
+                .set(&new_cache)
+                .execute(conn)?;
+        }
+        Ok(())
+    })
+}
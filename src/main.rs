@@ -1,7 +1,7 @@
 use std::{collections::HashMap, env, sync::Arc};
 
 use actix_web::{middleware, web, App, HttpResponse, HttpServer, Responder};
-use bot::{commands, utils::player::{PlayerData, PlayerDataType, PlayerDataBase}, auto_complete};
+use bot::{commands, utils::{library::{get_library_paths, scan_library}, resolver::init_source_registry, player::{PlayerData, PlayerDataType, PlayerDataBase}, presence::{Presence, PresenceData}}, auto_complete, components};
 use diesel::{r2d2::ConnectionManager, SqliteConnection};
 use serenity::{
     all::Command, async_trait, client::Cache, model::{gateway::Ready, application::Interaction}, prelude::*
@@ -33,11 +33,38 @@ impl EventHandler for DiscordClient {
                 "speed" => commands::speed::run(ctx, command).await,
                 "password" => commands::password::run(ctx, command).await,
                 "bass" => commands::bass::run(ctx, command).await,
+                "nowplaying" => commands::nowplaying::run(ctx, command).await,
+                "announce" => commands::announce::run(ctx, command).await,
+                "sleep" => commands::sleep::run(ctx, command).await,
+                "playlist" => commands::playlist::run(ctx, command).await,
+                "like" => commands::like::run(ctx, command).await,
+                "likes" => commands::likes::run(ctx, command).await,
+                "stats" => commands::stats::run(ctx, command).await,
+                "queue" => commands::queue::run(ctx, command).await,
+                "library" => commands::library::run(ctx, command).await,
+                "import" => commands::import::run(ctx, command).await,
+                "search" => commands::search::run(ctx, command).await,
+                "radio" => commands::radio::run(ctx, command).await,
+                "podcast" => commands::podcast::run(ctx, command).await,
+                "filter" => commands::filter::run(ctx, command).await,
                 _ => {}
             },
             Interaction::Autocomplete(autocomplete) => match autocomplete.data.name.as_str() {
                 "skip" => auto_complete::skip::run(ctx, autocomplete).await,
                 "move" => auto_complete::r#move::run(ctx, autocomplete).await,
+                "playlist" => auto_complete::playlist::run(ctx, autocomplete).await,
+                "library" => auto_complete::library::run(ctx, autocomplete).await,
+                "play" => auto_complete::play::run(ctx, autocomplete).await,
+                "radio" => auto_complete::radio::run(ctx, autocomplete).await,
+                "podcast" => auto_complete::podcast::run(ctx, autocomplete).await,
+                _ => {}
+            }
+            Interaction::Component(component) => match component.data.custom_id.as_str() {
+                "player_like" => components::likes::like(ctx, component).await,
+                "search_select" => components::search::run(ctx, component).await,
+                "podcast_select" => components::podcast::run(ctx, component).await,
+                id if id.starts_with("player_") => components::player::run(ctx, component).await,
+                id if id.starts_with("likes_") => components::likes::run(ctx, component).await,
                 _ => {}
             }
             _ => {}
@@ -61,6 +88,20 @@ impl EventHandler for DiscordClient {
             commands::speed::register(),
             commands::password::register(),
             commands::bass::register(),
+            commands::nowplaying::register(),
+            commands::announce::register(),
+            commands::sleep::register(),
+            commands::playlist::register(),
+            commands::like::register(),
+            commands::likes::register(),
+            commands::stats::register(),
+            commands::queue::register(),
+            commands::library::register(),
+            commands::import::register(),
+            commands::search::register(),
+            commands::radio::register(),
+            commands::podcast::register(),
+            commands::filter::register(),
         ]).await.expect("commands load error");
     }
 }
@@ -86,6 +127,19 @@ async fn main() -> std::io::Result<()> {
         .build(db)
         .expect("Failed to build pool manager");
     let pool_clone = pool.clone();
+    init_source_registry(pool.clone());
+
+    let library_paths = get_library_paths();
+    if !library_paths.is_empty() {
+        let library_pool = pool.clone();
+        tokio::task::spawn_blocking(move || {
+            if let Ok(mut conn) = library_pool.get() {
+                if let Err(why) = scan_library(&mut conn, &library_paths) {
+                    log::error!("Failed to scan library: {:?}", why);
+                }
+            }
+        });
+    }
 
     let songbird = Songbird::serenity();
     songbird.set_config(Config::default().preallocated_tracks(2));
@@ -99,6 +153,12 @@ async fn main() -> std::io::Result<()> {
         .expect("Error creating client");
 
     let cache_clone = client.cache.clone();
+    let http_clone = client.http.clone();
+    let presence = Presence {
+        shard_manager: client.shard_manager.clone(),
+        enabled: env::var("PRESENCE_ENABLED").map(|value| value != "false" && value != "0").unwrap_or(true)
+    };
+    let presence_clone = presence.clone();
     
     // Запуск веб-сервера Actix
     let server = HttpServer::new(move || {
@@ -117,6 +177,8 @@ async fn main() -> std::io::Result<()> {
             .app_data(web::Data::new(pool.clone()))
             .app_data(web::Data::new(None::<Arc<Cache>>))
             .app_data(web::Data::new(cache_clone.clone()))
+            .app_data(web::Data::new(http_clone.clone()))
+            .app_data(web::Data::new(presence.clone()))
     })
     .bind("127.0.0.1:8081")?;
 
@@ -129,6 +191,7 @@ async fn main() -> std::io::Result<()> {
 
             data.insert::<PlayerData>(player_data_clone);
             data.insert::<PlayerDataBase>(pool_clone);
+            data.insert::<PresenceData>(presence_clone);
         }
 
         if let Err(why) = client.start_shards(2).await {
@@ -1,4 +1,5 @@
 use diesel::prelude::*;
+use serde::Serialize;
 
 #[derive(Insertable, Selectable, Queryable, Identifiable, Debug, Clone, Copy)]
 #[diesel(table_name = crate::schema::guild_settings)]
@@ -19,6 +20,9 @@ pub struct GuildSettingsDB {
     pub equalizer_4k: f64,
     pub equalizer_8k: f64,
     pub equalizer_16k: f64,
+    pub announce_channel: Option<i64>,
+    pub announce_delete_previous: bool,
+    pub effects: i32,
 }
 
 impl GuildSettingsDB {
@@ -40,6 +44,9 @@ impl GuildSettingsDB {
             equalizer_4k: 0.0,
             equalizer_8k: 0.0,
             equalizer_16k: 0.0,
+            announce_channel: None,
+            announce_delete_previous: false,
+            effects: 0,
         }
     }
 }
@@ -49,4 +56,170 @@ impl GuildSettingsDB {
 pub struct UpdateBass {
     pub bass_enabled: bool,
     pub bass_gain: f64,
-}
\ No newline at end of file
+}
+
+#[derive(AsChangeset)]
+#[diesel(table_name = crate::schema::guild_settings)]
+#[diesel(treat_none_as_null = true)]
+pub struct UpdateAnnounce {
+    pub announce_channel: Option<i64>,
+    pub announce_delete_previous: bool,
+}
+#[derive(Selectable, Queryable, Identifiable, Debug, Clone)]
+#[diesel(table_name = crate::schema::playlists)]
+pub struct PlaylistDB {
+    pub id: i32,
+    pub owner_id: i64,
+    pub name: String,
+    pub created_at: chrono::NaiveDateTime,
+}
+
+#[derive(Insertable)]
+#[diesel(table_name = crate::schema::playlists)]
+pub struct NewPlaylist<'a> {
+    pub owner_id: i64,
+    pub name: &'a str,
+}
+
+#[derive(Selectable, Queryable, Identifiable, Associations, Debug, Clone)]
+#[diesel(belongs_to(PlaylistDB, foreign_key = playlist_id))]
+#[diesel(table_name = crate::schema::playlist_tracks)]
+pub struct PlaylistTrackDB {
+    pub id: i32,
+    pub playlist_id: i32,
+    pub position: i32,
+    pub webpage_url: String,
+    pub title: Option<String>,
+    pub duration: Option<f64>,
+    pub track: String,
+}
+
+#[derive(Insertable)]
+#[diesel(table_name = crate::schema::playlist_tracks)]
+pub struct NewPlaylistTrack {
+    pub playlist_id: i32,
+    pub position: i32,
+    pub webpage_url: String,
+    pub title: Option<String>,
+    pub duration: Option<f64>,
+    pub track: String,
+}
+
+#[derive(Insertable, Selectable, Queryable, Debug, Clone)]
+#[diesel(table_name = crate::schema::users)]
+pub struct UserDB {
+    pub id: i64,
+    pub password: String,
+    pub is_admin: bool,
+}
+
+#[derive(Selectable, Queryable, Identifiable, Debug, Clone)]
+#[diesel(table_name = crate::schema::likes)]
+pub struct LikeDB {
+    pub id: i32,
+    pub user_id: i64,
+    pub webpage_url: String,
+    pub title: Option<String>,
+    pub duration: Option<f64>,
+    pub track: String,
+    pub created_at: chrono::NaiveDateTime,
+}
+
+#[derive(Insertable)]
+#[diesel(table_name = crate::schema::likes)]
+pub struct NewLike {
+    pub user_id: i64,
+    pub webpage_url: String,
+    pub title: Option<String>,
+    pub duration: Option<f64>,
+    pub track: String,
+}
+
+#[derive(Insertable)]
+#[diesel(table_name = crate::schema::plays)]
+pub struct NewPlay {
+    pub guild_id: i64,
+    pub webpage_url: String,
+    pub title: Option<String>,
+    pub requester: Option<i64>,
+    pub started_at: chrono::NaiveDateTime,
+    pub listened: f64,
+    pub skipped: bool,
+}
+
+#[derive(Selectable, Queryable, Identifiable, Debug, Clone)]
+#[diesel(table_name = crate::schema::library_tracks)]
+pub struct LibraryTrackDB {
+    pub id: i32,
+    pub path: String,
+    pub modified: i64,
+    pub size: i64,
+    pub title: Option<String>,
+    pub artist: Option<String>,
+    pub album: Option<String>,
+    pub duration: Option<f64>,
+    pub chapters: String,
+    #[diesel(select_expression = crate::schema::library_tracks::art.is_not_null())]
+    #[diesel(select_expression_type = diesel::dsl::IsNotNull<crate::schema::library_tracks::art>)]
+    pub has_art: bool,
+}
+
+#[derive(Insertable, AsChangeset)]
+#[diesel(table_name = crate::schema::library_tracks)]
+#[diesel(treat_none_as_null = true)]
+pub struct NewLibraryTrack {
+    pub path: String,
+    pub modified: i64,
+    pub size: i64,
+    pub title: Option<String>,
+    pub artist: Option<String>,
+    pub album: Option<String>,
+    pub duration: Option<f64>,
+    pub chapters: String,
+    pub art: Option<Vec<u8>>,
+    pub art_type: Option<String>,
+}
+
+#[derive(Insertable, AsChangeset)]
+#[diesel(table_name = crate::schema::track_cache)]
+pub struct NewTrackCache<'a> {
+    pub cache_key: &'a str,
+    pub track: &'a str,
+    pub cached_at: chrono::NaiveDateTime,
+}
+
+#[derive(Selectable, Queryable, Identifiable, Serialize, Debug, Clone)]
+#[diesel(table_name = crate::schema::radio_stations)]
+pub struct RadioStationDB {
+    pub id: i32,
+    pub guild_id: i64,
+    pub name: String,
+    pub url: String,
+    pub created_at: chrono::NaiveDateTime,
+}
+
+#[derive(Insertable)]
+#[diesel(table_name = crate::schema::radio_stations)]
+pub struct NewRadioStation<'a> {
+    pub guild_id: i64,
+    pub name: &'a str,
+    pub url: &'a str,
+}
+
+#[derive(Selectable, Queryable, Identifiable, Serialize, Debug, Clone)]
+#[diesel(table_name = crate::schema::podcast_subscriptions)]
+pub struct PodcastSubscriptionDB {
+    pub id: i32,
+    pub guild_id: i64,
+    pub title: String,
+    pub feed_url: String,
+    pub created_at: chrono::NaiveDateTime,
+}
+
+#[derive(Insertable)]
+#[diesel(table_name = crate::schema::podcast_subscriptions)]
+pub struct NewPodcastSubscription<'a> {
+    pub guild_id: i64,
+    pub title: &'a str,
+    pub feed_url: &'a str,
+}
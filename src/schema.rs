@@ -18,6 +18,99 @@ diesel::table! {
         equalizer_4k -> Double,
         equalizer_8k -> Double,
         equalizer_16k -> Double,
+        announce_channel -> Nullable<BigInt>,
+        announce_delete_previous -> Bool,
+        effects -> Integer,
+    }
+}
+
+diesel::table! {
+    library_tracks (id) {
+        id -> Integer,
+        path -> Text,
+        modified -> BigInt,
+        size -> BigInt,
+        title -> Nullable<Text>,
+        artist -> Nullable<Text>,
+        album -> Nullable<Text>,
+        duration -> Nullable<Double>,
+        chapters -> Text,
+        art -> Nullable<Binary>,
+        art_type -> Nullable<Text>,
+    }
+}
+
+diesel::table! {
+    likes (id) {
+        id -> Integer,
+        user_id -> BigInt,
+        webpage_url -> Text,
+        title -> Nullable<Text>,
+        duration -> Nullable<Double>,
+        track -> Text,
+        created_at -> Timestamp,
+    }
+}
+
+diesel::table! {
+    playlist_tracks (id) {
+        id -> Integer,
+        playlist_id -> Integer,
+        position -> Integer,
+        webpage_url -> Text,
+        title -> Nullable<Text>,
+        duration -> Nullable<Double>,
+        track -> Text,
+    }
+}
+
+diesel::table! {
+    playlists (id) {
+        id -> Integer,
+        owner_id -> BigInt,
+        name -> Text,
+        created_at -> Timestamp,
+    }
+}
+
+diesel::table! {
+    plays (id) {
+        id -> Integer,
+        guild_id -> BigInt,
+        webpage_url -> Text,
+        title -> Nullable<Text>,
+        requester -> Nullable<BigInt>,
+        started_at -> Timestamp,
+        listened -> Double,
+        skipped -> Bool,
+    }
+}
+
+diesel::table! {
+    podcast_subscriptions (id) {
+        id -> Integer,
+        guild_id -> BigInt,
+        title -> Text,
+        feed_url -> Text,
+        created_at -> Timestamp,
+    }
+}
+
+diesel::table! {
+    radio_stations (id) {
+        id -> Integer,
+        guild_id -> BigInt,
+        name -> Text,
+        url -> Text,
+        created_at -> Timestamp,
+    }
+}
+
+diesel::table! {
+    track_cache (cache_key) {
+        cache_key -> Text,
+        track -> Text,
+        cached_at -> Timestamp,
     }
 }
 
@@ -29,7 +122,17 @@ diesel::table! {
     }
 }
 
+diesel::joinable!(playlist_tracks -> playlists (playlist_id));
+
 diesel::allow_tables_to_appear_in_same_query!(
     guild_settings,
+    library_tracks,
+    likes,
+    playlist_tracks,
+    playlists,
+    plays,
+    podcast_subscriptions,
+    radio_stations,
+    track_cache,
     users,
 );